@@ -0,0 +1,175 @@
+//! Runs the economy forward without a window and prints what happened.
+//!
+//!     orb-sim [--save FILE] [--strategy idle|acolytes|generators|cheapest]
+//!             [--hours H] [--step SECS] [--clicks-per-sec N]
+//!             [--content FILE] [--balance FILE] [--advise] [--json]
+//!
+//! With `--advise` it instead ranks every open purchase by payback time and plans
+//! a greedy purchase schedule for the same number of hours.
+
+use orb_pondering_simulator::gameplay::advisor::{
+    self, AdvisorConfig, Appraisal, Price, ScheduledPurchase,
+};
+use orb_pondering_simulator::gameplay::balance::Balance;
+use orb_pondering_simulator::gameplay::content::GameContent;
+use orb_pondering_simulator::gameplay::economy::EconomyModel;
+use orb_pondering_simulator::gameplay::persistence::SaveData;
+use orb_pondering_simulator::gameplay::save_migration::parse_save;
+use orb_pondering_simulator::gameplay::simulation::{format_duration, simulate, SimConfig};
+use serde::Serialize;
+use std::fmt::Write;
+use std::process::ExitCode;
+
+struct Args {
+    save: Option<String>,
+    content: Option<String>,
+    balance: Option<String>,
+    advise: bool,
+    json: bool,
+    config: SimConfig,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+        save: None,
+        content: None,
+        balance: None,
+        advise: false,
+        json: false,
+        config: SimConfig::default(),
+    };
+    while let Some(flag) = args.next() {
+        if flag == "--json" || flag == "--advise" {
+            parsed.json |= flag == "--json";
+            parsed.advise |= flag == "--advise";
+            continue;
+        }
+        let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        let number = || {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite() && *n >= 0.0)
+                .ok_or_else(|| format!("{} needs a non-negative number", flag))
+        };
+        match flag.as_str() {
+            "--save" => parsed.save = Some(value),
+            "--content" => parsed.content = Some(value),
+            "--balance" => parsed.balance = Some(value),
+            "--strategy" => parsed.config.strategy = value.parse()?,
+            "--hours" => parsed.config.duration_secs = number()? * 3600.0,
+            "--step" => parsed.config.step_secs = number()?,
+            "--clicks-per-sec" => parsed.config.clicks_per_sec = number()?,
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+    if parsed.config.step_secs <= 0.0 {
+        return Err("--step must be positive".to_string());
+    }
+    Ok(parsed)
+}
+
+fn read(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("could not read {}: {}", path, e))
+}
+
+fn run(args: Args) -> Result<String, String> {
+    let mut content = match &args.content {
+        Some(path) => GameContent::parse(&read(path)?).map_err(|e| format!("{}: {}", path, e))?,
+        None => GameContent::default(),
+    };
+    if let Some(path) = &args.balance {
+        content.balance = Balance::parse(&read(path)?).map_err(|e| format!("{}: {}", path, e))?;
+    }
+    let save = match &args.save {
+        Some(path) => {
+            let json = String::from_utf8_lossy(&read(path)?).into_owned();
+            parse_save(&json).map_err(|e| format!("{}: {}", path, e))?
+        }
+        None => SaveData::new_game(),
+    };
+
+    if args.advise {
+        return advise(&save, &content, &args);
+    }
+    let report = simulate(&save, &content, &args.config);
+    if args.json {
+        serde_json::to_string_pretty(&report).map_err(|e| e.to_string())
+    } else {
+        Ok(report.to_string())
+    }
+}
+
+#[derive(Serialize)]
+struct Advice {
+    ranking: Vec<Appraisal>,
+    schedule: Vec<ScheduledPurchase>,
+}
+
+fn advise(save: &SaveData, content: &GameContent, args: &Args) -> Result<String, String> {
+    let model = EconomyModel::from_save(save, content);
+    let config = AdvisorConfig {
+        clicks_per_sec: args.config.clicks_per_sec,
+        ..AdvisorConfig::default()
+    };
+    let advice = Advice {
+        ranking: advisor::appraise(&model, &config),
+        schedule: advisor::greedy_schedule(&model, &config, args.config.duration_secs),
+    };
+    if args.json {
+        return serde_json::to_string_pretty(&advice).map_err(|e| e.to_string());
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "Ranking (best first):");
+    for appraisal in &advice.ranking {
+        let price = match appraisal.price {
+            Price::Afp(cost) => format!("{} AFP", cost.format_short()),
+            Price::Insight(cost) => format!("{} insight", cost),
+        };
+        let payback = match (appraisal.price, appraisal.payback_secs) {
+            (_, Some(secs)) => format!("pays back in {}", format_duration(secs)),
+            (Price::Insight(_), None) if appraisal.afp_gain_per_sec > 0.0 => {
+                format!("+{:.3} AFP/s", appraisal.afp_gain_per_sec)
+            }
+            _ => "no production gain".to_string(),
+        };
+        let _ = write!(
+            out,
+            "  {} {:<22} {:>12}  {}",
+            if appraisal.affordable { "*" } else { " " },
+            appraisal.action.name(content),
+            price,
+            payback
+        );
+        if let Some(better) = &appraisal.dominated_by {
+            let _ = write!(out, "  (dominated by {})", better.name(content));
+        }
+        out.push('\n');
+    }
+    let _ = writeln!(out, "  (* affordable now)");
+    let _ = writeln!(out, "Greedy schedule:");
+    for purchase in &advice.schedule {
+        let _ = writeln!(
+            out,
+            "  {:>12}  {:<22} {} AFP",
+            format_duration(purchase.at_secs),
+            purchase.action.name(content),
+            purchase.price.format_short()
+        );
+    }
+    Ok(out.trim_end().to_string())
+}
+
+fn main() -> ExitCode {
+    match parse_args(std::env::args().skip(1)).and_then(run) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("orb-sim: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::gameplay::economy::EconomySet;
 use bevy::prelude::*;
 
 pub mod daynight;
@@ -15,9 +16,11 @@ impl Plugin for EnvironmentPlugin {
                 Startup,
                 (tower::spawn_tower, lighting::setup_lighting, sky::spawn_sky),
             )
+            // The sky drives astral and dream production, so it advances with the economy
             .add_systems(
-                Update,
-                (daynight::update_cycle, lighting::update_ambient_from_cycle),
-            );
+                FixedUpdate,
+                daynight::update_cycle.before(EconomySet::Input),
+            )
+            .add_systems(Update, lighting::update_ambient_from_cycle);
     }
 }
@@ -1,3 +1,4 @@
+use crate::gameplay::state::GameState;
 use bevy::prelude::*;
 
 pub mod circle_material;
@@ -15,7 +16,7 @@ impl Plugin for FamiliarsPlugin {
                 (
                     familiar::spawn_familiar_timer,
                     familiar::familiar_movement,
-                    familiar::handle_pet_input,
+                    familiar::handle_pet_input.run_if(not(in_state(GameState::ProfileSelect))),
                     familiar::apply_familiar_effects,
                 ),
             );
@@ -1,8 +1,17 @@
-use super::acolytes::AcolyteState;
-use super::generators::{GeneratorState, GeneratorType};
+use super::acolytes::{AcolyteState, AcolyteSummoned};
+use super::bignum::BigNum;
+use super::challenges::ChallengeCompleted;
+use super::content::GameContent;
+use super::generators::{GeneratorPurchased, GeneratorState, GeneratorType};
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
+use super::moments::MomentClaimed;
+use super::pondering::DeepFocusActivated;
 use super::progression::ArcaneProgress;
-use super::transcendence::TranscendenceState;
+use super::shadow_thoughts::ShadowsDispelled;
+use super::shop::ShopItemBought;
+use super::transcendence::{Transcended, TranscendenceState};
 use super::wisdom::TruthGenerated;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -72,113 +81,36 @@ impl AchievementId {
         Self::DeepThinker,
         Self::TruthSeeker,
     ];
+}
 
-    pub fn name(&self) -> &'static str {
-        match self {
-            Self::FirstTruth => "First Insight",
-            Self::TenTruths => "Apprentice Ponderer",
-            Self::FiftyTruths => "Seasoned Thinker",
-            Self::HundredTruths => "Centurion of Wisdom",
-            Self::FiveHundredTruths => "Sage of the Tower",
-            Self::ThousandTruths => "Grand Philosopher",
-            Self::HundredAfp => "Arcane Dabbler",
-            Self::ThousandAfp => "Focus Adept",
-            Self::HundredKAfp => "Arcane Reservoir",
-            Self::MillionAfp => "Master of Focus",
-            Self::FirstTranscendence => "Beyond the Veil",
-            Self::FiveTranscendences => "Cycle Walker",
-            Self::TenTranscendences => "Eternal Return",
-            Self::FirstGenerator => "Automated Wisdom",
-            Self::AllGeneratorTypes => "Full Arsenal",
-            Self::FiftyCandles => "Candle Hoarder",
-            Self::HundredGenerators => "Factory of Thought",
-            Self::FirstAcolyte => "First Follower",
-            Self::TenAcolytes => "Small Gathering",
-            Self::TwentyFiveAcolytes => "Growing Order",
-            Self::SpeedPonderer => "Swift Awakening",
-            Self::DeepThinker => "Into the Deep",
-            Self::TruthSeeker => "Collector of Oddities",
-        }
-    }
-
-    pub fn description(&self) -> &'static str {
-        match self {
-            Self::FirstTruth => "Generate your first truth.",
-            Self::TenTruths => "Generate 10 truths across all runs.",
-            Self::FiftyTruths => "Generate 50 truths across all runs.",
-            Self::HundredTruths => "Generate 100 truths across all runs.",
-            Self::FiveHundredTruths => "Generate 500 truths across all runs.",
-            Self::ThousandTruths => "Generate 1,000 truths across all runs.",
-            Self::HundredAfp => "Accumulate 100 AFP in a single run.",
-            Self::ThousandAfp => "Accumulate 1,000 AFP in a single run.",
-            Self::HundredKAfp => "Accumulate 100,000 AFP in a single run.",
-            Self::MillionAfp => "Accumulate 1,000,000 AFP in a single run.",
-            Self::FirstTranscendence => "Transcend for the first time.",
-            Self::FiveTranscendences => "Transcend 5 times.",
-            Self::TenTranscendences => "Transcend 10 times.",
-            Self::FirstGenerator => "Purchase your first generator.",
-            Self::AllGeneratorTypes => "Own at least one of every generator type.",
-            Self::FiftyCandles => "Own 50 Enchanted Candles.",
-            Self::HundredGenerators => "Own 100 generators total.",
-            Self::FirstAcolyte => "Summon your first acolyte.",
-            Self::TenAcolytes => "Have 10 acolytes at once.",
-            Self::TwentyFiveAcolytes => "Have 25 acolytes at once.",
-            Self::SpeedPonderer => "Generate a truth within 30 seconds of starting a run.",
-            Self::DeepThinker => "Use Deep Focus 10 times in a single run.",
-            Self::TruthSeeker => "Generate 50 truths in a single run.",
-        }
-    }
-
-    pub fn hidden_description(&self) -> &'static str {
-        match self {
-            Self::SpeedPonderer => "Speed is its own reward. ???",
-            Self::DeepThinker => "Go deeper. ???",
-            Self::TruthSeeker => "One run to rule them all. ???",
-            _ => self.description(),
-        }
-    }
+/// An achievement's text and reward, from the content file
+#[derive(Debug, Clone, Deserialize)]
+pub struct AchievementDef {
+    pub id: AchievementId,
+    pub name: String,
+    pub description: String,
+    /// Shown instead of the name and description until unlocked; only hidden
+    /// achievements have one
+    #[serde(default)]
+    pub hidden_hint: Option<String>,
+    /// Permanent wisdom multiplier bonus when unlocked (additive)
+    pub reward: f32,
+}
 
+impl AchievementDef {
     pub fn is_hidden(&self) -> bool {
-        matches!(
-            self,
-            Self::SpeedPonderer | Self::DeepThinker | Self::TruthSeeker
-        )
+        self.hidden_hint.is_some()
     }
 
-    /// Permanent wisdom multiplier bonus when unlocked (additive)
-    pub fn reward_multiplier(&self) -> f32 {
-        match self {
-            Self::FirstTruth => 0.01,
-            Self::TenTruths => 0.02,
-            Self::FiftyTruths => 0.03,
-            Self::HundredTruths => 0.05,
-            Self::FiveHundredTruths => 0.08,
-            Self::ThousandTruths => 0.12,
-            Self::HundredAfp => 0.01,
-            Self::ThousandAfp => 0.02,
-            Self::HundredKAfp => 0.05,
-            Self::MillionAfp => 0.10,
-            Self::FirstTranscendence => 0.05,
-            Self::FiveTranscendences => 0.08,
-            Self::TenTranscendences => 0.12,
-            Self::FirstGenerator => 0.01,
-            Self::AllGeneratorTypes => 0.10,
-            Self::FiftyCandles => 0.03,
-            Self::HundredGenerators => 0.05,
-            Self::FirstAcolyte => 0.01,
-            Self::TenAcolytes => 0.03,
-            Self::TwentyFiveAcolytes => 0.05,
-            Self::SpeedPonderer => 0.05,
-            Self::DeepThinker => 0.04,
-            Self::TruthSeeker => 0.06,
-        }
+    pub fn hidden_description(&self) -> &str {
+        self.hidden_hint.as_deref().unwrap_or(&self.description)
     }
 
     pub fn color(&self) -> Color {
         if self.is_hidden() {
             Color::srgb(1.0, 0.5, 0.3) // Orange for hidden/secret
         } else {
-            match self.reward_multiplier() {
+            match self.reward {
                 r if r >= 0.10 => Color::srgb(1.0, 0.85, 0.3), // Gold for big rewards
                 r if r >= 0.05 => Color::srgb(0.7, 0.5, 1.0),  // Purple for medium
                 _ => Color::srgb(0.5, 0.8, 1.0),                // Blue for small
@@ -189,13 +121,13 @@ impl AchievementId {
 
 // ========== TRACKER ==========
 
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Clone)]
 pub struct AchievementTracker {
     pub unlocked: Vec<AchievementId>,
     /// Truths generated across all runs
     pub lifetime_truths: u32,
     /// Peak AFP reached in current run
-    pub peak_afp: u64,
+    pub peak_afp: BigNum,
     /// Deep Focus uses this run
     pub deep_focus_uses: u32,
     /// Time elapsed this run (seconds)
@@ -204,6 +136,9 @@ pub struct AchievementTracker {
     pub run_truths: u32,
     /// Queue of achievements to show notifications for
     pub notification_queue: Vec<AchievementId>,
+    /// Set by purchases and transcending, which happen with a menu open;
+    /// conditions are checked on the next game tick
+    pub recheck: bool,
 }
 
 impl Default for AchievementTracker {
@@ -211,11 +146,12 @@ impl Default for AchievementTracker {
         Self {
             unlocked: Vec::new(),
             lifetime_truths: 0,
-            peak_afp: 0,
+            peak_afp: BigNum::ZERO,
             deep_focus_uses: 0,
             run_elapsed: 0.0,
             run_truths: 0,
             notification_queue: Vec::new(),
+            recheck: false,
         }
     }
 }
@@ -233,23 +169,132 @@ impl AchievementTracker {
     }
 
     /// Total permanent wisdom multiplier from all unlocked achievements (1.0 = no bonus)
-    pub fn wisdom_multiplier(&self) -> f32 {
+    pub fn wisdom_multiplier(&self, content: &GameContent) -> f32 {
         1.0 + self
             .unlocked
             .iter()
-            .map(|a| a.reward_multiplier())
+            .map(|&a| content.achievement(a).reward)
             .sum::<f32>()
     }
 
+    /// Checks all achievement conditions and unlocks any that are met
+    pub fn check_unlocks(
+        &mut self,
+        generators: &GeneratorState,
+        acolytes: &AcolyteState,
+        transcendence: &TranscendenceState,
+    ) {
+        // Truth milestones (lifetime)
+        let lt = self.lifetime_truths;
+        if lt >= 1 {
+            self.unlock(AchievementId::FirstTruth);
+        }
+        if lt >= 10 {
+            self.unlock(AchievementId::TenTruths);
+        }
+        if lt >= 50 {
+            self.unlock(AchievementId::FiftyTruths);
+        }
+        if lt >= 100 {
+            self.unlock(AchievementId::HundredTruths);
+        }
+        if lt >= 500 {
+            self.unlock(AchievementId::FiveHundredTruths);
+        }
+        if lt >= 1000 {
+            self.unlock(AchievementId::ThousandTruths);
+        }
+
+        // AFP milestones (peak in current run)
+        let afp = self.peak_afp;
+        if afp >= BigNum::from(100.0) {
+            self.unlock(AchievementId::HundredAfp);
+        }
+        if afp >= BigNum::from(1_000.0) {
+            self.unlock(AchievementId::ThousandAfp);
+        }
+        if afp >= BigNum::from(100_000.0) {
+            self.unlock(AchievementId::HundredKAfp);
+        }
+        if afp >= BigNum::from(1_000_000.0) {
+            self.unlock(AchievementId::MillionAfp);
+        }
+
+        // Transcendence
+        let tc = transcendence.total_transcendences;
+        if tc >= 1 {
+            self.unlock(AchievementId::FirstTranscendence);
+        }
+        if tc >= 5 {
+            self.unlock(AchievementId::FiveTranscendences);
+        }
+        if tc >= 10 {
+            self.unlock(AchievementId::TenTranscendences);
+        }
+
+        // Generators
+        let total_gens: u32 = generators.owned.iter().sum();
+        if total_gens >= 1 {
+            self.unlock(AchievementId::FirstGenerator);
+        }
+        if total_gens >= 100 {
+            self.unlock(AchievementId::HundredGenerators);
+        }
+
+        let all_types = GeneratorType::ALL.iter().enumerate().all(|(i, _)| generators.owned[i] > 0);
+        if all_types {
+            self.unlock(AchievementId::AllGeneratorTypes);
+        }
+
+        if generators.count(GeneratorType::Candle) >= 50 {
+            self.unlock(AchievementId::FiftyCandles);
+        }
+
+        // Acolytes
+        if acolytes.count >= 1 {
+            self.unlock(AchievementId::FirstAcolyte);
+        }
+        if acolytes.count >= 10 {
+            self.unlock(AchievementId::TenAcolytes);
+        }
+        if acolytes.count >= 25 {
+            self.unlock(AchievementId::TwentyFiveAcolytes);
+        }
+
+        // Hidden achievements
+        // Speed Ponderer: truth within 30s of run start
+        if self.run_truths >= 1 && self.run_elapsed <= 30.0 {
+            self.unlock(AchievementId::SpeedPonderer);
+        }
+
+        // Deep Thinker: 10 deep focus uses in a run
+        if self.deep_focus_uses >= 10 {
+            self.unlock(AchievementId::DeepThinker);
+        }
+
+        // Truth Seeker: 50 truths in a single run
+        if self.run_truths >= 50 {
+            self.unlock(AchievementId::TruthSeeker);
+        }
+    }
+
     /// Reset per-run tracking stats (called on transcendence)
     pub fn reset_run_stats(&mut self) {
-        self.peak_afp = 0;
+        self.peak_afp = BigNum::ZERO;
         self.deep_focus_uses = 0;
         self.run_elapsed = 0.0;
         self.run_truths = 0;
     }
 }
 
+impl ModifierSource for AchievementTracker {
+    fn contribute(&self, content: &GameContent, modifiers: &mut WisdomModifiers) {
+        let mult = self.wisdom_multiplier(content) as f64;
+        modifiers.mul("achievements", ModifierChannel::Click, mult);
+        modifiers.mul("achievements", ModifierChannel::Passive, mult);
+    }
+}
+
 // ========== SYSTEMS ==========
 
 /// Tracks lifetime truths and run truths from TruthGenerated messages
@@ -276,114 +321,62 @@ pub fn track_achievement_stats(
 
 /// Tracks deep focus activations
 pub fn track_deep_focus_uses(
-    keys: Res<ButtonInput<KeyCode>>,
-    ponder: Res<super::pondering::PonderState>,
+    mut activations: MessageReader<DeepFocusActivated>,
+    mut tracker: ResMut<AchievementTracker>,
+) {
+    tracker.deep_focus_uses += activations.read().count() as u32;
+}
+
+/// Everything that can newly satisfy an achievement condition
+#[derive(SystemParam)]
+pub struct AchievementTriggers<'w, 's> {
+    truths: MessageReader<'w, 's, TruthGenerated>,
+    acolytes: MessageReader<'w, 's, AcolyteSummoned>,
+    deep_focus: MessageReader<'w, 's, DeepFocusActivated>,
+    /// Moments can grant AFP outside of truths
+    moments: MessageReader<'w, 's, MomentClaimed>,
+    shadows: MessageReader<'w, 's, ShadowsDispelled>,
+    challenges: MessageReader<'w, 's, ChallengeCompleted>,
+}
+
+impl AchievementTriggers<'_, '_> {
+    /// Drains every reader; true if anything happened since the last check
+    fn any(&mut self) -> bool {
+        let seen = self.truths.read().count()
+            + self.acolytes.read().count()
+            + self.deep_focus.read().count()
+            + self.moments.read().count()
+            + self.shadows.read().count()
+            + self.challenges.read().count();
+        seen > 0
+    }
+}
+
+/// Flags a recheck for purchases and transcending. Runs in every state since
+/// these messages are written with a menu open, where the game tick is paused.
+pub fn latch_menu_triggers(
     mut tracker: ResMut<AchievementTracker>,
+    mut generators: MessageReader<GeneratorPurchased>,
+    mut shop: MessageReader<ShopItemBought>,
+    mut transcended: MessageReader<Transcended>,
 ) {
-    // Detect when deep focus was just activated
-    if keys.just_pressed(KeyCode::Space) && !ponder.deep_focus_active && ponder.deep_focus_cooldown <= 0.0 {
-        tracker.deep_focus_uses += 1;
+    let seen = generators.read().count() + shop.read().count() + transcended.read().count();
+    if seen > 0 {
+        tracker.recheck = true;
     }
 }
 
-/// Checks all achievement conditions and unlocks any that are met
+/// Checks all achievement conditions after anything that could satisfy one
 pub fn check_achievements(
+    mut triggers: AchievementTriggers,
     mut tracker: ResMut<AchievementTracker>,
     generators: Res<GeneratorState>,
     acolytes: Res<AcolyteState>,
     transcendence: Res<TranscendenceState>,
 ) {
-    // Truth milestones (lifetime)
-    let lt = tracker.lifetime_truths;
-    if lt >= 1 {
-        tracker.unlock(AchievementId::FirstTruth);
-    }
-    if lt >= 10 {
-        tracker.unlock(AchievementId::TenTruths);
-    }
-    if lt >= 50 {
-        tracker.unlock(AchievementId::FiftyTruths);
-    }
-    if lt >= 100 {
-        tracker.unlock(AchievementId::HundredTruths);
-    }
-    if lt >= 500 {
-        tracker.unlock(AchievementId::FiveHundredTruths);
-    }
-    if lt >= 1000 {
-        tracker.unlock(AchievementId::ThousandTruths);
-    }
-
-    // AFP milestones (peak in current run)
-    let afp = tracker.peak_afp;
-    if afp >= 100 {
-        tracker.unlock(AchievementId::HundredAfp);
-    }
-    if afp >= 1_000 {
-        tracker.unlock(AchievementId::ThousandAfp);
-    }
-    if afp >= 100_000 {
-        tracker.unlock(AchievementId::HundredKAfp);
-    }
-    if afp >= 1_000_000 {
-        tracker.unlock(AchievementId::MillionAfp);
-    }
-
-    // Transcendence
-    let tc = transcendence.total_transcendences;
-    if tc >= 1 {
-        tracker.unlock(AchievementId::FirstTranscendence);
-    }
-    if tc >= 5 {
-        tracker.unlock(AchievementId::FiveTranscendences);
-    }
-    if tc >= 10 {
-        tracker.unlock(AchievementId::TenTranscendences);
-    }
-
-    // Generators
-    let total_gens: u32 = generators.owned.iter().sum();
-    if total_gens >= 1 {
-        tracker.unlock(AchievementId::FirstGenerator);
-    }
-    if total_gens >= 100 {
-        tracker.unlock(AchievementId::HundredGenerators);
-    }
-
-    let all_types = GeneratorType::ALL.iter().enumerate().all(|(i, _)| generators.owned[i] > 0);
-    if all_types {
-        tracker.unlock(AchievementId::AllGeneratorTypes);
-    }
-
-    if generators.count(GeneratorType::Candle) >= 50 {
-        tracker.unlock(AchievementId::FiftyCandles);
-    }
-
-    // Acolytes
-    if acolytes.count >= 1 {
-        tracker.unlock(AchievementId::FirstAcolyte);
-    }
-    if acolytes.count >= 10 {
-        tracker.unlock(AchievementId::TenAcolytes);
-    }
-    if acolytes.count >= 25 {
-        tracker.unlock(AchievementId::TwentyFiveAcolytes);
-    }
-
-    // Hidden achievements
-    // Speed Ponderer: truth within 30s of run start
-    if tracker.run_truths >= 1 && tracker.run_elapsed <= 30.0 {
-        tracker.unlock(AchievementId::SpeedPonderer);
-    }
-
-    // Deep Thinker: 10 deep focus uses in a run
-    if tracker.deep_focus_uses >= 10 {
-        tracker.unlock(AchievementId::DeepThinker);
-    }
-
-    // Truth Seeker: 50 truths in a single run
-    if tracker.run_truths >= 50 {
-        tracker.unlock(AchievementId::TruthSeeker);
+    if triggers.any() || tracker.recheck {
+        tracker.recheck = false;
+        tracker.check_unlocks(&generators, &acolytes, &transcendence);
     }
 }
 
@@ -398,8 +391,10 @@ pub struct AchievementNotification {
 pub fn spawn_notifications(
     mut commands: Commands,
     mut tracker: ResMut<AchievementTracker>,
+    content: Res<GameContent>,
 ) {
     while let Some(id) = tracker.notification_queue.pop() {
+        let def = content.achievement(id);
         commands.spawn((
             Node {
                 position_type: PositionType::Absolute,
@@ -426,17 +421,17 @@ pub fn spawn_notifications(
                 TextColor(Color::srgba(1.0, 0.85, 0.3, 0.8)),
             ));
             popup.spawn((
-                Text::new(id.name()),
+                Text::new(def.name.clone()),
                 TextFont { font_size: 20.0, ..default() },
-                TextColor(id.color()),
+                TextColor(def.color()),
             ));
             popup.spawn((
-                Text::new(id.description()),
+                Text::new(def.description.clone()),
                 TextFont { font_size: 13.0, ..default() },
                 TextColor(Color::srgba(0.8, 0.75, 0.85, 0.7)),
             ));
             popup.spawn((
-                Text::new(format!("+{:.0}% wisdom", id.reward_multiplier() * 100.0)),
+                Text::new(format!("+{:.0}% wisdom", def.reward * 100.0)),
                 TextFont { font_size: 12.0, ..default() },
                 TextColor(Color::srgba(0.5, 1.0, 0.5, 0.8)),
             ));
@@ -481,10 +476,14 @@ pub fn toggle_achievements(
     }
 }
 
-pub fn open_achievements(mut commands: Commands, tracker: Res<AchievementTracker>) {
+pub fn open_achievements(
+    mut commands: Commands,
+    tracker: Res<AchievementTracker>,
+    content: Res<GameContent>,
+) {
     let total_unlocked = tracker.unlocked.len();
     let total_achievements = AchievementId::ALL.len();
-    let bonus = tracker.wisdom_multiplier();
+    let bonus = tracker.wisdom_multiplier(&content);
 
     commands
         .spawn((
@@ -550,23 +549,24 @@ pub fn open_achievements(mut commands: Commands, tracker: Res<AchievementTracker
                         })
                         .with_children(|list| {
                             for id in AchievementId::ALL {
+                                let def = content.achievement(id);
                                 let owned = tracker.has(id);
-                                let is_hidden = id.is_hidden() && !owned;
+                                let is_hidden = def.is_hidden() && !owned;
 
                                 let bg_alpha = if owned { 0.15 } else { 0.05 };
                                 let name_str = if is_hidden {
                                     "???".to_string()
                                 } else {
-                                    id.name().to_string()
+                                    def.name.clone()
                                 };
                                 let desc_str = if is_hidden {
-                                    id.hidden_description().to_string()
+                                    def.hidden_description().to_string()
                                 } else {
-                                    id.description().to_string()
+                                    def.description.clone()
                                 };
 
                                 let name_color = if owned {
-                                    id.color()
+                                    def.color()
                                 } else if is_hidden {
                                     Color::srgba(0.5, 0.5, 0.5, 0.4)
                                 } else {
@@ -615,7 +615,7 @@ pub fn open_achievements(mut commands: Commands, tracker: Res<AchievementTracker
                                         row.spawn((
                                             Text::new(format!(
                                                 "+{:.0}%",
-                                                id.reward_multiplier() * 100.0
+                                                def.reward * 100.0
                                             )),
                                             TextFont { font_size: 14.0, ..default() },
                                             TextColor(Color::srgba(0.5, 1.0, 0.5, 0.8)),
@@ -624,7 +624,7 @@ pub fn open_achievements(mut commands: Commands, tracker: Res<AchievementTracker
                                         row.spawn((
                                             Text::new(format!(
                                                 "+{:.0}%",
-                                                id.reward_multiplier() * 100.0
+                                                def.reward * 100.0
                                             )),
                                             TextFont { font_size: 14.0, ..default() },
                                             TextColor(Color::srgba(0.5, 0.5, 0.5, 0.3)),
@@ -1,88 +1,65 @@
-use super::achievements::AchievementTracker;
-use super::challenges::ChallengeState;
-use super::codex::TruthCodex;
-use super::layers::LayerState;
-use super::moments::MomentState;
+use super::balance::Balance;
+use super::bignum::BigNum;
+use super::content::GameContent;
+use super::economy;
+use super::input::PlayerInput;
+use super::ledger::{WisdomLedger, WisdomSource};
+use super::modifiers::WisdomModifiers;
 use super::progression::ArcaneProgress;
-use super::resources::SecondaryResources;
-use super::schools::SchoolState;
-use super::shop::PurchaseTracker;
-use super::transcendence::TranscendenceState;
 use super::wisdom::WisdomMeter;
-use crate::environment::daynight::DayNightCycle;
 use bevy::prelude::*;
 
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Clone, Default)]
 pub struct AcolyteState {
     pub count: u32,
-    pub base_rate: f32,
-    pub base_cost: u64,
-    pub cost_growth: f32,
-}
-
-impl Default for AcolyteState {
-    fn default() -> Self {
-        Self {
-            count: 0,
-            base_rate: 0.2,
-            base_cost: 20,
-            cost_growth: 1.15,
-        }
-    }
 }
 
 impl AcolyteState {
-    pub fn next_cost(&self) -> u64 {
-        (self.base_cost as f64 * self.cost_growth.powi(self.count as i32) as f64).ceil() as u64
+    pub fn next_cost(&self, balance: &Balance) -> BigNum {
+        let growth = BigNum::from(balance.acolytes.cost_growth).powi(self.count as i32);
+        (BigNum::from(balance.acolytes.base_cost) * growth).ceil()
     }
 
-    pub fn passive_rate(&self) -> f32 {
-        self.count as f32 * self.base_rate
+    pub fn passive_rate(&self, balance: &Balance) -> f32 {
+        self.count as f32 * balance.acolytes.base_rate
     }
 }
 
+/// An acolyte joined
+#[derive(Message)]
+pub struct AcolyteSummoned {
+    /// Acolytes after this one joined
+    pub count: u32,
+}
+
 pub fn summon_acolyte(
-    keys: Res<ButtonInput<KeyCode>>,
+    input: Res<PlayerInput>,
     mut acolytes: ResMut<AcolyteState>,
     mut progress: ResMut<ArcaneProgress>,
+    mut summoned: MessageWriter<AcolyteSummoned>,
+    content: Res<GameContent>,
 ) {
-    if keys.just_pressed(KeyCode::KeyA) {
-        let cost = acolytes.next_cost();
+    if input.summon_acolyte {
+        let cost = acolytes.next_cost(&content.balance);
         if progress.focus_points >= cost {
             progress.focus_points -= cost;
             acolytes.count += 1;
+            summoned.write(AcolyteSummoned {
+                count: acolytes.count,
+            });
         }
     }
 }
 
 pub fn passive_wisdom(
     acolytes: Res<AcolyteState>,
-    tracker: Res<PurchaseTracker>,
-    moments: Res<MomentState>,
-    transcendence: Res<TranscendenceState>,
-    school: Res<SchoolState>,
-    achievements: Res<AchievementTracker>,
-    challenges: Res<ChallengeState>,
-    resources: Res<SecondaryResources>,
-    codex: Res<TruthCodex>,
-    layers: Res<LayerState>,
-    cycle: Res<DayNightCycle>,
+    modifiers: Res<WisdomModifiers>,
     mut wisdom: ResMut<WisdomMeter>,
+    mut ledger: ResMut<WisdomLedger>,
+    content: Res<GameContent>,
     time: Res<Time>,
 ) {
-    if acolytes.count == 0 {
-        return;
-    }
-    let rate = acolytes.passive_rate()
-        * (1.0 + tracker.efficiency_bonus)
-        * tracker.wisdom_speed_bonus
-        * moments.wisdom_multiplier()
-        * transcendence.passive_multiplier()
-        * school.passive_multiplier()
-        * achievements.wisdom_multiplier()
-        * challenges.passive_multiplier()
-        * resources.focus_mult()
-        * codex.wisdom_multiplier()
-        * layers.dream_multiplier(&cycle);
-    wisdom.current += rate * time.delta_secs();
+    let gained = economy::acolyte_rate(&acolytes, &modifiers, &content.balance)
+        * time.delta_secs() as f64;
+    ledger.gain(&mut wisdom, WisdomSource::Acolyte, gained);
 }
@@ -0,0 +1,453 @@
+use super::bignum::BigNum;
+use super::content::GameContent;
+use super::economy::EconomyModel;
+use super::generators::GeneratorType;
+use super::resources::serenity_rate;
+use super::shop::ShopItemId;
+use super::transcendence::{EnlightenmentId, NodeStatus};
+use serde::Serialize;
+
+// ========== ACTIONS ==========
+
+/// Something the player can spend AFP or insight on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum Action {
+    Acolyte,
+    Generator(GeneratorType),
+    ShopItem(ShopItemId),
+    Enlightenment(EnlightenmentId),
+}
+
+impl Action {
+    pub fn name(&self, content: &GameContent) -> String {
+        match self {
+            Self::Acolyte => "Acolyte".to_string(),
+            Self::Generator(gt) => content.generator(*gt).name.clone(),
+            Self::ShopItem(id) => content
+                .shop_item(id)
+                .map_or_else(|| id.to_string(), |item| item.name.clone()),
+            Self::Enlightenment(id) => content.enlightenment(*id).name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum Price {
+    Afp(BigNum),
+    Insight(u32),
+}
+
+/// Every action still open to the player: acolytes, unlocked generators, shop items
+/// not yet bought and enlightenment ranks the tree allows next
+fn available_actions(model: &EconomyModel) -> Vec<Action> {
+    let content = &model.content;
+    let mut actions = vec![Action::Acolyte];
+    actions.extend(
+        GeneratorType::ALL
+            .into_iter()
+            .filter(|&gt| model.progress.total_truths >= content.generator(gt).unlock_truths)
+            .map(Action::Generator),
+    );
+    actions.extend(
+        content
+            .shop_items
+            .iter()
+            .filter(|item| {
+                !model.tracker.purchased.contains(&item.id) && item.is_unlocked(&model.generators)
+            })
+            .map(|item| Action::ShopItem(item.id.clone())),
+    );
+    actions.extend(
+        EnlightenmentId::ALL
+            .into_iter()
+            .filter(|&id| {
+                let status = model.transcendence.status(id, content, &model.ascension);
+                matches!(status, NodeStatus::Available(_))
+            })
+            .map(Action::Enlightenment),
+    );
+    actions
+}
+
+fn price(model: &EconomyModel, action: &Action) -> Price {
+    let content = &model.content;
+    match action {
+        Action::Acolyte => Price::Afp(model.acolytes.next_cost(&content.balance)),
+        Action::Generator(gt) => {
+            let discount = model.transcendence.generator_cost_discount();
+            Price::Afp(content.generator_cost(*gt, model.generators.count(*gt), discount))
+        }
+        Action::ShopItem(id) => {
+            Price::Afp(content.shop_item(id).map_or(BigNum::ZERO, |i| BigNum::from(i.cost)))
+        }
+        Action::Enlightenment(id) => Price::Insight(model.enlightenment_cost(*id)),
+    }
+}
+
+/// Serenity a generator tier needs on top of its AFP price
+fn serenity_cost(model: &EconomyModel, action: &Action) -> Option<f64> {
+    match action {
+        Action::Generator(gt) => model.content.generator(*gt).serenity_cost,
+        _ => None,
+    }
+}
+
+/// Buy `action` through the same rules as the shop; false if it can't be bought now
+pub fn apply(model: &mut EconomyModel, action: &Action) -> bool {
+    match action {
+        Action::Acolyte => model.summon_acolyte(),
+        Action::Generator(gt) => model.buy_generator(*gt),
+        Action::ShopItem(id) => model.buy_shop_item(id),
+        Action::Enlightenment(id) => model.buy_enlightenment(*id),
+    }
+}
+
+/// Hand over whatever `action` costs and buy it, so its effect can be measured
+/// before the player can afford it
+fn grant_and_apply(model: &mut EconomyModel, action: &Action) -> bool {
+    match price(model, action) {
+        Price::Afp(cost) => model.progress.focus_points += cost,
+        Price::Insight(cost) => model.transcendence.insight += cost,
+    }
+    if let Some(serenity) = serenity_cost(model, action) {
+        model.resources.serenity += serenity;
+    }
+    apply(model, action)
+}
+
+// ========== PAYBACK ==========
+
+#[derive(Debug, Clone)]
+pub struct AdvisorConfig {
+    /// How far ahead each action's effect is measured. Long enough to see truth
+    /// scaling and a few truths, short enough to stay about the present.
+    pub horizon_secs: f64,
+    pub step_secs: f64,
+    /// Orb clicks per second assumed while measuring
+    pub clicks_per_sec: f64,
+}
+
+impl Default for AdvisorConfig {
+    fn default() -> Self {
+        Self {
+            horizon_secs: 600.0,
+            step_secs: 5.0,
+            clicks_per_sec: 0.0,
+        }
+    }
+}
+
+/// AFP earned over the horizon, counting a partly filled meter by how full it is
+fn afp_over_horizon(model: &EconomyModel, config: &AdvisorConfig) -> f64 {
+    let mut model = model.clone();
+    let start = meter_afp(&model);
+    let mut earned = BigNum::ZERO;
+    let mut clicks_owed = 0.0;
+    let mut elapsed = 0.0;
+    while elapsed < config.horizon_secs {
+        let dt = config.step_secs.min(config.horizon_secs - elapsed);
+        clicks_owed += config.clicks_per_sec * dt;
+        let clicks = clicks_owed.floor();
+        clicks_owed -= clicks;
+        model.ponder(clicks as u32);
+        earned += model.advance(dt).afp_earned;
+        elapsed += dt;
+    }
+    (earned + meter_afp(&model) - start).to_f64()
+}
+
+fn meter_afp(model: &EconomyModel) -> BigNum {
+    let fill = (model.wisdom.current / model.wisdom.max_wisdom).to_f64().min(1.0);
+    model.modifiers.afp_per_truth() * fill
+}
+
+/// How one action pays for itself
+#[derive(Debug, Clone, Serialize)]
+pub struct Appraisal {
+    pub action: Action,
+    pub price: Price,
+    /// Serenity needed on top of the price
+    pub serenity_cost: Option<f64>,
+    /// Extra AFP per second once bought, averaged over the horizon
+    pub afp_gain_per_sec: f64,
+    /// Seconds of the extra AFP needed to earn back an AFP price; `None` if it never does
+    pub payback_secs: Option<f64>,
+    pub affordable: bool,
+    /// A cheaper or equal action in the same currency that gains at least as much
+    pub dominated_by: Option<Action>,
+}
+
+impl Appraisal {
+    fn ranks_before(&self, other: &Appraisal) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+        match (self.price, other.price) {
+            (Price::Afp(_), Price::Insight(_)) => Ordering::Less,
+            (Price::Insight(_), Price::Afp(_)) => Ordering::Greater,
+            (Price::Afp(_), Price::Afp(_)) => match (self.payback_secs, other.payback_secs) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            (Price::Insight(a), Price::Insight(b)) => {
+                let per_insight = |gain: f64, cost: u32| gain / cost.max(1) as f64;
+                per_insight(other.afp_gain_per_sec, b)
+                    .partial_cmp(&per_insight(self.afp_gain_per_sec, a))
+                    .unwrap_or(Ordering::Equal)
+            }
+        }
+    }
+
+    /// True if `self` is at least as cheap and at least as strong, and better in one
+    fn dominates(&self, other: &Appraisal) -> bool {
+        let (cheaper, same_price) = match (self.price, other.price) {
+            (Price::Afp(a), Price::Afp(b)) => (a < b, a == b),
+            (Price::Insight(a), Price::Insight(b)) => (a < b, a == b),
+            _ => return false,
+        };
+        let stronger = self.afp_gain_per_sec > other.afp_gain_per_sec;
+        let as_strong = self.afp_gain_per_sec >= other.afp_gain_per_sec;
+        (cheaper && as_strong) || (same_price && stronger)
+    }
+}
+
+/// Every open action with its payback time, best first: AFP purchases by payback,
+/// then enlightenments by AFP gain per insight
+pub fn appraise(model: &EconomyModel, config: &AdvisorConfig) -> Vec<Appraisal> {
+    let baseline = afp_over_horizon(model, config);
+    let mut appraisals: Vec<Appraisal> = available_actions(model)
+        .into_iter()
+        .map(|action| {
+            let price = price(model, &action);
+            let serenity_cost = serenity_cost(model, &action);
+            let affordable = match price {
+                Price::Afp(cost) => model.progress.focus_points >= cost,
+                Price::Insight(cost) => model.transcendence.insight >= cost,
+            } && serenity_cost.is_none_or(|s| model.resources.serenity >= s);
+
+            let mut after = model.clone();
+            let gain = if grant_and_apply(&mut after, &action) {
+                ((afp_over_horizon(&after, config) - baseline) / config.horizon_secs).max(0.0)
+            } else {
+                0.0
+            };
+            let payback_secs = match price {
+                Price::Afp(cost) if gain > 0.0 => Some(cost.to_f64() / gain),
+                _ => None,
+            };
+            Appraisal {
+                action,
+                price,
+                serenity_cost,
+                afp_gain_per_sec: gain,
+                payback_secs,
+                affordable,
+                dominated_by: None,
+            }
+        })
+        .collect();
+
+    for i in 0..appraisals.len() {
+        appraisals[i].dominated_by = appraisals
+            .iter()
+            .find(|other| other.dominates(&appraisals[i]))
+            .map(|other| other.action.clone());
+    }
+    appraisals.sort_by(|a, b| a.ranks_before(b));
+    appraisals
+}
+
+/// Affordable actions only, best first
+pub fn rank_affordable(model: &EconomyModel, config: &AdvisorConfig) -> Vec<Appraisal> {
+    appraise(model, config)
+        .into_iter()
+        .filter(|appraisal| appraisal.affordable)
+        .collect()
+}
+
+// ========== SCHEDULE ==========
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledPurchase {
+    /// Seconds from the start of the schedule
+    pub at_secs: f64,
+    pub action: Action,
+    pub price: BigNum,
+}
+
+/// Plan AFP purchases for `duration_secs`. Each pick is the action that pays back
+/// soonest once the wait to afford it is counted; the model then plays forward to
+/// that purchase and picks again.
+pub fn greedy_schedule(
+    start: &EconomyModel,
+    config: &AdvisorConfig,
+    duration_secs: f64,
+) -> Vec<ScheduledPurchase> {
+    let mut model = start.clone();
+    let mut schedule = Vec::new();
+    let mut elapsed = 0.0;
+    let mut clicks_owed = 0.0;
+
+    while elapsed < duration_secs {
+        let afp_rate = afp_over_horizon(&model, config) / config.horizon_secs;
+        let serenity_per_sec = serenity_rate(&model.acolytes, &model.generators);
+        let pick = appraise(&model, config)
+            .into_iter()
+            .filter_map(|appraisal| {
+                let (Price::Afp(cost), Some(payback)) = (appraisal.price, appraisal.payback_secs)
+                else {
+                    return None;
+                };
+                let afp_short = (cost - model.progress.focus_points).to_f64().max(0.0);
+                let serenity_short = appraisal
+                    .serenity_cost
+                    .map_or(0.0, |s| (s - model.resources.serenity).max(0.0));
+                let wait = wait_secs(afp_short, afp_rate).max(wait_secs(serenity_short, serenity_per_sec));
+                wait.is_finite().then_some((appraisal.action, cost, wait + payback))
+            })
+            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+        let Some((action, cost, _)) = pick else {
+            break;
+        };
+
+        // Play forward until the pick can be bought
+        let mut bought = apply(&mut model, &action);
+        while !bought && elapsed < duration_secs {
+            let dt = config.step_secs.min(duration_secs - elapsed);
+            clicks_owed += config.clicks_per_sec * dt;
+            let clicks = clicks_owed.floor();
+            clicks_owed -= clicks;
+            model.ponder(clicks as u32);
+            model.advance(dt);
+            elapsed += dt;
+            bought = apply(&mut model, &action);
+        }
+        if !bought {
+            break;
+        }
+        schedule.push(ScheduledPurchase {
+            at_secs: elapsed,
+            action,
+            price: cost,
+        });
+    }
+    schedule
+}
+
+fn wait_secs(short: f64, rate: f64) -> f64 {
+    if short <= 0.0 {
+        0.0
+    } else if rate > 0.0 {
+        short / rate
+    } else {
+        f64::INFINITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::persistence::SaveData;
+
+    fn model_with(setup: impl FnOnce(&mut SaveData)) -> EconomyModel {
+        let mut save = SaveData::new_game();
+        setup(&mut save);
+        EconomyModel::from_save(&save, &GameContent::default())
+    }
+
+    fn find<'a>(appraisals: &'a [Appraisal], action: &Action) -> &'a Appraisal {
+        appraisals.iter().find(|a| a.action == *action).unwrap()
+    }
+
+    #[test]
+    fn ranking_is_sorted_by_payback() {
+        let model = model_with(|save| {
+            save.acolyte_count = 3;
+            save.focus_points = BigNum::from(1_000_000.0);
+            save.total_truths = 30;
+        });
+        let ranked = rank_affordable(&model, &AdvisorConfig::default());
+        assert!(ranked.iter().all(|a| a.affordable));
+        let paybacks: Vec<f64> = ranked.iter().filter_map(|a| a.payback_secs).collect();
+        assert!(paybacks.len() > 3);
+        assert!(paybacks.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn milestones_and_synergies_count_toward_gain() {
+        let config = AdvisorConfig::default();
+        let plain = model_with(|save| save.generators_owned[0] = 3);
+        let at_milestone = model_with(|save| save.generators_owned[0] = 4);
+        let candle = Action::Generator(GeneratorType::Candle);
+
+        // The fifth candle crosses the x1.5 milestone, so it is worth more than the fourth
+        let fourth = find(&appraise(&plain, &config), &candle).afp_gain_per_sec;
+        let fifth = find(&appraise(&at_milestone, &config), &candle).afp_gain_per_sec;
+        assert!(fifth > fourth * 1.5, "fourth {} fifth {}", fourth, fifth);
+
+        // With crystal balls owned, a candle also boosts them through their synergy
+        let with_balls = model_with(|save| {
+            save.generators_owned[0] = 3;
+            save.generators_owned[1] = 10;
+        });
+        let mut no_synergy = with_balls.clone();
+        no_synergy.content.balance.synergies.clear();
+        no_synergy.synergies.recalculate(
+            &no_synergy.generators,
+            &no_synergy.content.balance,
+            &no_synergy.tracker,
+        );
+        let linked = find(&appraise(&with_balls, &config), &candle).afp_gain_per_sec;
+        let unlinked = find(&appraise(&no_synergy, &config), &candle).afp_gain_per_sec;
+        assert!(linked > unlinked, "linked {} unlinked {}", linked, unlinked);
+    }
+
+    #[test]
+    fn serenity_gates_affordability() {
+        let model = model_with(|save| {
+            save.focus_points = BigNum::from(1e30);
+            save.total_truths = 10_000;
+            save.serenity = 0.0;
+        });
+        let appraisals = appraise(&model, &AdvisorConfig::default());
+        let gated = GeneratorType::ALL
+            .into_iter()
+            .find(|&gt| model.content.generator(gt).serenity_cost.is_some())
+            .expect("some tier needs serenity");
+        let entry = find(&appraisals, &Action::Generator(gated));
+        assert!(!entry.affordable);
+        assert!(entry.serenity_cost.is_some());
+        assert!(find(&appraisals, &Action::Generator(GeneratorType::Candle)).affordable);
+    }
+
+    #[test]
+    fn items_with_no_production_effect_are_flagged() {
+        let model = model_with(|save| {
+            save.acolyte_count = 5;
+            save.insight = 100;
+            save.enlightenment_ranks = vec![(EnlightenmentId::DeepRoots, 1)];
+        });
+        let appraisals = appraise(&model, &AdvisorConfig::default());
+        let head_start = find(&appraisals, &Action::Enlightenment(EnlightenmentId::HeadStart));
+        assert_eq!(head_start.afp_gain_per_sec, 0.0);
+        assert!(head_start.dominated_by.is_some());
+        let eternal = find(&appraisals, &Action::Enlightenment(EnlightenmentId::EternalFlow));
+        assert!(eternal.afp_gain_per_sec > 0.0);
+    }
+
+    #[test]
+    fn greedy_schedule_buys_in_order_and_grows_production() {
+        let start = model_with(|save| save.acolyte_count = 2);
+        let config = AdvisorConfig::default();
+        let schedule = greedy_schedule(&start, &config, 1800.0);
+        assert!(schedule.len() > 2);
+        assert!(schedule.windows(2).all(|w| w[0].at_secs <= w[1].at_secs));
+
+        let mut replay = start.clone();
+        for purchase in &schedule {
+            replay.progress.focus_points += purchase.price;
+            assert!(apply(&mut replay, &purchase.action));
+        }
+        assert!(replay.passive_rate() > start.passive_rate());
+    }
+}
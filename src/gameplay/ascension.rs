@@ -0,0 +1,641 @@
+use super::content::GameContent;
+use super::layers::LayerState;
+use super::ledger::WisdomLedger;
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
+use super::schools::SchoolState;
+use super::state::GameState;
+use super::transcendence::TranscendenceState;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// ========== DATA ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AscensionUpgradeId {
+    /// +50% click and passive wisdom permanently
+    CelestialMind,
+    /// +50% insight from every transcendence
+    InsightWell,
+    /// Start each ascension with 10 insight
+    SeedOfInsight,
+    /// Enlightenments cost 25% less insight
+    FrugalEnlightenment,
+    /// Content layers open at half the transcendences
+    LayerMemory,
+    /// +25% AFP per truth
+    StarlitTruths,
+}
+
+impl AscensionUpgradeId {
+    pub const ALL: [AscensionUpgradeId; 6] = [
+        Self::CelestialMind,
+        Self::InsightWell,
+        Self::SeedOfInsight,
+        Self::FrugalEnlightenment,
+        Self::LayerMemory,
+        Self::StarlitTruths,
+    ];
+}
+
+/// An ascension upgrade's text and Starlight cost, from the content file
+#[derive(Debug, Clone, Deserialize)]
+pub struct AscensionUpgradeDef {
+    pub id: AscensionUpgradeId,
+    pub name: String,
+    pub description: String,
+    pub cost: u32,
+    /// Every one of these must be bought first
+    #[serde(default)]
+    pub requires: Vec<AscensionUpgradeId>,
+}
+
+// ========== RESOURCES ==========
+
+/// The prestige tier above transcendence. Starlight and upgrades are never reset.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct AscensionState {
+    pub starlight: u32,
+    pub total_ascensions: u32,
+    pub purchased_upgrades: Vec<AscensionUpgradeId>,
+}
+
+/// How much starlight would be earned if ascending now. Ascension opens once every
+/// enlightenment is learned or closed off by an exclusive choice, and pays the square
+/// root of this ascension's transcendences.
+pub fn pending_starlight(transcendence: &TranscendenceState, content: &GameContent) -> u32 {
+    let (settled, total) = transcendence.tree_progress(content);
+    if settled < total {
+        return 0;
+    }
+    (transcendence.total_transcendences as f64).sqrt().floor() as u32
+}
+
+impl AscensionState {
+    pub fn has(&self, id: AscensionUpgradeId) -> bool {
+        self.purchased_upgrades.contains(&id)
+    }
+
+    /// Whether every prerequisite of `id` is owned
+    pub fn can_unlock(&self, id: AscensionUpgradeId, content: &GameContent) -> bool {
+        content
+            .ascension_upgrade(id)
+            .requires
+            .iter()
+            .all(|&req| self.has(req))
+    }
+
+    /// Permanent click and passive wisdom multiplier
+    pub fn wisdom_multiplier(&self) -> f64 {
+        if self.has(AscensionUpgradeId::CelestialMind) {
+            1.5
+        } else {
+            1.0
+        }
+    }
+
+    /// Permanent AFP per truth multiplier
+    pub fn afp_multiplier(&self) -> f64 {
+        if self.has(AscensionUpgradeId::StarlitTruths) {
+            1.25
+        } else {
+            1.0
+        }
+    }
+
+    /// Insight granted for a transcendence worth `base`
+    pub fn insight_gain(&self, base: u32) -> u32 {
+        if self.has(AscensionUpgradeId::InsightWell) {
+            base + base / 2
+        } else {
+            base
+        }
+    }
+
+    /// Insight each ascension starts with
+    pub fn starting_insight(&self) -> u32 {
+        if self.has(AscensionUpgradeId::SeedOfInsight) {
+            10
+        } else {
+            0
+        }
+    }
+
+    /// What an enlightenment listed at `base` insight actually costs
+    pub fn enlightenment_cost(&self, base: u32) -> u32 {
+        if self.has(AscensionUpgradeId::FrugalEnlightenment) {
+            (base * 3).div_ceil(4)
+        } else {
+            base
+        }
+    }
+
+    /// Transcendences a layer listed at `required` actually needs
+    pub fn layer_requirement(&self, required: u32) -> u32 {
+        if self.has(AscensionUpgradeId::LayerMemory) {
+            required.div_ceil(2)
+        } else {
+            required
+        }
+    }
+}
+
+impl ModifierSource for AscensionState {
+    fn contribute(&self, _content: &GameContent, modifiers: &mut WisdomModifiers) {
+        modifiers.mul("ascension", ModifierChannel::Click, self.wisdom_multiplier());
+        modifiers.mul("ascension", ModifierChannel::Passive, self.wisdom_multiplier());
+        modifiers.mul("ascension", ModifierChannel::Afp, self.afp_multiplier());
+    }
+}
+
+/// The player ascended; the new run starts once a school is chosen
+#[derive(Message)]
+pub struct Ascended {
+    pub starlight_gained: u32,
+}
+
+// ========== ASCENSION UI ==========
+
+#[derive(Component)]
+pub struct AscensionPanel;
+
+#[derive(Component)]
+pub struct AscendButton;
+
+#[derive(Component)]
+pub struct AscensionUpgradeButton(pub AscensionUpgradeId);
+
+#[derive(Component)]
+pub struct AscensionUpgradeList;
+
+#[derive(Component)]
+pub struct StarlightText;
+
+pub fn toggle_ascension(
+    keys: Res<ButtonInput<KeyCode>>,
+    current_state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::KeyN) {
+        match current_state.get() {
+            GameState::Playing => next_state.set(GameState::AscensionOpen),
+            GameState::AscensionOpen => next_state.set(GameState::Playing),
+            _ => {}
+        }
+    }
+}
+
+pub fn open_ascension_ui(
+    mut commands: Commands,
+    ascension: Res<AscensionState>,
+    transcendence: Res<TranscendenceState>,
+    content: Res<GameContent>,
+) {
+    let pending = pending_starlight(&transcendence, &content);
+    let (settled, total) = transcendence.tree_progress(&content);
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            AscensionPanel,
+        ))
+        .with_children(|backdrop| {
+            backdrop
+                .spawn((
+                    Node {
+                        width: Val::Px(550.0),
+                        max_height: Val::Percent(85.0),
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(24.0)),
+                        row_gap: Val::Px(12.0),
+                        overflow: Overflow::scroll_y(),
+                        border_radius: BorderRadius::all(Val::Px(8.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.1, 0.08, 0.04, 0.95)),
+                ))
+                .with_children(|panel| {
+                    // Title
+                    panel.spawn((
+                        Text::new("Ascension"),
+                        TextFont { font_size: 28.0, ..default() },
+                        TextColor(Color::srgb(1.0, 0.85, 0.4)),
+                    ));
+
+                    // Divider
+                    panel.spawn((
+                        Node { width: Val::Percent(100.0), height: Val::Px(1.0), ..default() },
+                        BackgroundColor(Color::srgba(1.0, 0.85, 0.4, 0.3)),
+                    ));
+
+                    // Starlight display
+                    panel.spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        ..default()
+                    }).with_children(|section| {
+                        section.spawn((
+                            Text::new(starlight_line(&ascension)),
+                            TextFont { font_size: 18.0, ..default() },
+                            TextColor(Color::srgb(1.0, 0.95, 0.8)),
+                            StarlightText,
+                        ));
+
+                        let starlight_msg = if pending > 0 {
+                            format!("Ascending now would grant +{} Starlight", pending)
+                        } else {
+                            format!(
+                                "Complete the enlightenment tree to ascend ({}/{})",
+                                settled, total
+                            )
+                        };
+                        section.spawn((
+                            Text::new(starlight_msg),
+                            TextFont { font_size: 14.0, ..default() },
+                            TextColor(Color::srgba(0.9, 0.8, 0.6, 0.8)),
+                        ));
+
+                        section.spawn((
+                            Text::new("Ascension resets insight, enlightenments, transcendences, your school and content layers, then starts a new run.\nStarlight and ascension upgrades are permanent."),
+                            TextFont { font_size: 13.0, ..default() },
+                            TextColor(Color::srgba(0.7, 0.65, 0.55, 0.6)),
+                        ));
+                    });
+
+                    // Ascend button (only if pending > 0)
+                    if pending > 0 {
+                        panel.spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(24.0), Val::Px(10.0)),
+                                border_radius: BorderRadius::all(Val::Px(4.0)),
+                                align_self: AlignSelf::Center,
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgba(0.9, 0.7, 0.2, 0.9)),
+                            AscendButton,
+                        )).with_children(|btn| {
+                            btn.spawn((
+                                Text::new(format!("Ascend (+{} Starlight)", pending)),
+                                TextFont { font_size: 18.0, ..default() },
+                                TextColor(Color::srgb(1.0, 1.0, 1.0)),
+                            ));
+                        });
+                    }
+
+                    // Divider
+                    panel.spawn((
+                        Node { width: Val::Percent(100.0), height: Val::Px(1.0), ..default() },
+                        BackgroundColor(Color::srgba(1.0, 0.85, 0.4, 0.15)),
+                    ));
+
+                    panel.spawn((
+                        Text::new("Ascension Upgrades"),
+                        TextFont { font_size: 20.0, ..default() },
+                        TextColor(Color::srgb(1.0, 0.95, 0.8)),
+                    ));
+
+                    // Upgrade items
+                    panel
+                        .spawn((
+                            Node {
+                                width: Val::Percent(100.0),
+                                flex_direction: FlexDirection::Column,
+                                row_gap: Val::Px(8.0),
+                                ..default()
+                            },
+                            AscensionUpgradeList,
+                        ))
+                        .with_children(|list| spawn_ascension_upgrades(list, &ascension, &content));
+
+                    // Footer
+                    panel.spawn((
+                        Node { width: Val::Percent(100.0), height: Val::Px(1.0), margin: UiRect::top(Val::Px(8.0)), ..default() },
+                        BackgroundColor(Color::srgba(1.0, 0.85, 0.4, 0.15)),
+                    ));
+                    panel.spawn((
+                        Text::new("Press [N] to close"),
+                        TextFont { font_size: 14.0, ..default() },
+                        TextColor(Color::srgba(0.7, 0.65, 0.55, 0.5)),
+                    ));
+                });
+        });
+}
+
+/// Redraw the upgrade list and the Starlight line after a purchase
+pub fn rebuild_ascension_panel(
+    mut commands: Commands,
+    ascension: Res<AscensionState>,
+    content: Res<GameContent>,
+    list_query: Query<Entity, With<AscensionUpgradeList>>,
+    mut starlight_text: Query<&mut Text, With<StarlightText>>,
+) {
+    if !ascension.is_changed() {
+        return;
+    }
+
+    for mut text in &mut starlight_text {
+        **text = starlight_line(&ascension);
+    }
+
+    for list in &list_query {
+        commands.entity(list).despawn_related::<Children>();
+        commands
+            .entity(list)
+            .with_children(|list: &mut ChildSpawnerCommands| {
+                spawn_ascension_upgrades(list, &ascension, &content);
+            });
+    }
+}
+
+pub fn close_ascension_ui(mut commands: Commands, panels: Query<Entity, With<AscensionPanel>>) {
+    for entity in &panels {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn handle_ascend_click(
+    interactions: Query<&Interaction, (Changed<Interaction>, With<AscendButton>)>,
+    mut ascension: ResMut<AscensionState>,
+    mut transcendence: ResMut<TranscendenceState>,
+    mut school: ResMut<SchoolState>,
+    mut layers: ResMut<LayerState>,
+    mut ledger: ResMut<WisdomLedger>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut ascended: MessageWriter<Ascended>,
+    content: Res<GameContent>,
+) {
+    for interaction in &interactions {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let gained = pending_starlight(&transcendence, &content);
+        if gained == 0 {
+            continue;
+        }
+
+        ascension.starlight += gained;
+        ascension.total_ascensions += 1;
+        *transcendence = TranscendenceState {
+            insight: ascension.starting_insight(),
+            ..default()
+        };
+        *school = SchoolState::default();
+        *layers = LayerState::default();
+        ledger.start_run();
+        ascended.write(Ascended {
+            starlight_gained: gained,
+        });
+
+        // Same as transcending: the run itself resets when a school is chosen
+        next_state.set(GameState::SchoolSelection);
+    }
+}
+
+pub fn handle_ascension_upgrade_buy(
+    interactions: Query<(&Interaction, &AscensionUpgradeButton), Changed<Interaction>>,
+    mut ascension: ResMut<AscensionState>,
+    content: Res<GameContent>,
+) {
+    for (interaction, button) in &interactions {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if ascension.has(button.0) || !ascension.can_unlock(button.0, &content) {
+            continue;
+        }
+
+        let cost = content.ascension_upgrade(button.0).cost;
+        if ascension.starlight < cost {
+            continue;
+        }
+
+        ascension.starlight -= cost;
+        ascension.purchased_upgrades.push(button.0);
+    }
+}
+
+// ========== UI HELPERS ==========
+
+fn starlight_line(ascension: &AscensionState) -> String {
+    format!(
+        "Starlight: {}  |  Ascensions: {}",
+        ascension.starlight, ascension.total_ascensions
+    )
+}
+
+/// One row per upgrade: name, description and prerequisites beside its buy button
+fn spawn_ascension_upgrades(
+    list: &mut ChildSpawnerCommands,
+    ascension: &AscensionState,
+    content: &GameContent,
+) {
+    for id in AscensionUpgradeId::ALL {
+        let def = content.ascension_upgrade(id);
+        let owned = ascension.has(id);
+        let unlocked = ascension.can_unlock(id, content);
+        let affordable = ascension.starlight >= def.cost;
+
+        list.spawn(Node {
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(8.0)),
+            column_gap: Val::Px(12.0),
+            border_radius: BorderRadius::all(Val::Px(4.0)),
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn(Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                flex_grow: 1.0,
+                ..default()
+            })
+            .with_children(|info| {
+                let name_color = if owned || !unlocked {
+                    Color::srgba(0.7, 0.65, 0.55, 0.5)
+                } else {
+                    Color::srgb(0.95, 0.9, 0.8)
+                };
+                info.spawn((
+                    Text::new(def.name.clone()),
+                    TextFont { font_size: 18.0, ..default() },
+                    TextColor(name_color),
+                ));
+                info.spawn((
+                    Text::new(def.description.clone()),
+                    TextFont { font_size: 13.0, ..default() },
+                    TextColor(Color::srgba(0.7, 0.65, 0.55, 0.7)),
+                ));
+                if !owned && !def.requires.is_empty() {
+                    let names: Vec<&str> = def
+                        .requires
+                        .iter()
+                        .map(|&req| content.ascension_upgrade(req).name.as_str())
+                        .collect();
+                    let color = if unlocked {
+                        Color::srgba(0.5, 0.9, 0.5, 0.6)
+                    } else {
+                        Color::srgba(0.9, 0.5, 0.4, 0.7)
+                    };
+                    info.spawn((
+                        Text::new(format!("Requires {}", names.join(", "))),
+                        TextFont { font_size: 12.0, ..default() },
+                        TextColor(color),
+                    ));
+                }
+            });
+
+            let (btn_bg, btn_text_color, label) = if owned {
+                (
+                    Color::srgba(0.2, 0.5, 0.25, 0.6),
+                    Color::srgb(0.4, 0.9, 0.5),
+                    "Owned".to_string(),
+                )
+            } else if !unlocked {
+                (
+                    Color::srgba(0.25, 0.22, 0.18, 0.5),
+                    Color::srgba(0.5, 0.45, 0.4, 0.5),
+                    "Locked".to_string(),
+                )
+            } else if affordable {
+                (
+                    Color::srgba(0.9, 0.7, 0.2, 0.9),
+                    Color::srgb(1.0, 1.0, 1.0),
+                    format!("{} Starlight", def.cost),
+                )
+            } else {
+                (
+                    Color::srgba(0.4, 0.35, 0.25, 0.5),
+                    Color::srgba(0.6, 0.55, 0.45, 0.5),
+                    format!("{} Starlight", def.cost),
+                )
+            };
+
+            row.spawn((
+                Button,
+                Node {
+                    padding: UiRect::axes(Val::Px(14.0), Val::Px(6.0)),
+                    border_radius: BorderRadius::all(Val::Px(4.0)),
+                    justify_content: JustifyContent::Center,
+                    min_width: Val::Px(100.0),
+                    ..default()
+                },
+                BackgroundColor(btn_bg),
+                AscensionUpgradeButton(id),
+            ))
+            .with_children(|btn| {
+                btn.spawn((
+                    Text::new(label),
+                    TextFont { font_size: 14.0, ..default() },
+                    TextColor(btn_text_color),
+                ));
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::layers::ContentLayer;
+    use crate::gameplay::schools::{SchoolChoiceButton, SchoolOfThought};
+    use crate::gameplay::testing::TestApp;
+    use crate::gameplay::transcendence::EnlightenmentId;
+
+    /// Every enlightenment but the one its exclusive choice closed off
+    fn fully_enlightened(transcendences: u32) -> TranscendenceState {
+        TranscendenceState {
+            insight: 7,
+            total_transcendences: transcendences,
+            ranks: EnlightenmentId::ALL
+                .into_iter()
+                .filter(|&id| id != EnlightenmentId::ClarityAffinity)
+                .map(|id| (id, 1))
+                .collect(),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn starlight_needs_a_complete_tree() {
+        let content = GameContent::default();
+        let mut transcendence = fully_enlightened(10);
+        assert_eq!(pending_starlight(&transcendence, &content), 3);
+        transcendence.ranks.remove(&EnlightenmentId::Transcendent);
+        assert_eq!(pending_starlight(&transcendence, &content), 0);
+    }
+
+    #[test]
+    fn upgrades_discount_enlightenments_and_layers() {
+        let mut ascension = AscensionState::default();
+        assert_eq!(ascension.enlightenment_cost(15), 15);
+        assert_eq!(ascension.layer_requirement(5), 5);
+        ascension.purchased_upgrades = AscensionUpgradeId::ALL.to_vec();
+        assert_eq!(ascension.enlightenment_cost(15), 12);
+        assert_eq!(ascension.enlightenment_cost(1), 1);
+        assert_eq!(ascension.layer_requirement(5), 3);
+        assert_eq!(ascension.insight_gain(5), 7);
+    }
+
+    #[test]
+    fn ascending_resets_the_prestige_loop_for_starlight() {
+        let mut game = TestApp::new();
+        *game.resource_mut::<TranscendenceState>() = fully_enlightened(9);
+        game.resource_mut::<SchoolState>().active = SchoolOfThought::Stoicism;
+        game.resource_mut::<LayerState>().unlock(ContentLayer::Astral);
+        game.resource_mut::<AscensionState>().purchased_upgrades =
+            vec![AscensionUpgradeId::SeedOfInsight];
+
+        game.set_state(GameState::AscensionOpen);
+        game.click_button::<AscendButton>(|_| true);
+        assert_eq!(game.state(), GameState::SchoolSelection);
+
+        let ascension = game.resource::<AscensionState>();
+        assert_eq!((ascension.starlight, ascension.total_ascensions), (3, 1));
+        let transcendence = game.resource::<TranscendenceState>();
+        assert_eq!(transcendence.insight, 10);
+        assert_eq!(transcendence.total_transcendences, 0);
+        assert!(transcendence.ranks.is_empty());
+        assert!(!game.resource::<LayerState>().has(ContentLayer::Astral));
+
+        game.click_button::<SchoolChoiceButton>(|b| b.0 == SchoolOfThought::Mysticism);
+        assert_eq!(game.state(), GameState::Playing);
+        assert_eq!(game.resource::<SchoolState>().active, SchoolOfThought::Mysticism);
+
+        // Spend the starlight on an upgrade that changes the modifiers; it
+        // stays locked until its prerequisite is bought
+        game.set_state(GameState::AscensionOpen);
+        let celestial = |b: &AscensionUpgradeButton| b.0 == AscensionUpgradeId::CelestialMind;
+        game.click_button::<AscensionUpgradeButton>(celestial);
+        assert!(!game.resource::<AscensionState>().has(AscensionUpgradeId::CelestialMind));
+        game.click_button::<AscensionUpgradeButton>(|b| b.0 == AscensionUpgradeId::InsightWell);
+        game.click_button::<AscensionUpgradeButton>(celestial);
+        let ascension = game.resource::<AscensionState>();
+        assert!(ascension.has(AscensionUpgradeId::CelestialMind));
+        assert_eq!(ascension.starlight, 0);
+    }
+
+    #[test]
+    fn upgrades_unlock_after_their_prerequisites() {
+        let content = GameContent::default();
+        let mut ascension = AscensionState::default();
+        for id in AscensionUpgradeId::ALL {
+            let root = content.ascension_upgrade(id).requires.is_empty();
+            assert_eq!(ascension.can_unlock(id, &content), root, "{:?}", id);
+        }
+        ascension.purchased_upgrades = vec![AscensionUpgradeId::InsightWell];
+        assert!(ascension.can_unlock(AscensionUpgradeId::CelestialMind, &content));
+        assert!(!ascension.can_unlock(AscensionUpgradeId::StarlitTruths, &content));
+    }
+}
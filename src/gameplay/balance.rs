@@ -0,0 +1,305 @@
+use super::content::{ContentError, GameContent};
+use super::generators::GeneratorType;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Loaded from the `assets` folder, relative to the asset root
+pub const BALANCE_PATH: &str = "content/game.balance.json";
+
+/// The same file, compiled in for headless runs and as the starting values
+const BUILT_IN: &str = include_str!("../../assets/content/game.balance.json");
+
+// ========== BALANCE ==========
+
+/// Tuning numbers shared by the live systems and `EconomyModel`.
+/// Kept on `GameContent` and swapped whenever the balance file changes.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, PartialEq)]
+pub struct Balance {
+    pub acolytes: AcolyteBalance,
+    /// Each generator costs this much more than the last of its tier
+    pub generator_cost_growth: f64,
+    pub offline: OfflineBalance,
+    pub deep_focus: DeepFocusBalance,
+    pub synergies: Vec<SynergyLink>,
+    /// Sorted by `owned`; a generator gets the highest milestone it has reached
+    pub milestones: Vec<Milestone>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct AcolyteBalance {
+    /// Wisdom/sec per acolyte
+    pub base_rate: f32,
+    pub base_cost: u64,
+    pub cost_growth: f64,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct OfflineBalance {
+    /// Longest absence that still earns anything
+    pub max_secs: f64,
+    /// Share of live production earned while away
+    pub rate: f64,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct DeepFocusBalance {
+    pub duration_secs: f32,
+    /// Counted from activation, so it includes the active time
+    pub cooldown_secs: f32,
+    pub click_multiplier: f64,
+}
+
+/// Owning units of `source` boosts `target`'s production
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct SynergyLink {
+    pub source: GeneratorType,
+    pub target: GeneratorType,
+    /// Bonus per source unit owned (e.g., 0.02 = +2% per unit)
+    pub bonus_per_unit: f64,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Milestone {
+    pub owned: u32,
+    pub multiplier: f64,
+}
+
+impl Default for Balance {
+    fn default() -> Self {
+        Self::parse(BUILT_IN.as_bytes()).expect("built-in balance is valid")
+    }
+}
+
+impl Balance {
+    /// Parse and validate a balance file
+    pub fn parse(bytes: &[u8]) -> Result<Self, ContentError> {
+        let balance: Self = serde_json::from_slice(bytes).map_err(ContentError::Parse)?;
+        let errors = balance.validate();
+        if errors.is_empty() {
+            Ok(balance)
+        } else {
+            Err(ContentError::Invalid(errors))
+        }
+    }
+
+    /// Production multiplier for owning `owned` of one generator
+    pub fn milestone_multiplier(&self, owned: u32) -> f64 {
+        self.milestones
+            .iter()
+            .take_while(|m| owned >= m.owned)
+            .last()
+            .map_or(1.0, |m| m.multiplier)
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let acolytes = &self.acolytes;
+        if !(acolytes.base_rate >= 0.0 && acolytes.base_rate.is_finite()) {
+            errors.push("acolytes.base_rate can't be negative".to_string());
+        }
+        if acolytes.base_cost == 0 {
+            errors.push("acolytes.base_cost must be positive".to_string());
+        }
+        if !(acolytes.cost_growth > 1.0 && acolytes.cost_growth.is_finite()) {
+            errors.push("acolytes.cost_growth must be above 1".to_string());
+        }
+        if !(self.generator_cost_growth > 1.0 && self.generator_cost_growth.is_finite()) {
+            errors.push("generator_cost_growth must be above 1".to_string());
+        }
+        if !(self.offline.max_secs >= 0.0 && self.offline.max_secs.is_finite()) {
+            errors.push("offline.max_secs can't be negative".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.offline.rate) {
+            errors.push("offline.rate must be between 0 and 1".to_string());
+        }
+
+        let focus = &self.deep_focus;
+        if !(focus.duration_secs > 0.0 && focus.duration_secs.is_finite()) {
+            errors.push("deep_focus.duration_secs must be positive".to_string());
+        }
+        if !(focus.cooldown_secs >= focus.duration_secs && focus.cooldown_secs.is_finite()) {
+            errors.push("deep_focus.cooldown_secs can't be shorter than the duration".to_string());
+        }
+        if !(focus.click_multiplier >= 1.0 && focus.click_multiplier.is_finite()) {
+            errors.push("deep_focus.click_multiplier must be at least 1".to_string());
+        }
+
+        for link in &self.synergies {
+            if link.source == link.target {
+                errors.push(format!("synergy {:?} can't boost itself", link.source));
+            }
+            if !(link.bonus_per_unit > 0.0 && link.bonus_per_unit.is_finite()) {
+                errors.push(format!(
+                    "synergy {:?} -> {:?} needs a positive bonus",
+                    link.source, link.target
+                ));
+            }
+        }
+
+        let mut last = (0, 1.0);
+        for milestone in &self.milestones {
+            if milestone.owned <= last.0 {
+                errors.push(format!("milestone at {} is out of order", milestone.owned));
+            }
+            if !(milestone.multiplier >= last.1 && milestone.multiplier.is_finite()) {
+                errors.push(format!(
+                    "milestone at {} is weaker than the one before it",
+                    milestone.owned
+                ));
+            }
+            last = (milestone.owned, milestone.multiplier);
+        }
+        errors
+    }
+}
+
+// ========== LOADING ==========
+
+#[derive(Default, TypePath)]
+pub struct BalanceLoader;
+
+impl AssetLoader for BalanceLoader {
+    type Asset = Balance;
+    type Settings = ();
+    type Error = ContentError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Balance, ContentError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(ContentError::Io)?;
+        Balance::parse(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["balance.json"]
+    }
+}
+
+/// Keeps the balance asset loaded
+#[derive(Resource)]
+pub struct BalanceHandle(pub Handle<Balance>);
+
+pub fn load_balance(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(BalanceHandle(asset_server.load(BALANCE_PATH)));
+}
+
+/// Swap in the balance file whenever it loads. Rejected files never reach here,
+/// so the running game keeps its last good numbers.
+pub fn apply_balance(
+    mut events: MessageReader<AssetEvent<Balance>>,
+    handle: Res<BalanceHandle>,
+    assets: Res<Assets<Balance>>,
+    mut content: ResMut<GameContent>,
+) {
+    let updated = events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+            *id == handle.0.id()
+        }
+        _ => false,
+    });
+    if !updated {
+        return;
+    }
+    let Some(loaded) = assets.get(&handle.0) else {
+        return;
+    };
+    if content.balance != *loaded {
+        info!("Balance updated from {}", BALANCE_PATH);
+        content.balance = loaded.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn built_in_json() -> serde_json::Value {
+        serde_json::from_str(BUILT_IN).unwrap()
+    }
+
+    fn parse_errors(json: &serde_json::Value) -> Vec<String> {
+        match Balance::parse(json.to_string().as_bytes()) {
+            Err(ContentError::Invalid(errors)) => errors,
+            other => panic!("expected validation errors, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn built_in_balance_is_valid() {
+        let balance = Balance::default();
+        assert_eq!(balance.acolytes.base_cost, 20);
+        assert_eq!(balance.synergies.len(), 18);
+        assert_eq!(balance.deep_focus.click_multiplier, 3.0);
+    }
+
+    #[test]
+    fn milestones_use_the_highest_reached() {
+        let balance = Balance::default();
+        assert_eq!(balance.milestone_multiplier(4), 1.0);
+        assert_eq!(balance.milestone_multiplier(5), 1.5);
+        assert_eq!(balance.milestone_multiplier(49), 3.0);
+        assert_eq!(balance.milestone_multiplier(500), 5.0);
+    }
+
+    #[test]
+    fn bad_values_are_rejected() {
+        let mut json = built_in_json();
+        json["acolytes"]["cost_growth"] = 1.0.into();
+        json["offline"]["rate"] = 2.0.into();
+        json["deep_focus"]["cooldown_secs"] = 5.0.into();
+        json["milestones"][1]["owned"] = 3.into();
+        json["synergies"][0]["target"] = "Candle".into();
+
+        let errors = parse_errors(&json);
+        assert_eq!(errors.len(), 5, "{:?}", errors);
+        assert!(errors.contains(&"offline.rate must be between 0 and 1".to_string()));
+        assert!(errors.contains(&"synergy Candle can't boost itself".to_string()));
+    }
+
+    #[test]
+    fn reloaded_balance_reaches_the_running_game() {
+        use crate::gameplay::generators::GeneratorState;
+        use crate::gameplay::shop::PurchaseTracker;
+        use crate::gameplay::synergies::{SynergyState, recalculate_synergies};
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<GameContent>()
+            .init_resource::<Assets<Balance>>()
+            .add_message::<AssetEvent<Balance>>()
+            .init_resource::<GeneratorState>()
+            .init_resource::<SynergyState>()
+            .init_resource::<PurchaseTracker>()
+            .add_systems(Update, (apply_balance, recalculate_synergies).chain());
+        let mut tuned = Balance::default();
+        tuned.milestones[0].multiplier = 1.8;
+        let handle = app.world_mut().resource_mut::<Assets<Balance>>().add(tuned);
+        let id = handle.id();
+        app.insert_resource(BalanceHandle(handle));
+        app.world_mut().resource_mut::<GeneratorState>().owned[0] = 5;
+        app.update();
+        assert_eq!(app.world().resource::<SynergyState>().milestone_mult[0], 1.5);
+
+        app.world_mut().write_message(AssetEvent::Modified { id });
+        app.update();
+
+        let synergies = app.world().resource::<SynergyState>();
+        assert_eq!(synergies.milestone_mult[0], 1.8);
+    }
+
+    #[test]
+    fn missing_fields_fail_to_parse() {
+        let mut json = built_in_json();
+        json.as_object_mut().unwrap().remove("offline");
+        assert!(matches!(
+            Balance::parse(json.to_string().as_bytes()),
+            Err(ContentError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,528 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Mantissa/exponent number for wisdom, AFP and costs.
+/// Value = mantissa * 10^exponent. Below 1e300 the exponent stays 0, so everyday
+/// values keep plain f64 precision (whole AFP stays exact); past that the exponent
+/// grows in steps of `CHUNK` and the mantissa stays in [1, 1e300).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BigNum {
+    mantissa: f64,
+    exponent: i64,
+}
+
+/// Decimal digits moved between mantissa and exponent at a time
+const CHUNK: i64 = 300;
+const CHUNK_SCALE: f64 = 1e300;
+
+/// Below this magnitude a value is printed as a plain number
+const PLAIN_LIMIT: f64 = 1e15;
+
+impl BigNum {
+    pub const ZERO: BigNum = BigNum {
+        mantissa: 0.0,
+        exponent: 0,
+    };
+
+    /// mantissa * 10^exponent for any decimal exponent
+    pub fn new(mantissa: f64, exponent: i64) -> Self {
+        if mantissa == 0.0 || !mantissa.is_finite() {
+            return Self::ZERO;
+        }
+        if exponent <= 0 {
+            return Self::normalized(mantissa * 10f64.powi(exponent.max(-400) as i32), 0);
+        }
+        let rest = exponent.rem_euclid(CHUNK);
+        Self::normalized(mantissa * 10f64.powi(rest as i32), exponent - rest)
+    }
+
+    /// Brings the mantissa back into range; `exponent` must be a multiple of `CHUNK`
+    fn normalized(mut mantissa: f64, mut exponent: i64) -> Self {
+        if mantissa == 0.0 || mantissa.is_nan() {
+            return Self::ZERO;
+        }
+        if mantissa.is_infinite() {
+            // Only reachable from f64 overflow; clamp to the largest f64
+            mantissa = f64::MAX.copysign(mantissa);
+        }
+        while mantissa.abs() >= CHUNK_SCALE {
+            mantissa /= CHUNK_SCALE;
+            exponent += CHUNK;
+        }
+        while exponent > 0 && mantissa.abs() < 1.0 {
+            mantissa *= CHUNK_SCALE;
+            exponent -= CHUNK;
+        }
+        if exponent < 0 {
+            return Self::ZERO;
+        }
+        Self { mantissa, exponent }
+    }
+
+    /// 10^x for an arbitrary (possibly huge) real exponent
+    pub fn from_log10(x: f64) -> Self {
+        if !x.is_finite() {
+            return Self::ZERO;
+        }
+        let e = (x / CHUNK as f64).floor() as i64 * CHUNK;
+        Self::normalized(10f64.powf(x - e as f64), e)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0.0
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.mantissa > 0.0
+    }
+
+    /// Lossy conversion; saturates to +/-infinity beyond f64 range
+    pub fn to_f64(self) -> f64 {
+        if self.exponent == 0 {
+            self.mantissa
+        } else {
+            f64::INFINITY.copysign(self.mantissa)
+        }
+    }
+
+    /// log10 of the value (negative infinity for zero or negatives)
+    pub fn log10(&self) -> f64 {
+        if self.mantissa <= 0.0 {
+            f64::NEG_INFINITY
+        } else {
+            self.mantissa.log10() + self.exponent as f64
+        }
+    }
+
+    pub fn floor(self) -> Self {
+        if self.exponent > 0 {
+            self
+        } else {
+            Self::from(self.mantissa.floor())
+        }
+    }
+
+    pub fn ceil(self) -> Self {
+        if self.exponent > 0 {
+            self
+        } else {
+            Self::from(self.mantissa.ceil())
+        }
+    }
+
+    pub fn sqrt(self) -> Self {
+        if self.mantissa <= 0.0 {
+            return Self::ZERO;
+        }
+        Self::new(self.mantissa.sqrt(), self.exponent / 2)
+    }
+
+    /// Integer power, exact in f64 range and log-based beyond it
+    pub fn powi(self, n: i32) -> Self {
+        let direct = self.to_f64().powi(n);
+        if direct.is_finite() && direct != 0.0 {
+            return Self::from(direct);
+        }
+        if self.mantissa <= 0.0 {
+            return Self::ZERO;
+        }
+        Self::from_log10(self.log10() * n as f64)
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        if other > self { other } else { self }
+    }
+
+    /// Saturating conversion for counters like insight
+    pub fn to_u32(self) -> u32 {
+        self.to_f64() as u32
+    }
+
+    /// Decimal mantissa in [1, 10) and exponent, rounded to `precision` digits
+    fn scientific(&self, precision: usize) -> (f64, i64) {
+        let log = self.mantissa.abs().log10() + self.exponent as f64;
+        let mut exp = log.floor() as i64;
+        let scale = 10f64.powi(precision as i32);
+        let mut mant = (10f64.powf(log - exp as f64) * scale).round() / scale;
+        if mant >= 10.0 {
+            mant /= 10.0;
+            exp += 1;
+        }
+        (mant.copysign(self.mantissa), exp)
+    }
+
+    /// Compact label for HUD and shop: 1234, 12.3K, 4.5M, 6.7B, 8.9T, then 1.23e15
+    pub fn format_short(&self) -> String {
+        let v = self.to_f64();
+        if v >= PLAIN_LIMIT {
+            format!("{:.2}", self)
+        } else if v >= 1e12 {
+            format!("{:.1}T", v / 1e12)
+        } else if v >= 1e9 {
+            format!("{:.1}B", v / 1e9)
+        } else if v >= 1e6 {
+            format!("{:.1}M", v / 1e6)
+        } else if v >= 1e4 {
+            format!("{:.1}K", v / 1e3)
+        } else {
+            format!("{:.0}", v)
+        }
+    }
+}
+
+impl From<f64> for BigNum {
+    fn from(value: f64) -> Self {
+        Self::normalized(value, 0)
+    }
+}
+
+impl From<f32> for BigNum {
+    fn from(value: f32) -> Self {
+        Self::normalized(value as f64, 0)
+    }
+}
+
+impl From<u64> for BigNum {
+    fn from(value: u64) -> Self {
+        Self::normalized(value as f64, 0)
+    }
+}
+
+impl From<u32> for BigNum {
+    fn from(value: u32) -> Self {
+        Self::normalized(value as f64, 0)
+    }
+}
+
+// ========== ARITHMETIC ==========
+
+impl Neg for BigNum {
+    type Output = BigNum;
+    fn neg(self) -> BigNum {
+        Self {
+            mantissa: -self.mantissa,
+            exponent: self.exponent,
+        }
+    }
+}
+
+impl Add for BigNum {
+    type Output = BigNum;
+    fn add(self, rhs: BigNum) -> BigNum {
+        if self.is_zero() {
+            return rhs;
+        }
+        if rhs.is_zero() {
+            return self;
+        }
+        let (big, small) = if self.exponent >= rhs.exponent {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        match big.exponent - small.exponent {
+            0 => Self::normalized(big.mantissa + small.mantissa, big.exponent),
+            CHUNK => Self::normalized(big.mantissa + small.mantissa / CHUNK_SCALE, big.exponent),
+            // Anything further apart is below f64 precision
+            _ => big,
+        }
+    }
+}
+
+impl Sub for BigNum {
+    type Output = BigNum;
+    fn sub(self, rhs: BigNum) -> BigNum {
+        self + -rhs
+    }
+}
+
+impl Mul for BigNum {
+    type Output = BigNum;
+    fn mul(self, rhs: BigNum) -> BigNum {
+        let exponent = self.exponent + rhs.exponent;
+        let mantissa = self.mantissa * rhs.mantissa;
+        if mantissa.is_finite() {
+            Self::normalized(mantissa, exponent)
+        } else {
+            Self::normalized(self.mantissa / CHUNK_SCALE * rhs.mantissa, exponent + CHUNK)
+        }
+    }
+}
+
+impl BigNum {
+    /// `self / rhs`, or `None` when `rhs` is zero
+    pub fn checked_div(self, rhs: BigNum) -> Option<BigNum> {
+        if rhs.is_zero() {
+            return None;
+        }
+        Some(self / rhs)
+    }
+}
+
+/// Dividing by zero is a bug in the caller: debug builds panic, release builds
+/// yield `ZERO` so a bad ratio can't poison saves with NaN. Use `checked_div`
+/// where a zero divisor is expected.
+impl Div for BigNum {
+    type Output = BigNum;
+    fn div(self, rhs: BigNum) -> BigNum {
+        debug_assert!(!rhs.is_zero(), "BigNum division by zero");
+        if rhs.is_zero() {
+            return Self::ZERO;
+        }
+        let mut exponent = self.exponent - rhs.exponent;
+        let mut mantissa = self.mantissa / rhs.mantissa;
+        while exponent < 0 && mantissa != 0.0 {
+            mantissa /= CHUNK_SCALE;
+            exponent += CHUNK;
+        }
+        Self::normalized(mantissa, exponent)
+    }
+}
+
+impl Mul<f64> for BigNum {
+    type Output = BigNum;
+    fn mul(self, rhs: f64) -> BigNum {
+        self * BigNum::from(rhs)
+    }
+}
+
+impl Div<f64> for BigNum {
+    type Output = BigNum;
+    fn div(self, rhs: f64) -> BigNum {
+        self / BigNum::from(rhs)
+    }
+}
+
+impl AddAssign for BigNum {
+    fn add_assign(&mut self, rhs: BigNum) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for BigNum {
+    fn sub_assign(&mut self, rhs: BigNum) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<f64> for BigNum {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = *self * rhs;
+    }
+}
+
+impl Sum for BigNum {
+    fn sum<I: Iterator<Item = BigNum>>(iter: I) -> BigNum {
+        iter.fold(BigNum::ZERO, |acc, x| acc + x)
+    }
+}
+
+// ========== COMPARISON ==========
+
+impl PartialOrd for BigNum {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let sign = |n: &BigNum| {
+            if n.mantissa > 0.0 {
+                1
+            } else if n.mantissa < 0.0 {
+                -1
+            } else {
+                0
+            }
+        };
+        let (sa, sb) = (sign(self), sign(other));
+        if sa != sb || sa == 0 {
+            return Some(sa.cmp(&sb));
+        }
+        let magnitude = self
+            .exponent
+            .cmp(&other.exponent)
+            .then(self.mantissa.abs().total_cmp(&other.mantissa.abs()));
+        Some(if sa > 0 { magnitude } else { magnitude.reverse() })
+    }
+}
+
+// ========== DISPLAY ==========
+
+impl fmt::Display for BigNum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.exponent == 0 && self.mantissa.abs() < PLAIN_LIMIT {
+            return match f.precision() {
+                Some(p) => write!(f, "{:.*}", p, self.mantissa),
+                None => write!(f, "{}", self.mantissa),
+            };
+        }
+        let precision = f.precision().unwrap_or(2);
+        let (mantissa, exponent) = self.scientific(precision);
+        write!(f, "{:.*}e{}", precision, mantissa, exponent)
+    }
+}
+
+// ========== SERDE ==========
+
+/// Values in f64 range are saved as plain JSON numbers, exactly as before BigNum existed.
+/// Larger values are saved as a "<mantissa>e<exponent>" string.
+impl Serialize for BigNum {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.exponent == 0 {
+            return serializer.serialize_f64(self.mantissa);
+        }
+        let chunk = format!("{:e}", self.mantissa);
+        let (mantissa, exponent) = chunk.split_once('e').unwrap_or((&chunk, "0"));
+        let exponent: i64 = exponent.parse().unwrap_or(0);
+        serializer.serialize_str(&format!("{}e{}", mantissa, exponent + self.exponent))
+    }
+}
+
+struct BigNumVisitor;
+
+impl Visitor<'_> for BigNumVisitor {
+    type Value = BigNum;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a number or a \"<mantissa>e<exponent>\" string")
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<BigNum, E> {
+        Ok(BigNum::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<BigNum, E> {
+        Ok(BigNum::from(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<BigNum, E> {
+        Ok(BigNum::from(v as f64))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<BigNum, E> {
+        v.parse().map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for BigNum {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(BigNumVisitor)
+    }
+}
+
+impl std::str::FromStr for BigNum {
+    type Err = std::num::ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // Anything f64 can hold parses directly, keeping full precision
+        let plain: f64 = s.parse()?;
+        if plain.is_finite() {
+            return Ok(BigNum::from(plain));
+        }
+        match s.rfind(['e', 'E']) {
+            Some(idx) => {
+                // Split the exponent into a chunk and a remainder f64 can parse exactly
+                let exponent: f64 = s[idx + 1..].parse()?;
+                let exponent = exponent as i64;
+                let rest = exponent.rem_euclid(CHUNK);
+                let mantissa: f64 = format!("{}e{}", &s[..idx], rest).parse()?;
+                Ok(BigNum::normalized(mantissa, exponent - rest))
+            }
+            None => Ok(BigNum::from(plain)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Equal to 12 significant digits
+    fn close(a: BigNum, b: BigNum) -> bool {
+        a == b || (a.log10() - b.log10()).abs() < 1e-12
+    }
+
+    #[test]
+    fn values_normalize_into_chunks() {
+        let n = BigNum::new(1.5, 450);
+        assert_eq!(n.exponent, 300);
+        assert!((n.mantissa / 1.5e150 - 1.0).abs() < 1e-12);
+        assert_eq!(BigNum::new(2.0, 0), BigNum::from(2.0));
+        assert!(close(BigNum::new(5.0, -2), BigNum::from(0.05)));
+        assert_eq!(BigNum::new(f64::NAN, 10), BigNum::ZERO);
+        assert!(close(BigNum::from(1e299) * BigNum::from(1e10), BigNum::new(1.0, 309)));
+        assert!((BigNum::from_log10(1000.5).log10() - 1000.5).abs() < 1e-9);
+        // Shrinking back below a chunk returns to plain f64
+        let back = BigNum::new(1.0, 310) / BigNum::from(1e20);
+        assert_eq!(back.exponent, 0);
+        assert!((back.to_f64() - 1e290).abs() / 1e290 < 1e-12);
+    }
+
+    #[test]
+    fn add_and_sub_across_exponent_gaps() {
+        let big = BigNum::new(1.0, 400);
+        assert_eq!(big + BigNum::from(1.0), big);
+        assert_eq!(big + BigNum::new(1.0, 4000), BigNum::new(1.0, 4000));
+        let near = BigNum::new(1.0, 301) + BigNum::new(5.0, 299);
+        assert!(close(near, BigNum::new(1.05, 301)));
+        assert_eq!(big - big, BigNum::ZERO);
+        assert_eq!(BigNum::from(5.0) - BigNum::from(7.0), BigNum::from(-2.0));
+        let sum: BigNum = [1.0, 2.0, 3.0].into_iter().map(BigNum::from).sum();
+        assert_eq!(sum, BigNum::from(6.0));
+    }
+
+    #[test]
+    fn comparison_orders_sign_then_magnitude() {
+        let values = [
+            -BigNum::new(1.0, 500),
+            BigNum::from(-3.0),
+            BigNum::ZERO,
+            BigNum::from(0.5),
+            BigNum::from(1e299),
+            BigNum::new(1.0, 300),
+            BigNum::new(9.0, 900),
+        ];
+        for pair in values.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(BigNum::from(2.0).max(BigNum::from(3.0)), BigNum::from(3.0));
+    }
+
+    #[test]
+    fn formatting_switches_to_scientific() {
+        assert_eq!(BigNum::from(1234.0).format_short(), "1234");
+        assert_eq!(BigNum::from(12_345.0).format_short(), "12.3K");
+        assert_eq!(BigNum::from(4.5e6).format_short(), "4.5M");
+        assert_eq!(BigNum::from(8.9e12).format_short(), "8.9T");
+        assert_eq!(BigNum::from(1.234e15).format_short(), "1.23e15");
+        assert_eq!(BigNum::new(9.999, 400).format_short(), "1.00e401");
+        assert_eq!(format!("{:.1}", BigNum::from(2.25)), "2.2");
+    }
+
+    #[test]
+    fn serde_round_trips_plain_and_huge_values() {
+        for n in [BigNum::ZERO, BigNum::from(42.5), BigNum::new(3.25, 1234)] {
+            let json = serde_json::to_string(&n).unwrap();
+            let back: BigNum = serde_json::from_str(&json).unwrap();
+            assert!(close(back, n), "{}", json);
+        }
+        assert_eq!(serde_json::to_string(&BigNum::from(7.0)).unwrap(), "7.0");
+        assert_eq!(serde_json::from_str::<BigNum>("12").unwrap(), BigNum::from(12.0));
+        assert!(serde_json::from_str::<BigNum>("\"lots\"").is_err());
+    }
+
+    #[test]
+    fn division_by_zero_is_explicit() {
+        assert_eq!(BigNum::from(4.0).checked_div(BigNum::ZERO), None);
+        assert_eq!(BigNum::from(4.0).checked_div(BigNum::from(2.0)), Some(BigNum::from(2.0)));
+        assert!(close(BigNum::new(1.0, 600) / BigNum::new(1.0, 300), BigNum::new(1.0, 300)));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "division by zero")]
+    fn division_by_zero_panics_in_debug() {
+        let _ = BigNum::from(1.0) / BigNum::ZERO;
+    }
+}
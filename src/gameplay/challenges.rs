@@ -1,5 +1,10 @@
-use super::generators::GeneratorState;
+use super::acolytes::{AcolyteState, AcolyteSummoned};
+use super::content::GameContent;
+use super::generators::{GeneratorPurchased, GeneratorState};
+use super::input::PlayerInput;
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
 use super::state::GameState;
+use super::wisdom::TruthGenerated;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -25,33 +30,6 @@ impl ChallengeId {
         Self::Solitude,
     ];
 
-    pub fn name(&self) -> &'static str {
-        match self {
-            Self::Silence => "Silence",
-            Self::Blindfold => "Blindfold",
-            Self::Austerity => "Austerity",
-            Self::Solitude => "Solitude",
-        }
-    }
-
-    pub fn description(&self) -> &'static str {
-        match self {
-            Self::Silence => "Own no generators for 10 minutes.",
-            Self::Blindfold => "Do not click the orb for 5 minutes.",
-            Self::Austerity => "Endure double wisdom scaling for 15 minutes.",
-            Self::Solitude => "Generate 5 truths with zero acolytes.",
-        }
-    }
-
-    pub fn reward_description(&self) -> &'static str {
-        match self {
-            Self::Silence => "+5% all wisdom production",
-            Self::Blindfold => "+10% passive generation",
-            Self::Austerity => "+8% click wisdom",
-            Self::Solitude => "+5% AFP earned per truth",
-        }
-    }
-
     pub fn color(&self) -> Color {
         match self {
             Self::Silence => Color::srgb(0.4, 0.7, 0.9),
@@ -60,21 +38,23 @@ impl ChallengeId {
             Self::Solitude => Color::srgb(0.3, 0.8, 0.5),
         }
     }
+}
 
+/// A challenge's text and length, from the content file
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChallengeDef {
+    pub id: ChallengeId,
+    pub name: String,
+    pub description: String,
+    pub reward: String,
     /// Duration in seconds for timed challenges (None for goal-based)
-    fn required_duration(&self) -> Option<f32> {
-        match self {
-            Self::Silence => Some(600.0),
-            Self::Blindfold => Some(300.0),
-            Self::Austerity => Some(900.0),
-            Self::Solitude => None,
-        }
-    }
+    #[serde(default)]
+    pub duration_secs: Option<f32>,
 }
 
 // ========== STATE ==========
 
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Clone)]
 pub struct ChallengeState {
     /// Challenges completed (permanent)
     pub completed: Vec<ChallengeId>,
@@ -82,7 +62,7 @@ pub struct ChallengeState {
     pub active: Option<ActiveChallenge>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActiveChallenge {
     pub id: ChallengeId,
     /// Time elapsed since challenge started
@@ -111,6 +91,16 @@ impl ChallengeState {
         self.active.is_some()
     }
 
+    /// Ends the active challenge as a success and records its reward
+    pub fn complete_active(&mut self) -> Option<ChallengeCompleted> {
+        let id = self.active.take()?.id;
+        let first_time = !self.has_completed(id);
+        if first_time {
+            self.completed.push(id);
+        }
+        Some(ChallengeCompleted { id, first_time })
+    }
+
     /// Permanent passive generation multiplier from completed challenges
     pub fn passive_multiplier(&self) -> f32 {
         let mut mult = 1.0;
@@ -150,15 +140,31 @@ impl ChallengeState {
     }
 }
 
+impl ModifierSource for ChallengeState {
+    fn contribute(&self, _content: &GameContent, modifiers: &mut WisdomModifiers) {
+        modifiers.mul("challenges", ModifierChannel::Click, self.click_multiplier() as f64);
+        modifiers.mul("challenges", ModifierChannel::Passive, self.passive_multiplier() as f64);
+        modifiers.mul("challenges", ModifierChannel::Afp, self.afp_multiplier() as f64);
+    }
+}
+
+/// A challenge was finished successfully
+#[derive(Message)]
+pub struct ChallengeCompleted {
+    pub id: ChallengeId,
+    /// False when the reward was already earned by an earlier completion
+    pub first_time: bool,
+}
+
 // ========== SYSTEMS ==========
 
 /// Enforces challenge constraints and tracks progress
 pub fn update_challenges(
     mut challenges: ResMut<ChallengeState>,
-    mouse: Res<ButtonInput<MouseButton>>,
-    generators: Res<GeneratorState>,
+    input: Res<PlayerInput>,
+    content: Res<GameContent>,
+    mut completed: MessageWriter<ChallengeCompleted>,
     time: Res<Time>,
-    interactions: Query<&Interaction>,
 ) {
     let Some(ref mut active) = challenges.active else {
         return;
@@ -173,20 +179,12 @@ pub fn update_challenges(
     // Check constraint violations
     match active.id {
         ChallengeId::Silence => {
-            let total_gens: u32 = generators.owned.iter().sum();
-            if total_gens > 0 {
-                active.failed = true;
-            }
+            // Tracked via fail_silence_on_purchase below
         }
         ChallengeId::Blindfold => {
-            if mouse.just_pressed(MouseButton::Left) {
-                // Only fail if not clicking UI buttons
-                let clicking_ui = interactions
-                    .iter()
-                    .any(|i| *i == Interaction::Pressed);
-                if !clicking_ui {
-                    active.failed = true;
-                }
+            // Orb clicks only; clicks on UI buttons don't count
+            if input.orb_clicks > 0 {
+                active.failed = true;
             }
         }
         ChallengeId::Austerity => {
@@ -198,53 +196,63 @@ pub fn update_challenges(
     }
 
     // Check timed completion
-    if let Some(duration) = active.id.required_duration() {
+    if let Some(duration) = content.challenge(active.id).duration_secs {
         if active.elapsed >= duration && !active.failed {
-            let id = active.id;
-            challenges.active = None;
-            if !challenges.has_completed(id) {
-                challenges.completed.push(id);
+            if let Some(done) = challenges.complete_active() {
+                completed.write(done);
             }
         }
     }
 }
 
+/// Fails Silence on any generator purchase; runs in every state since
+/// generators are bought with the shop open
+pub fn fail_silence_on_purchase(
+    mut challenges: ResMut<ChallengeState>,
+    mut purchases: MessageReader<GeneratorPurchased>,
+) {
+    if purchases.read().count() == 0 {
+        return;
+    }
+
+    if let Some(ref mut active) = challenges.active {
+        if active.id == ChallengeId::Silence {
+            active.failed = true;
+        }
+    }
+}
+
 /// Tracks truth generation for the Solitude challenge
 pub fn track_solitude_progress(
     mut challenges: ResMut<ChallengeState>,
-    wisdom: Res<super::wisdom::WisdomMeter>,
-    acolytes: Res<super::acolytes::AcolyteState>,
-    mut last_truths: Local<u32>,
+    mut truth_messages: MessageReader<TruthGenerated>,
+    mut summoned: MessageReader<AcolyteSummoned>,
+    mut completed: MessageWriter<ChallengeCompleted>,
 ) {
+    let truths = truth_messages.read().filter(|t| t.is_meter_truth()).count() as u32;
+    let summons = summoned.read().count();
+
     let Some(ref mut active) = challenges.active else {
-        *last_truths = wisdom.truths_generated;
         return;
     };
 
     if active.id != ChallengeId::Solitude || active.failed {
-        *last_truths = wisdom.truths_generated;
         return;
     }
 
-    // Fail if acolytes were summoned
-    if acolytes.count > 0 {
+    // Fail if an acolyte was summoned
+    if summons > 0 {
         active.failed = true;
-        *last_truths = wisdom.truths_generated;
         return;
     }
 
     // Track truths earned during challenge
-    if wisdom.truths_generated > *last_truths {
-        active.progress += wisdom.truths_generated - *last_truths;
-    }
-    *last_truths = wisdom.truths_generated;
+    active.progress += truths;
 
     // Check completion
     if active.progress >= 5 {
-        let id = active.id;
-        challenges.active = None;
-        if !challenges.has_completed(id) {
-            challenges.completed.push(id);
+        if let Some(done) = challenges.complete_active() {
+            completed.write(done);
         }
     }
 }
@@ -282,7 +290,11 @@ pub struct ChallengesPanel;
 #[derive(Component)]
 pub struct ChallengeButton(pub ChallengeId);
 
-pub fn open_challenges(mut commands: Commands, challenges: Res<ChallengeState>) {
+pub fn open_challenges(
+    mut commands: Commands,
+    challenges: Res<ChallengeState>,
+    content: Res<GameContent>,
+) {
     commands
         .spawn((
             Node {
@@ -332,6 +344,7 @@ pub fn open_challenges(mut commands: Commands, challenges: Res<ChallengeState>)
 
                     // Challenge cards
                     for id in ChallengeId::ALL {
+                        let def = content.challenge(id);
                         let completed = challenges.has_completed(id);
                         let challenge_color = id.color();
 
@@ -360,17 +373,17 @@ pub fn open_challenges(mut commands: Commands, challenges: Res<ChallengeState>)
                                     challenge_color
                                 };
                                 info.spawn((
-                                    Text::new(id.name()),
+                                    Text::new(def.name.clone()),
                                     TextFont { font_size: 18.0, ..default() },
                                     TextColor(name_color),
                                 ));
                                 info.spawn((
-                                    Text::new(id.description()),
+                                    Text::new(def.description.clone()),
                                     TextFont { font_size: 13.0, ..default() },
                                     TextColor(Color::srgba(0.7, 0.65, 0.75, 0.7)),
                                 ));
                                 info.spawn((
-                                    Text::new(format!("Reward: {}", id.reward_description())),
+                                    Text::new(format!("Reward: {}", def.reward)),
                                     TextFont { font_size: 12.0, ..default() },
                                     TextColor(Color::srgba(0.5, 0.9, 0.5, 0.7)),
                                 ));
@@ -429,6 +442,8 @@ pub fn close_challenges(mut commands: Commands, panels: Query<Entity, With<Chall
 pub fn handle_challenge_begin(
     interactions: Query<(&Interaction, &ChallengeButton), Changed<Interaction>>,
     mut challenges: ResMut<ChallengeState>,
+    generators: Res<GeneratorState>,
+    acolytes: Res<AcolyteState>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
     for (interaction, button) in &interactions {
@@ -439,10 +454,16 @@ pub fn handle_challenge_begin(
             continue;
         }
 
+        // Later purchases and summons fail these through their messages
+        let broken = match button.0 {
+            ChallengeId::Silence => generators.owned.iter().sum::<u32>() > 0,
+            ChallengeId::Solitude => acolytes.count > 0,
+            ChallengeId::Blindfold | ChallengeId::Austerity => false,
+        };
         challenges.active = Some(ActiveChallenge {
             id: button.0,
             elapsed: 0.0,
-            failed: false,
+            failed: broken,
             progress: 0,
         });
         next_state.set(GameState::Playing);
@@ -458,6 +479,7 @@ pub struct ChallengeIndicator;
 pub fn render_challenge_indicator(
     mut commands: Commands,
     challenges: Res<ChallengeState>,
+    content: Res<GameContent>,
     existing: Query<Entity, With<ChallengeIndicator>>,
 ) {
     if !challenges.is_changed() {
@@ -471,10 +493,11 @@ pub fn render_challenge_indicator(
     let Some(ref active) = challenges.active else {
         return;
     };
+    let def = content.challenge(active.id);
 
     let status_text = if active.failed {
         "FAILED - Press [C] to cancel".to_string()
-    } else if let Some(duration) = active.id.required_duration() {
+    } else if let Some(duration) = def.duration_secs {
         let remaining = (duration - active.elapsed).max(0.0);
         let mins = (remaining / 60.0) as u32;
         let secs = (remaining % 60.0) as u32;
@@ -506,7 +529,7 @@ pub fn render_challenge_indicator(
         ))
         .with_children(|panel| {
             panel.spawn((
-                Text::new(format!("Challenge: {}", active.id.name())),
+                Text::new(format!("Challenge: {}", def.name)),
                 TextFont { font_size: 14.0, ..default() },
                 TextColor(status_color),
             ));
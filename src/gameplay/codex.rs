@@ -1,3 +1,5 @@
+use super::content::GameContent;
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
 use super::state::GameState;
 use super::wisdom::TruthGenerated;
 use bevy::prelude::*;
@@ -133,7 +135,7 @@ impl TruthCategory {
 
 // ========== TRUTH CODEX RESOURCE ==========
 
-#[derive(Resource, Debug, Default)]
+#[derive(Resource, Debug, Default, Clone)]
 pub struct TruthCodex {
     pub discovered: HashSet<usize>,
     pub completed_categories: Vec<TruthCategory>,
@@ -174,6 +176,14 @@ impl TruthCodex {
     }
 }
 
+impl ModifierSource for TruthCodex {
+    fn contribute(&self, _content: &GameContent, modifiers: &mut WisdomModifiers) {
+        let mult = self.wisdom_multiplier() as f64;
+        modifiers.mul("codex", ModifierChannel::Click, mult);
+        modifiers.mul("codex", ModifierChannel::Passive, mult);
+    }
+}
+
 // ========== SYSTEMS ==========
 
 /// Track truth discoveries from TruthGenerated messages
@@ -0,0 +1,832 @@
+use super::achievements::{AchievementDef, AchievementId};
+use super::ascension::{AscensionUpgradeDef, AscensionUpgradeId};
+use super::balance::Balance;
+use super::bignum::BigNum;
+use super::challenges::{ChallengeDef, ChallengeId};
+use super::generators::{GeneratorDef, GeneratorType};
+use super::layers::{ContentLayer, LayerDef};
+use super::schools::{SchoolDef, SchoolOfThought};
+use super::shop::{PurchaseTracker, ShopCategory, ShopEffect, ShopItem, ShopItemId};
+use super::transcendence::{
+    EnlightenmentDef, EnlightenmentId, DISCOUNT_PER_RANK, MAX_GENERATOR_DISCOUNT,
+};
+use crate::orb::types::EquippedOrb;
+use bevy::asset::io::Reader;
+use bevy::asset::io::file::FileAssetReader;
+use bevy::asset::{AssetLoader, AssetLoadFailedEvent, LoadContext, UntypedAssetId};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Loaded from the `assets` folder, relative to the asset root
+pub const CONTENT_PATH: &str = "content/game.content.json";
+
+/// The same file, compiled in so there is always valid content before the asset
+/// loads, and in headless runs that have no asset server
+const BUILT_IN: &str = include_str!("../../assets/content/game.content.json");
+
+/// Most generators one bulk purchase will buy, so "max" stays cheap to price
+pub const MAX_GENERATOR_BATCH: u32 = 10_000;
+
+// ========== CONTENT ==========
+
+/// Names, costs and numbers for everything the player can buy, unlock or choose.
+/// Shop items are keyed by string and can be added freely. Every other list is
+/// keyed by an enum built into the game, so those files can retune existing
+/// entries but not add new ones; they must list each id once, in enum order, so
+/// lookups are a plain index.
+#[derive(Asset, TypePath, Resource, Deserialize, Debug, Clone)]
+pub struct GameContent {
+    generators: Vec<GeneratorDef>,
+    pub shop_items: Vec<ShopItem>,
+    enlightenments: Vec<EnlightenmentDef>,
+    ascension_upgrades: Vec<AscensionUpgradeDef>,
+    challenges: Vec<ChallengeDef>,
+    achievements: Vec<AchievementDef>,
+    schools: Vec<SchoolDef>,
+    layers: Vec<LayerDef>,
+    /// Comes from its own file; see `balance::apply_balance`
+    #[serde(skip)]
+    pub balance: Balance,
+}
+
+impl Default for GameContent {
+    fn default() -> Self {
+        Self::parse(BUILT_IN.as_bytes()).expect("built-in content is valid")
+    }
+}
+
+impl GameContent {
+    /// Parse and validate a content file
+    pub fn parse(bytes: &[u8]) -> Result<Self, ContentError> {
+        let mut content: Self = serde_json::from_slice(bytes).map_err(ContentError::Parse)?;
+        let errors = content.validate();
+        if errors.is_empty() {
+            Ok(content)
+        } else {
+            Err(ContentError::Invalid(errors))
+        }
+    }
+
+    pub fn generator(&self, id: GeneratorType) -> &GeneratorDef {
+        &self.generators[id as usize]
+    }
+
+    /// Cost of the next generator given how many are owned, with optional discount (0.1 = 10% off)
+    pub fn generator_cost(&self, id: GeneratorType, owned: u32, discount: f64) -> BigNum {
+        let growth = BigNum::from(self.balance.generator_cost_growth).powi(owned as i32);
+        let base = BigNum::from(self.generator(id).base_cost) * growth;
+        (base * (1.0 - discount)).ceil().max(BigNum::from(1.0))
+    }
+
+    /// Cost of the next `count` generators bought together: the geometric sum
+    /// next · (growthⁿ − 1) / (growth − 1), rounded up once for the whole batch.
+    /// Rounding once is deliberate: it keeps the price closed-form for batches of
+    /// thousands. A batch can come to at most `count − 1` AFP less than buying
+    /// one at a time, where each unit is rounded up on its own.
+    pub fn generator_bulk_cost(
+        &self,
+        id: GeneratorType,
+        owned: u32,
+        count: u32,
+        discount: f64,
+    ) -> BigNum {
+        if count <= 1 {
+            return if count == 0 {
+                BigNum::ZERO
+            } else {
+                self.generator_cost(id, owned, discount)
+            };
+        }
+        let growth = self.balance.generator_cost_growth;
+        let next =
+            BigNum::from(self.generator(id).base_cost) * BigNum::from(growth).powi(owned as i32);
+        let series = (BigNum::from(growth).powi(count as i32) - BigNum::from(1.0)) / (growth - 1.0);
+        (next * series * (1.0 - discount)).ceil().max(BigNum::from(count))
+    }
+
+    /// The most generators `afp` and `serenity` pay for in one batch, checked
+    /// against `generator_bulk_cost` so it always matches what a purchase charges
+    pub fn max_affordable_generators(
+        &self,
+        id: GeneratorType,
+        owned: u32,
+        discount: f64,
+        afp: BigNum,
+        serenity: f64,
+    ) -> u32 {
+        let mut cap = MAX_GENERATOR_BATCH;
+        if let Some(per_unit) = self.generator(id).serenity_cost {
+            cap = cap.min((serenity / per_unit).floor().max(0.0) as u32);
+        }
+        let first = self.generator_cost(id, owned, discount);
+        if cap == 0 || afp < first {
+            return 0;
+        }
+
+        // Invert the sum for an estimate, then settle rounding against the real price
+        let growth = self.balance.generator_cost_growth;
+        let ratio = afp * (growth - 1.0) / first + BigNum::from(1.0);
+        let estimate = (ratio.log10() / growth.log10()).floor();
+        let mut count = (estimate.max(1.0) as u32).min(cap);
+        while count > 1 && self.generator_bulk_cost(id, owned, count, discount) > afp {
+            count -= 1;
+        }
+        while count < cap && self.generator_bulk_cost(id, owned, count + 1, discount) <= afp {
+            count += 1;
+        }
+        count
+    }
+
+    pub fn shop_item(&self, id: &ShopItemId) -> Option<&ShopItem> {
+        self.shop_items.iter().find(|item| item.id == *id)
+    }
+
+    pub fn enlightenment(&self, id: EnlightenmentId) -> &EnlightenmentDef {
+        &self.enlightenments[id as usize]
+    }
+
+    pub fn ascension_upgrade(&self, id: AscensionUpgradeId) -> &AscensionUpgradeDef {
+        &self.ascension_upgrades[id as usize]
+    }
+
+    pub fn challenge(&self, id: ChallengeId) -> &ChallengeDef {
+        &self.challenges[id as usize]
+    }
+
+    pub fn achievement(&self, id: AchievementId) -> &AchievementDef {
+        &self.achievements[id as usize]
+    }
+
+    pub fn school(&self, id: SchoolOfThought) -> &SchoolDef {
+        &self.schools[id as usize]
+    }
+
+    pub fn layer(&self, id: ContentLayer) -> &LayerDef {
+        &self.layers[id as usize]
+    }
+
+    /// Check enum-keyed lists against their enums and report every problem found
+    fn validate(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let e = &mut errors;
+        check_ids("generator", &self.generators, &GeneratorType::ALL, |d| d.id, e);
+        let enlightenments_listed =
+            check_ids("enlightenment", &self.enlightenments, &EnlightenmentId::ALL, |d| d.id, e);
+        let ascension_listed = check_ids(
+            "ascension upgrade",
+            &self.ascension_upgrades,
+            &AscensionUpgradeId::ALL,
+            |d| d.id,
+            e,
+        );
+        check_ids("challenge", &self.challenges, &ChallengeId::ALL, |d| d.id, e);
+        check_ids("achievement", &self.achievements, &AchievementId::ALL, |d| d.id, e);
+        check_ids("school", &self.schools, &SchoolOfThought::ALL, |d| d.id, e);
+        check_ids("layer", &self.layers, &ContentLayer::ALL, |d| d.id, e);
+
+        let mut last_unlock = 0;
+        for def in &self.generators {
+            if def.base_cost == 0 || def.base_production <= 0.0 {
+                errors.push(format!("generator {:?} needs a positive cost and production", def.id));
+            }
+            if def.serenity_cost.is_some_and(|s| s <= 0.0) {
+                errors.push(format!("generator {:?} serenity_cost must be positive", def.id));
+            }
+            if def.unlock_truths < last_unlock {
+                errors.push(format!("generator {:?} unlocks before the tier below it", def.id));
+            }
+            last_unlock = def.unlock_truths;
+        }
+
+        let mut seen = HashSet::new();
+        for item in &self.shop_items {
+            if item.id.0.is_empty() {
+                errors.push(format!("shop item \"{}\" has an empty id", item.name));
+            } else if !seen.insert(&item.id) {
+                errors.push(format!("shop item {} is defined more than once", item.id));
+            }
+            if item.cost == 0 {
+                errors.push(format!("shop item {} needs a positive cost", item.id));
+            }
+            // Generator upgrades are listed under the generator they need
+            match (item.category == ShopCategory::Generators, item.requires) {
+                (true, None) => {
+                    errors.push(format!("generator upgrade {} needs a threshold", item.id));
+                }
+                (false, Some(_)) => {
+                    errors.push(format!("shop item {} requires generators but isn't one", item.id));
+                }
+                (true, Some(req)) if req.owned == 0 => {
+                    errors.push(format!("generator upgrade {} needs a positive count", item.id));
+                }
+                _ => {}
+            }
+            match item.effect {
+                ShopEffect::Efficiency(v) | ShopEffect::WisdomSpeed(v) if v <= 0.0 => {
+                    errors.push(format!("shop item {} needs a positive bonus", item.id));
+                }
+                ShopEffect::Scaling(v) if v <= 1.0 => {
+                    errors.push(format!("shop item {} scaling must be above 1", item.id));
+                }
+                ShopEffect::GeneratorMultiplier { factor, .. } if factor <= 1.0 => {
+                    errors.push(format!("shop item {} factor must be above 1", item.id));
+                }
+                ShopEffect::ClickPerGenerator { amount, .. } if amount <= 0.0 => {
+                    errors.push(format!("shop item {} needs a positive bonus", item.id));
+                }
+                ShopEffect::ShadowWard(v) if !(v > 0.0 && v <= 0.9) => {
+                    errors.push(format!("shop item {} ward must be between 0 and 0.9", item.id));
+                }
+                _ => {}
+            }
+        }
+
+        for def in &self.enlightenments {
+            if def.cost == 0 {
+                errors.push(format!("enlightenment {:?} needs a positive cost", def.id));
+            }
+            if def.max_rank == 0 {
+                errors.push(format!("enlightenment {:?} needs at least one rank", def.id));
+            }
+            if !(def.cost_growth >= 1.0 && def.cost_growth.is_finite()) {
+                errors.push(format!("enlightenment {:?} cost_growth can't be below 1", def.id));
+            }
+            let discount = DISCOUNT_PER_RANK * def.max_rank as f64;
+            if def.id == EnlightenmentId::EfficientDesign && discount > MAX_GENERATOR_DISCOUNT {
+                errors.push(format!(
+                    "enlightenment {:?} can't discount generators past {:.0}%",
+                    def.id,
+                    MAX_GENERATOR_DISCOUNT * 100.0
+                ));
+            }
+        }
+        if enlightenments_listed {
+            self.place_enlightenments(&mut errors);
+        }
+        for def in &self.ascension_upgrades {
+            if def.cost == 0 {
+                errors.push(format!("ascension upgrade {:?} needs a positive cost", def.id));
+            }
+            if def.requires.contains(&def.id) {
+                errors.push(format!("ascension upgrade {:?} requires itself", def.id));
+            }
+        }
+        if ascension_listed {
+            let requires: Vec<Vec<usize>> = self
+                .ascension_upgrades
+                .iter()
+                .map(|def| def.requires.iter().map(|&req| req as usize).collect())
+                .collect();
+            for (def, tier) in self.ascension_upgrades.iter().zip(prerequisite_tiers(&requires)) {
+                if tier.is_none() {
+                    errors.push(format!(
+                        "ascension upgrade {:?} has a cycle in its prerequisites",
+                        def.id
+                    ));
+                }
+            }
+        }
+        for def in &self.challenges {
+            if def.duration_secs.is_some_and(|d| d <= 0.0) {
+                errors.push(format!("challenge {:?} duration must be positive", def.id));
+            }
+        }
+        for def in &self.achievements {
+            if def.reward < 0.0 || !def.reward.is_finite() {
+                errors.push(format!("achievement {:?} reward can't be negative", def.id));
+            }
+        }
+
+        let mut last_required = 0;
+        for def in &self.layers {
+            if def.id == ContentLayer::Surface && def.required_transcendences != 0 {
+                errors.push("layer Surface must be open from the start".to_string());
+            }
+            if def.required_transcendences < last_required {
+                errors.push(format!("layer {:?} unlocks before the layer above it", def.id));
+            }
+            last_required = def.required_transcendences;
+        }
+        errors
+    }
+}
+
+impl GameContent {
+    /// Check the enlightenment graph and give each node its tier. Needs the
+    /// enlightenments in enum order.
+    fn place_enlightenments(&mut self, errors: &mut Vec<String>) {
+        let defs = &self.enlightenments;
+        for def in defs {
+            for &req in &def.requires {
+                let group = &defs[req as usize].exclusive;
+                if req == def.id {
+                    errors.push(format!("enlightenment {:?} requires itself", def.id));
+                } else if group.is_some() && *group == def.exclusive {
+                    errors.push(format!(
+                        "enlightenment {:?} requires {:?} from its own exclusive group",
+                        def.id, req
+                    ));
+                }
+            }
+        }
+
+        let requires: Vec<Vec<usize>> = defs
+            .iter()
+            .map(|def| def.requires.iter().map(|&req| req as usize).collect())
+            .collect();
+        let tiers = prerequisite_tiers(&requires);
+        for (def, tier) in self.enlightenments.iter_mut().zip(tiers) {
+            match tier {
+                Some(tier) => def.tier = tier,
+                None => errors.push(format!(
+                    "enlightenment {:?} has a cycle in its prerequisites",
+                    def.id
+                )),
+            }
+        }
+    }
+}
+
+/// Each node's tier, one above its highest prerequisite, given the indices each
+/// node requires. Anything never placed sits on or above a cycle and is `None`.
+fn prerequisite_tiers(requires: &[Vec<usize>]) -> Vec<Option<u32>> {
+    let mut tiers: Vec<Option<u32>> = vec![None; requires.len()];
+    let mut placed_any = true;
+    while placed_any {
+        placed_any = false;
+        for (i, reqs) in requires.iter().enumerate() {
+            if tiers[i].is_some() {
+                continue;
+            }
+            let below: Option<Vec<u32>> = reqs.iter().map(|&req| tiers[req]).collect();
+            if let Some(below) = below {
+                tiers[i] = Some(below.iter().map(|t| t + 1).max().unwrap_or(0));
+                placed_any = true;
+            }
+        }
+    }
+    tiers
+}
+
+/// Each kind has a fixed set of ids compiled into the game, so `defs` must list
+/// exactly the ids in `all`, in that order. A missing, repeated or misplaced id is
+/// reported rather than quietly fixed up; true if the list is usable by index.
+fn check_ids<T, I: Copy + PartialEq + fmt::Debug>(
+    kind: &str,
+    defs: &[T],
+    all: &[I],
+    id_of: impl Fn(&T) -> I,
+    errors: &mut Vec<String>,
+) -> bool {
+    let before = errors.len();
+    for &id in all {
+        match defs.iter().filter(|d| id_of(d) == id).count() {
+            0 => errors.push(format!("{} {:?} is not defined", kind, id)),
+            1 => {}
+            _ => errors.push(format!("{} {:?} is defined more than once", kind, id)),
+        }
+    }
+    if errors.len() == before && !defs.iter().map(&id_of).eq(all.iter().copied()) {
+        errors.push(format!("{} entries must be listed in the order {:?}", kind, all));
+    }
+    errors.len() == before
+}
+
+#[derive(Debug)]
+pub enum ContentError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "could not read content: {}", e),
+            Self::Parse(e) => write!(f, "could not parse content: {}", e),
+            Self::Invalid(errors) => write!(f, "invalid content: {}", errors.join("; ")),
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+// ========== LOADING ==========
+
+#[derive(Default, TypePath)]
+pub struct GameContentLoader;
+
+impl AssetLoader for GameContentLoader {
+    type Asset = GameContent;
+    type Settings = ();
+    type Error = ContentError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<GameContent, ContentError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(ContentError::Io)?;
+        GameContent::parse(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["content.json"]
+    }
+}
+
+/// Keeps the content asset loaded
+#[derive(Resource)]
+pub struct ContentHandle(pub Handle<GameContent>);
+
+pub fn load_game_content(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ContentHandle(asset_server.load(CONTENT_PATH)));
+}
+
+/// Swap in the content file once it loads. Files that fail validation are
+/// rejected by the loader, so the previous content stays in use.
+pub fn apply_game_content(
+    mut events: MessageReader<AssetEvent<GameContent>>,
+    handle: Res<ContentHandle>,
+    assets: Res<Assets<GameContent>>,
+    mut content: ResMut<GameContent>,
+    mut tracker: ResMut<PurchaseTracker>,
+    equipped: Res<EquippedOrb>,
+) {
+    let updated = events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+            *id == handle.0.id()
+        }
+        _ => false,
+    });
+    if !updated {
+        return;
+    }
+    let Some(loaded) = assets.get(&handle.0) else {
+        return;
+    };
+    let balance = content.balance.clone();
+    *content = GameContent {
+        balance,
+        ..loaded.clone()
+    };
+    tracker.recalculate(equipped.0, &content);
+}
+
+// ========== HOT RELOAD ==========
+
+/// Polls the content and balance files and reloads them when they change on disk,
+/// so numbers can be tuned in a running session
+#[derive(Resource)]
+pub struct ContentWatcher {
+    timer: Timer,
+    files: Vec<WatchedFile>,
+}
+
+struct WatchedFile {
+    asset_path: &'static str,
+    disk_path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl ContentWatcher {
+    pub fn new(asset_paths: &[&'static str]) -> Self {
+        let root = FileAssetReader::get_base_path().join("assets");
+        Self {
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            files: asset_paths
+                .iter()
+                .map(|&asset_path| {
+                    let disk_path = root.join(asset_path);
+                    WatchedFile {
+                        asset_path,
+                        modified: modified_time(&disk_path),
+                        disk_path,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+fn modified_time(path: &PathBuf) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+pub fn watch_content_files(
+    time: Res<Time>,
+    mut watcher: ResMut<ContentWatcher>,
+    asset_server: Res<AssetServer>,
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    for file in &mut watcher.files {
+        let modified = modified_time(&file.disk_path);
+        if modified != file.modified {
+            file.modified = modified;
+            if modified.is_some() {
+                asset_server.reload(file.asset_path);
+            }
+        }
+    }
+}
+
+fn loaded_id<A: Asset>(event: &AssetEvent<A>) -> Option<UntypedAssetId> {
+    match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+            Some(id.untyped())
+        }
+        _ => None,
+    }
+}
+
+/// Red banner listing content files that were rejected
+#[derive(Component)]
+pub struct ContentErrorBanner;
+
+/// Shows why a content or balance file was rejected, until a good version loads
+pub fn show_content_errors(
+    mut commands: Commands,
+    mut failures: Local<BTreeMap<String, String>>,
+    mut content_failed: MessageReader<AssetLoadFailedEvent<GameContent>>,
+    mut balance_failed: MessageReader<AssetLoadFailedEvent<Balance>>,
+    mut content_events: MessageReader<AssetEvent<GameContent>>,
+    mut balance_events: MessageReader<AssetEvent<Balance>>,
+    asset_server: Res<AssetServer>,
+    banners: Query<Entity, With<ContentErrorBanner>>,
+) {
+    let before = failures.clone();
+
+    let loaded_ids: Vec<UntypedAssetId> = content_events
+        .read()
+        .filter_map(loaded_id)
+        .chain(balance_events.read().filter_map(loaded_id))
+        .collect();
+    for id in loaded_ids {
+        if let Some(path) = asset_server.get_path(id) {
+            failures.remove(&path.to_string());
+        }
+    }
+
+    let failed = content_failed
+        .read()
+        .map(|e| (e.path.to_string(), e.error.to_string()))
+        .chain(balance_failed.read().map(|e| (e.path.to_string(), e.error.to_string())));
+    for (path, error) in failed {
+        warn!("Rejected {}: {}", path, error);
+        failures.insert(path, error);
+    }
+
+    if *failures == before {
+        return;
+    }
+    for entity in &banners {
+        commands.entity(entity).despawn();
+    }
+    if failures.is_empty() {
+        return;
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(12.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ContentErrorBanner,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        max_width: Val::Percent(80.0),
+                        padding: UiRect::axes(Val::Px(16.0), Val::Px(10.0)),
+                        border_radius: BorderRadius::all(Val::Px(6.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.6, 0.1, 0.1, 0.92)),
+                ))
+                .with_children(|banner| {
+                    banner.spawn((
+                        Text::new("Content file rejected; keeping the last good values"),
+                        TextFont { font_size: 16.0, ..default() },
+                        TextColor(Color::srgb(1.0, 1.0, 1.0)),
+                    ));
+                    for (path, error) in failures.iter() {
+                        banner.spawn((
+                            Text::new(format!("{}: {}", path, error)),
+                            TextFont { font_size: 12.0, ..default() },
+                            TextColor(Color::srgba(1.0, 0.9, 0.9, 0.9)),
+                        ));
+                    }
+                });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn built_in_json() -> serde_json::Value {
+        serde_json::from_str(BUILT_IN).unwrap()
+    }
+
+    fn parse_errors(json: &serde_json::Value) -> Vec<String> {
+        match GameContent::parse(json.to_string().as_bytes()) {
+            Err(ContentError::Invalid(errors)) => errors,
+            other => panic!("expected validation errors, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn built_in_content_is_valid() {
+        let content = GameContent::default();
+        for gt in GeneratorType::ALL {
+            assert_eq!(content.generator(gt).id, gt);
+        }
+        assert_eq!(content.generator(GeneratorType::Candle).name, "Enchanted Candle");
+        assert_eq!(content.layer(ContentLayer::Void).required_transcendences, 20);
+        assert!(content.shop_item(&ShopItemId::from("GalaxyOrb")).is_some());
+    }
+
+    #[test]
+    fn lists_out_of_enum_order_are_reported() {
+        let mut json = built_in_json();
+        json["schools"].as_array_mut().unwrap().swap(0, 1);
+        let errors = parse_errors(&json);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("school entries must be listed in the order ["));
+    }
+
+    #[test]
+    fn unknown_ids_cannot_be_added() {
+        let mut json = built_in_json();
+        let mut extra = json["generators"][0].clone();
+        extra["id"] = "Lantern".into();
+        json["generators"].as_array_mut().unwrap().push(extra);
+        let err = GameContent::parse(json.to_string().as_bytes()).unwrap_err();
+        assert!(matches!(err, ContentError::Parse(_)));
+    }
+
+    #[test]
+    fn missing_and_duplicate_ids_are_reported() {
+        let mut json = built_in_json();
+        json["layers"].as_array_mut().unwrap().pop();
+        let tea = json["shop_items"][1].clone();
+        json["shop_items"].as_array_mut().unwrap().push(tea);
+
+        let errors = parse_errors(&json);
+        assert!(errors.contains(&"layer Void is not defined".to_string()));
+        assert!(errors.contains(&"shop item VoidTea is defined more than once".to_string()));
+    }
+
+    #[test]
+    fn bad_numbers_are_reported() {
+        let mut json = built_in_json();
+        json["generators"][0]["base_production"] = 0.0.into();
+        json["enlightenments"][0]["cost"] = 0.into();
+        assert_eq!(parse_errors(&json).len(), 2);
+    }
+
+    #[test]
+    fn generator_upgrades_are_listed_under_their_generator() {
+        let content = GameContent::default();
+        let wicks = content.shop_item(&ShopItemId::from("BraidedWicks")).unwrap();
+        assert_eq!(wicks.requires.map(|req| req.generator), Some(GeneratorType::Candle));
+
+        let mut json = built_in_json();
+        let items = json["shop_items"].as_array_mut().unwrap();
+        let upgrade = items.iter().position(|i| i["category"] == "Generators").unwrap();
+        items[upgrade].as_object_mut().unwrap().remove("requires");
+        items[0]["requires"] = serde_json::json!({ "generator": "Candle", "owned": 0 });
+        assert_eq!(parse_errors(&json).len(), 2);
+    }
+
+    #[test]
+    fn enlightenment_graph_is_checked_and_tiered() {
+        let content = GameContent::default();
+        assert_eq!(content.enlightenment(EnlightenmentId::DeepRoots).tier, 0);
+        assert_eq!(content.enlightenment(EnlightenmentId::Transcendent).tier, 3);
+
+        let mut json = built_in_json();
+        // Deep Roots <-> Head Start, and Clarity Affinity behind its own rival
+        json["enlightenments"][0]["requires"] = serde_json::json!(["HeadStart"]);
+        json["enlightenments"][5]["requires"] = serde_json::json!(["CosmicResonance"]);
+        let errors = parse_errors(&json);
+        assert!(errors.contains(
+            &"enlightenment DeepRoots has a cycle in its prerequisites".to_string()
+        ));
+        assert!(errors.contains(
+            &"enlightenment ClarityAffinity requires CosmicResonance from its own exclusive group"
+                .to_string()
+        ));
+    }
+
+    #[test]
+    fn efficient_design_cannot_make_generators_free() {
+        let mut json = built_in_json();
+        let design = json["enlightenments"]
+            .as_array_mut()
+            .unwrap()
+            .iter_mut()
+            .find(|e| e["id"] == "EfficientDesign")
+            .unwrap();
+        design["max_rank"] = 10.into();
+        assert_eq!(
+            parse_errors(&json),
+            vec!["enlightenment EfficientDesign can't discount generators past 90%".to_string()]
+        );
+    }
+
+    #[test]
+    fn ascension_prerequisite_cycles_are_reported() {
+        let mut json = built_in_json();
+        // Celestial Mind needs Well of Insight; close the loop
+        json["ascension_upgrades"][1]["requires"] = serde_json::json!(["StarlitTruths"]);
+        let errors = parse_errors(&json);
+        assert!(errors.contains(
+            &"ascension upgrade InsightWell has a cycle in its prerequisites".to_string()
+        ));
+    }
+
+    #[test]
+    fn bulk_generator_cost_is_a_geometric_sum() {
+        let content = GameContent::default();
+        let candle = GeneratorType::Candle;
+        assert_eq!(
+            content.generator_bulk_cost(candle, 3, 1, 0.0),
+            content.generator_cost(candle, 3, 0.0)
+        );
+        assert_eq!(content.generator_bulk_cost(candle, 3, 0, 0.0), BigNum::ZERO);
+
+        let by_hand: f64 = (3..13)
+            .map(|owned| {
+                content.generator(candle).base_cost as f64
+                    * content.balance.generator_cost_growth.powi(owned)
+            })
+            .sum();
+        let bulk = content.generator_bulk_cost(candle, 3, 10, 0.0).to_f64();
+        assert!((bulk - by_hand.ceil()).abs() <= 1.0, "{} vs {}", bulk, by_hand);
+
+        // One rounding for the batch saves at most a unit per extra generator
+        let one_by_one: f64 =
+            (3..13).map(|owned| content.generator_cost(candle, owned, 0.0).to_f64()).sum();
+        assert!(bulk <= one_by_one && one_by_one - bulk <= 9.0);
+    }
+
+    #[test]
+    fn max_affordable_generators_is_exact() {
+        let content = GameContent::default();
+        let candle = GeneratorType::Candle;
+        for afp in [0.0, 9.0, 10.0, 1234.0, 5.0e6, 1.0e30] {
+            let afp = BigNum::from(afp);
+            let n = content.max_affordable_generators(candle, 7, 0.1, afp, 0.0);
+            if n > 0 {
+                assert!(content.generator_bulk_cost(candle, 7, n, 0.1) <= afp);
+            }
+            if n < MAX_GENERATOR_BATCH {
+                assert!(content.generator_bulk_cost(candle, 7, n + 1, 0.1) > afp);
+            }
+        }
+
+        // Serenity is paid per unit, so it caps the batch on its own
+        let gt = GeneratorType::ALL
+            .into_iter()
+            .find(|gt| content.generator(*gt).serenity_cost.is_some())
+            .unwrap();
+        let per_unit = content.generator(gt).serenity_cost.unwrap();
+        let rich = BigNum::from(1.0e100);
+        assert_eq!(content.max_affordable_generators(gt, 0, 0.0, rich, per_unit * 3.5), 3);
+        assert_eq!(content.max_affordable_generators(gt, 0, 0.0, rich, 0.0), 0);
+    }
+
+    #[test]
+    fn a_new_shop_item_needs_no_code() {
+        let mut json = built_in_json();
+        json["shop_items"].as_array_mut().unwrap().push(serde_json::json!({
+            "id": "StarCookie",
+            "category": "Snacks",
+            "name": "Star Cookie",
+            "description": "Crumbles into constellations.",
+            "cost": 400,
+            "effect": { "Efficiency": 2.0 }
+        }));
+        let content = GameContent::parse(json.to_string().as_bytes()).unwrap();
+
+        let mut tracker = PurchaseTracker::default();
+        tracker.purchased.insert(ShopItemId::from("StarCookie"));
+        tracker.recalculate(crate::orb::types::OrbType::Crystal, &content);
+        assert_eq!(tracker.efficiency_bonus, 2.0);
+    }
+}
@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Environment variable that points all player data at one directory
+pub const DATA_DIR_ENV: &str = "ORB_SIM_DATA_DIR";
+
+/// Command-line flag with the same effect as `DATA_DIR_ENV`; takes precedence over it
+pub const DATA_DIR_FLAG: &str = "--data-dir";
+
+/// Folder name used under the platform's data and config directories
+#[cfg(any(windows, target_os = "macos"))]
+const APP_DIR: &str = "OrbPonderingSimulator";
+#[cfg(not(any(windows, target_os = "macos")))]
+const APP_DIR: &str = "orb-pondering-simulator";
+
+/// Where saves and settings live on this machine
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct DataDirs {
+    /// Saves, backups and profile metadata
+    pub data: PathBuf,
+    /// Per-profile settings
+    pub config: PathBuf,
+}
+
+impl Default for DataDirs {
+    /// Resolved from the process arguments and environment
+    fn default() -> Self {
+        Self::resolve(std::env::args().skip(1), |key| std::env::var_os(key))
+    }
+}
+
+impl DataDirs {
+    /// Both directories in one place, as used by the override and the old cwd layout
+    pub fn single(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        Self {
+            data: dir.clone(),
+            config: dir,
+        }
+    }
+
+    /// Pick the directories: `--data-dir`, then `ORB_SIM_DATA_DIR`, then the platform default
+    pub fn resolve(
+        args: impl IntoIterator<Item = String>,
+        env: impl Fn(&str) -> Option<OsString>,
+    ) -> Self {
+        if let Some(dir) = flag_value(args, DATA_DIR_FLAG) {
+            return Self::single(dir);
+        }
+        if let Some(dir) = env(DATA_DIR_ENV).filter(|d| !d.is_empty()) {
+            return Self::single(dir);
+        }
+        platform_dirs(&env).unwrap_or_else(|| Self::single("."))
+    }
+}
+
+/// Value of `<flag> <path>` or `<flag>=<path>`
+pub(crate) fn flag_value(args: impl IntoIterator<Item = String>, flag: &str) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix(flag).and_then(|v| v.strip_prefix('=')) {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+/// Absolute path from an environment variable; relative values are ignored per the XDG spec
+fn env_path(env: &impl Fn(&str) -> Option<OsString>, key: &str) -> Option<PathBuf> {
+    env(key).map(PathBuf::from).filter(|p| p.is_absolute())
+}
+
+#[cfg(windows)]
+fn platform_dirs(env: &impl Fn(&str) -> Option<OsString>) -> Option<DataDirs> {
+    env_path(env, "APPDATA").map(|dir| DataDirs::single(dir.join(APP_DIR)))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_dirs(env: &impl Fn(&str) -> Option<OsString>) -> Option<DataDirs> {
+    let home = env_path(env, "HOME")?;
+    Some(DataDirs::single(
+        home.join("Library").join("Application Support").join(APP_DIR),
+    ))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn platform_dirs(env: &impl Fn(&str) -> Option<OsString>) -> Option<DataDirs> {
+    let home = env_path(env, "HOME");
+    let data = env_path(env, "XDG_DATA_HOME")
+        .or_else(|| home.as_ref().map(|h| h.join(".local").join("share")))?;
+    let config = env_path(env, "XDG_CONFIG_HOME")
+        .or_else(|| home.as_ref().map(|h| h.join(".config")))?;
+    Some(DataDirs {
+        data: data.join(APP_DIR),
+        config: config.join(APP_DIR),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+        let vars: HashMap<String, OsString> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), OsString::from(v)))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn flag_beats_environment_variable() {
+        let env = env_of(&[(DATA_DIR_ENV, "/from/env")]);
+        let dirs = DataDirs::resolve(args(&["--data-dir", "/from/flag"]), &env);
+        assert_eq!(dirs, DataDirs::single("/from/flag"));
+
+        let dirs = DataDirs::resolve(args(&["--data-dir=/inline"]), &env);
+        assert_eq!(dirs, DataDirs::single("/inline"));
+
+        let dirs = DataDirs::resolve(args(&[]), &env);
+        assert_eq!(dirs, DataDirs::single("/from/env"));
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    #[test]
+    fn linux_uses_xdg_directories() {
+        let env = env_of(&[
+            ("HOME", "/home/luna"),
+            ("XDG_DATA_HOME", "/xdg/data"),
+            ("XDG_CONFIG_HOME", "/xdg/config"),
+        ]);
+        let dirs = DataDirs::resolve(args(&[]), env);
+        assert_eq!(dirs.data, PathBuf::from("/xdg/data").join(APP_DIR));
+        assert_eq!(dirs.config, PathBuf::from("/xdg/config").join(APP_DIR));
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    #[test]
+    fn linux_falls_back_to_home_and_ignores_relative_xdg() {
+        let env = env_of(&[("HOME", "/home/luna"), ("XDG_DATA_HOME", "relative/data")]);
+        let dirs = DataDirs::resolve(args(&[]), env);
+        assert_eq!(dirs.data, PathBuf::from("/home/luna/.local/share").join(APP_DIR));
+        assert_eq!(dirs.config, PathBuf::from("/home/luna/.config").join(APP_DIR));
+    }
+
+    #[test]
+    fn no_home_falls_back_to_working_directory() {
+        let dirs = DataDirs::resolve(args(&[]), env_of(&[]));
+        assert_eq!(dirs, DataDirs::single("."));
+    }
+}
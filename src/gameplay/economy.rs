@@ -0,0 +1,513 @@
+use super::achievements::AchievementTracker;
+use super::acolytes::AcolyteState;
+use super::ascension::AscensionState;
+use super::balance::Balance;
+use super::bignum::BigNum;
+use super::challenges::ChallengeState;
+use super::codex::TruthCodex;
+use super::content::GameContent;
+use super::generators::{GeneratorState, GeneratorType};
+use super::layers::LayerState;
+use super::modifiers::{ModifierSource, WisdomModifiers};
+use super::persistence::{enlightenment_ranks, SaveData};
+use super::progression::ArcaneProgress;
+use super::resources::{serenity_rate, SecondaryResources};
+use super::rng::GameRng;
+use super::schools::SchoolState;
+use super::shadow_thoughts::ShadowState;
+use super::shop::{PurchaseTracker, ShopEffect, ShopItemId};
+use super::synergies::SynergyState;
+use super::transcendence::{EnlightenmentId, TranscendenceState};
+use super::wisdom::{WisdomMeter, DEEP_TRUTHS};
+use crate::orb::types::{EquippedOrb, OrbType};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+/// Upper bound on truths resolved in a single `advance` call
+const MAX_TRUTHS_PER_ADVANCE: u32 = 100_000;
+
+/// Rate of the fixed economy tick
+pub const TICKS_PER_SECOND: f64 = 60.0;
+
+// ========== SCHEDULE ==========
+
+/// Stages of one economy tick in `FixedUpdate`, run in this order.
+/// Modifiers are collected between `Input` and `Production`.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EconomySet {
+    /// Apply the player's buffered actions
+    Input,
+    /// Timers and passive wisdom
+    Production,
+    /// Shadows skim this tick's gains
+    Siphon,
+    /// A full meter becomes a truth
+    TruthCheck,
+    /// AFP, run totals, achievements and unlocks for new truths
+    Rewards,
+}
+
+// ========== SHARED RULES ==========
+// The live systems and `EconomyModel` both go through these, so a rule change lands in both.
+
+/// Passive wisdom/sec from acolytes after modifiers
+pub fn acolyte_rate(
+    acolytes: &AcolyteState,
+    modifiers: &WisdomModifiers,
+    balance: &Balance,
+) -> BigNum {
+    if acolytes.count == 0 {
+        return BigNum::ZERO;
+    }
+    modifiers.passive_rate(acolytes.passive_rate(balance) as f64)
+}
+
+/// Passive wisdom/sec from generators after synergies, milestones and modifiers
+pub fn generator_rate(
+    generators: &GeneratorState,
+    synergies: &SynergyState,
+    modifiers: &WisdomModifiers,
+    content: &GameContent,
+) -> BigNum {
+    let base = synergies.total_synergized_production(generators, content);
+    if base <= 0.0 {
+        return BigNum::ZERO;
+    }
+    modifiers.passive_rate(base)
+}
+
+/// Growth of `max_wisdom` per truth: active challenge, then school, then shop upgrades
+pub fn truth_scaling(
+    tracker: &PurchaseTracker,
+    school: &SchoolState,
+    challenges: &ChallengeState,
+) -> f32 {
+    challenges
+        .active_scaling_override()
+        .or(school.scaling_override())
+        .unwrap_or(tracker.scaling_factor)
+}
+
+/// Empties a full meter, counts the truth and raises the next threshold.
+/// Returns the index of the revealed truth in `DEEP_TRUTHS`.
+pub fn complete_truth(wisdom: &mut WisdomMeter, scaling: f32) -> usize {
+    wisdom.current = BigNum::ZERO;
+    wisdom.truths_generated += 1;
+    wisdom.max_wisdom *= scaling as f64;
+    (wisdom.truths_generated as usize - 1) % DEEP_TRUTHS.len()
+}
+
+/// Grants the AFP for one truth
+pub fn award_truth(progress: &mut ArcaneProgress, modifiers: &WisdomModifiers) -> BigNum {
+    let afp = modifiers.afp_per_truth();
+    progress.focus_points += afp;
+    progress.total_truths += 1;
+    afp
+}
+
+// ========== LIVE SNAPSHOT ==========
+
+/// Read access to every resource the economy depends on, for snapshotting a running game
+#[derive(SystemParam)]
+pub struct EconomyResources<'w> {
+    pub wisdom: Res<'w, WisdomMeter>,
+    pub progress: Res<'w, ArcaneProgress>,
+    pub acolytes: Res<'w, AcolyteState>,
+    pub generators: Res<'w, GeneratorState>,
+    pub synergies: Res<'w, SynergyState>,
+    pub tracker: Res<'w, PurchaseTracker>,
+    pub transcendence: Res<'w, TranscendenceState>,
+    pub ascension: Res<'w, AscensionState>,
+    pub school: Res<'w, SchoolState>,
+    pub achievements: Res<'w, AchievementTracker>,
+    pub challenges: Res<'w, ChallengeState>,
+    pub resources: Res<'w, SecondaryResources>,
+    pub codex: Res<'w, TruthCodex>,
+    pub layers: Res<'w, LayerState>,
+    pub content: Res<'w, GameContent>,
+    pub equipped: Res<'w, EquippedOrb>,
+}
+
+// ========== MODEL ==========
+
+/// What happened during an `EconomyModel::advance` call
+#[derive(Debug, Clone, Default)]
+pub struct AdvanceReport {
+    pub truths: u32,
+    pub afp_earned: BigNum,
+    /// Passive wisdom produced, including wisdom consumed by truths
+    pub wisdom_produced: BigNum,
+}
+
+/// Headless copy of the run state that drives production.
+/// Built from a save or a live snapshot and advanced without an `App`.
+/// Transient effects (deep focus, moment buffs, the Focus resource, the Astral and Dream
+/// layers) are not modelled.
+#[derive(Debug, Clone)]
+pub struct EconomyModel {
+    pub wisdom: WisdomMeter,
+    pub progress: ArcaneProgress,
+    pub acolytes: AcolyteState,
+    pub generators: GeneratorState,
+    pub synergies: SynergyState,
+    pub tracker: PurchaseTracker,
+    pub transcendence: TranscendenceState,
+    pub ascension: AscensionState,
+    pub school: SchoolState,
+    pub achievements: AchievementTracker,
+    pub challenges: ChallengeState,
+    pub resources: SecondaryResources,
+    pub codex: TruthCodex,
+    pub layers: LayerState,
+    pub equipped: OrbType,
+    pub modifiers: WisdomModifiers,
+    /// Fraction of passive production that lands (1.0 in live play)
+    pub production_rate: f64,
+    pub content: GameContent,
+}
+
+impl EconomyModel {
+    /// Rebuild the economy from a save file
+    pub fn from_save(save: &SaveData, content: &GameContent) -> Self {
+        let mut model = Self {
+            wisdom: WisdomMeter::default(),
+            progress: ArcaneProgress::default(),
+            acolytes: AcolyteState::default(),
+            generators: GeneratorState::default(),
+            synergies: SynergyState::default(),
+            tracker: PurchaseTracker::default(),
+            transcendence: TranscendenceState::default(),
+            ascension: AscensionState::default(),
+            school: SchoolState::default(),
+            achievements: AchievementTracker::default(),
+            challenges: ChallengeState::default(),
+            resources: SecondaryResources::default(),
+            codex: TruthCodex::default(),
+            layers: LayerState::default(),
+            equipped: OrbType::Crystal,
+            modifiers: WisdomModifiers::default(),
+            production_rate: 1.0,
+            content: content.clone(),
+        };
+        let mut equipped = EquippedOrb(OrbType::Crystal);
+        save.restore(
+            &mut model.wisdom,
+            &mut model.progress,
+            &mut model.acolytes,
+            &mut model.generators,
+            &mut model.tracker,
+            &mut equipped,
+            &mut model.transcendence,
+            &mut model.ascension,
+            &mut model.school,
+            &mut model.achievements,
+            &mut ShadowState::new(&mut GameRng::from_seed(0)),
+            &mut model.challenges,
+            &mut model.resources,
+            &mut model.codex,
+            &mut model.layers,
+            content,
+        );
+        model.equipped = equipped.0;
+        model
+            .synergies
+            .recalculate(&model.generators, &model.content.balance, &model.tracker);
+        model.settle()
+    }
+
+    /// Copy the current state of a running game
+    pub fn capture(live: &EconomyResources) -> Self {
+        Self {
+            wisdom: live.wisdom.clone(),
+            progress: live.progress.clone(),
+            acolytes: live.acolytes.clone(),
+            generators: live.generators.clone(),
+            synergies: live.synergies.clone(),
+            tracker: live.tracker.clone(),
+            transcendence: live.transcendence.clone(),
+            ascension: live.ascension.clone(),
+            school: live.school.clone(),
+            achievements: live.achievements.clone(),
+            challenges: live.challenges.clone(),
+            resources: live.resources.clone(),
+            codex: live.codex.clone(),
+            layers: live.layers.clone(),
+            equipped: live.equipped.0,
+            modifiers: WisdomModifiers::default(),
+            production_rate: 1.0,
+            content: live.content.clone(),
+        }
+        .settle()
+    }
+
+    /// Drop UI-only state and transient boosts, then resolve modifiers
+    fn settle(mut self) -> Self {
+        // Focus bursts are player-driven and short, so the model runs unfocused
+        self.resources.focus_active = false;
+        self.achievements.notification_queue.clear();
+        self.codex.notification_queue.clear();
+        self.refresh_modifiers();
+        self
+    }
+
+    /// Re-collect every persistent modifier source into `modifiers`
+    pub fn refresh_modifiers(&mut self) {
+        let mut modifiers = WisdomModifiers::default();
+        let content = &self.content;
+        self.tracker.contribute(content, &mut modifiers);
+        self.synergies.contribute(content, &mut modifiers);
+        self.transcendence.contribute(content, &mut modifiers);
+        self.ascension.contribute(content, &mut modifiers);
+        self.school.contribute(content, &mut modifiers);
+        self.achievements.contribute(content, &mut modifiers);
+        self.challenges.contribute(content, &mut modifiers);
+        self.resources.contribute(content, &mut modifiers);
+        self.codex.contribute(content, &mut modifiers);
+        self.layers.contribute(content, &mut modifiers);
+        self.modifiers = modifiers;
+    }
+
+    /// Total passive wisdom/sec, scaled by `production_rate`
+    pub fn passive_rate(&self) -> BigNum {
+        let rate = acolyte_rate(&self.acolytes, &self.modifiers, &self.content.balance)
+            + generator_rate(&self.generators, &self.synergies, &self.modifiers, &self.content);
+        rate * self.production_rate
+    }
+
+    /// Run passive production for `secs`, resolving every truth along the way.
+    /// Production is constant between truths, so this jumps from truth to truth
+    /// instead of stepping frame by frame.
+    pub fn advance(&mut self, secs: f64) -> AdvanceReport {
+        let mut report = AdvanceReport::default();
+        if secs <= 0.0 {
+            return report;
+        }
+
+        self.resources.serenity += serenity_rate(&self.acolytes, &self.generators) * secs;
+
+        let mut remaining = secs;
+        while report.truths < MAX_TRUTHS_PER_ADVANCE {
+            let rate = self.passive_rate();
+            let needed = self.wisdom.max_wisdom - self.wisdom.current;
+            let time_to_truth = if !needed.is_positive() {
+                0.0
+            } else if rate.is_positive() {
+                (needed / rate).to_f64()
+            } else {
+                break;
+            };
+            if time_to_truth > remaining {
+                let gained = rate * remaining;
+                self.wisdom.current += gained;
+                report.wisdom_produced += gained;
+                break;
+            }
+            remaining -= time_to_truth;
+            report.wisdom_produced += needed.max(BigNum::ZERO);
+            report.afp_earned += self.generate_truth();
+            report.truths += 1;
+        }
+
+        self.achievements.run_elapsed += secs as f32;
+        report
+    }
+
+    /// Click the orb `clicks` times. A full meter becomes a truth on the next `advance`.
+    pub fn ponder(&mut self, clicks: u32) -> BigNum {
+        let gained = self.modifiers.click_wisdom() * clicks as f64;
+        self.wisdom.current += gained;
+        gained
+    }
+
+    /// Summon an acolyte if it is affordable
+    pub fn summon_acolyte(&mut self) -> bool {
+        let cost = self.acolytes.next_cost(&self.content.balance);
+        if self.progress.focus_points < cost {
+            return false;
+        }
+        self.progress.focus_points -= cost;
+        self.acolytes.count += 1;
+        true
+    }
+
+    /// Buy one generator if the shop would list it and it is affordable
+    pub fn buy_generator(&mut self, gt: GeneratorType) -> bool {
+        let def = self.content.generator(gt);
+        if self.progress.total_truths < def.unlock_truths {
+            return false;
+        }
+        let discount = self.transcendence.generator_cost_discount();
+        let cost = self.content.generator_cost(gt, self.generators.count(gt), discount);
+        if self.progress.focus_points < cost {
+            return false;
+        }
+        if let Some(serenity_cost) = def.serenity_cost {
+            if self.resources.serenity < serenity_cost {
+                return false;
+            }
+            self.resources.serenity -= serenity_cost;
+        }
+        self.progress.focus_points -= cost;
+        self.generators.add(gt);
+        self.synergies
+            .recalculate(&self.generators, &self.content.balance, &self.tracker);
+        // Upgrades that scale with owned generators feed the click rate
+        self.refresh_modifiers();
+        true
+    }
+
+    /// Buy a shop item if it is new, unlocked and affordable
+    pub fn buy_shop_item(&mut self, id: &ShopItemId) -> bool {
+        let Some(item) = self.content.shop_item(id) else {
+            return false;
+        };
+        let cost = BigNum::from(item.cost);
+        if self.tracker.purchased.contains(id)
+            || !item.is_unlocked(&self.generators)
+            || self.progress.focus_points < cost
+        {
+            return false;
+        }
+        if let ShopEffect::UnlockOrb(orb) = item.effect {
+            if !self.progress.unlocked_orbs.contains(&orb) {
+                self.progress.unlocked_orbs.push(orb);
+            }
+        }
+        self.progress.focus_points -= cost;
+        self.tracker.purchased.insert(id.clone());
+        self.tracker.recalculate(self.equipped, &self.content);
+        self.synergies
+            .recalculate(&self.generators, &self.content.balance, &self.tracker);
+        self.refresh_modifiers();
+        true
+    }
+
+    /// Insight the next rank of an enlightenment costs after ascension discounts
+    pub fn enlightenment_cost(&self, id: EnlightenmentId) -> u32 {
+        self.transcendence.next_rank_cost(id, &self.content, &self.ascension)
+    }
+
+    /// Learn the next rank of an enlightenment if the tree allows it and it is affordable
+    pub fn buy_enlightenment(&mut self, id: EnlightenmentId) -> bool {
+        if !self.transcendence.buy(id, &self.content, &self.ascension) {
+            return false;
+        }
+        self.refresh_modifiers();
+        true
+    }
+
+    /// Write everything `advance` and purchases can change back into a save
+    pub fn write_to_save(&self, save: &mut SaveData) {
+        save.acolyte_count = self.acolytes.count;
+        save.generators_owned = self.generators.owned;
+        save.purchased_items = self.tracker.purchased.iter().cloned().collect();
+        save.insight = self.transcendence.insight;
+        save.enlightenment_ranks = enlightenment_ranks(&self.transcendence);
+        save.enlightenment_spent = Some(self.transcendence.spent);
+        save.wisdom_current = self.wisdom.current;
+        save.wisdom_max = self.wisdom.max_wisdom;
+        save.truths_generated = self.wisdom.truths_generated;
+        save.focus_points = self.progress.focus_points;
+        save.total_truths = self.progress.total_truths;
+        save.school_run_truths = self.school.run_truths;
+        save.unlocked_achievements = self.achievements.unlocked.clone();
+        save.lifetime_truths = self.achievements.lifetime_truths;
+        save.achievement_peak_afp = self.achievements.peak_afp;
+        save.achievement_run_truths = self.achievements.run_truths;
+        save.achievement_run_elapsed = self.achievements.run_elapsed;
+        save.serenity = self.resources.serenity;
+        save.discovered_truths = self.codex.discovered.iter().copied().collect();
+        save.completed_codex_categories = self.codex.completed_categories.clone();
+        save.void_essence = self.layers.void_essence;
+        save.void_charge = self.layers.void_charge;
+    }
+
+    /// Resolve one truth from a full meter, applying the same bookkeeping as live play
+    pub fn generate_truth(&mut self) -> BigNum {
+        let scaling = truth_scaling(&self.tracker, &self.school, &self.challenges);
+        let index = complete_truth(&mut self.wisdom, scaling);
+        let afp = award_truth(&mut self.progress, &self.modifiers);
+        self.layers.void_truth(&mut self.wisdom, scaling);
+
+        self.achievements.peak_afp = self.achievements.peak_afp.max(self.progress.focus_points);
+        self.school.run_truths += 1;
+        self.achievements.lifetime_truths += 1;
+        self.achievements.run_truths += 1;
+        self.codex.discover(index);
+        self.achievements
+            .check_unlocks(&self.generators, &self.acolytes, &self.transcendence);
+        self.achievements.notification_queue.clear();
+        self.codex.notification_queue.clear();
+
+        self.refresh_modifiers();
+        afp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::input::PlayerInput;
+    use crate::gameplay::layers::ContentLayer;
+    use crate::gameplay::ledger::WisdomLedger;
+    use crate::gameplay::testing::TestApp;
+
+    /// Headless game mid-run with shadows and the Astral layer, rendering at `fps`
+    fn headless_game(fps: u32) -> TestApp {
+        let mut game = TestApp::new().at_fps(fps);
+        let world = game.app.world_mut();
+        world.resource_mut::<AcolyteState>().count = 3;
+        world.resource_mut::<GeneratorState>().owned[0] = 10;
+        world.resource_mut::<GeneratorState>().owned[1] = 2;
+        world.resource_mut::<LayerState>().unlock(ContentLayer::Astral);
+        world.resource_mut::<ShadowState>().spawn_timer =
+            Timer::from_seconds(5.0, TimerMode::Once);
+        game
+    }
+
+    #[test]
+    fn frame_rate_does_not_change_the_economy() {
+        let mut slow = headless_game(30);
+        let mut fast = headless_game(240);
+        slow.advance_secs(240);
+        fast.advance_secs(240);
+
+        let (slow, fast) = (slow.app.world(), fast.app.world());
+        let wisdom = |w: &World| w.resource::<WisdomMeter>().clone();
+        assert!(wisdom(slow).truths_generated > 0, "scenario should produce truths");
+        assert_eq!(wisdom(slow).truths_generated, wisdom(fast).truths_generated);
+        assert_eq!(wisdom(slow).current, wisdom(fast).current);
+        assert_eq!(
+            slow.resource::<ArcaneProgress>().focus_points,
+            fast.resource::<ArcaneProgress>().focus_points
+        );
+
+        let shadows = |w: &World| {
+            let s = w.resource::<ShadowState>();
+            (s.count, s.stored_wisdom)
+        };
+        assert!(shadows(slow).0 > 0, "scenario should spawn shadows");
+        assert_eq!(shadows(slow), shadows(fast));
+        assert_eq!(
+            slow.resource::<WisdomLedger>().run,
+            fast.resource::<WisdomLedger>().run
+        );
+        assert_eq!(
+            slow.resource::<SecondaryResources>().serenity,
+            fast.resource::<SecondaryResources>().serenity
+        );
+    }
+
+    #[test]
+    fn trackers_count_from_messages() {
+        let mut game = headless_game(60);
+        game.resource_mut::<PlayerInput>().deep_focus = true;
+        game.advance_secs(120);
+
+        let world = game.app.world();
+        let truths = world.resource::<WisdomMeter>().truths_generated;
+        assert!(truths > 0, "scenario should produce truths");
+        assert_eq!(world.resource::<SchoolState>().run_truths, truths);
+        assert_eq!(world.resource::<AchievementTracker>().deep_focus_uses, 1);
+    }
+}
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+/// Player actions seen since the last economy tick.
+/// Gathered every frame in `Update` and consumed in `FixedUpdate`, so a press is
+/// neither missed nor applied twice however many ticks a frame runs.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct PlayerInput {
+    /// Left clicks on the orb (not on UI buttons)
+    pub orb_clicks: u32,
+    /// [Space]
+    pub deep_focus: bool,
+    /// [A]
+    pub summon_acolyte: bool,
+    /// [D]
+    pub dispel: bool,
+    /// [G]
+    pub focus: bool,
+    /// Clicked the pending Moment of Clarity
+    pub claim_moment: bool,
+}
+
+/// Records this frame's presses into `PlayerInput`
+pub fn gather_player_input(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    interactions: Query<&Interaction>,
+    mut input: ResMut<PlayerInput>,
+) {
+    if mouse.just_pressed(MouseButton::Left) {
+        // Don't ponder if clicking a UI button
+        let clicking_ui = interactions.iter().any(|i| *i == Interaction::Pressed);
+        if !clicking_ui {
+            input.orb_clicks += 1;
+        }
+    }
+    input.deep_focus |= keys.just_pressed(KeyCode::Space);
+    input.summon_acolyte |= keys.just_pressed(KeyCode::KeyA);
+    input.dispel |= keys.just_pressed(KeyCode::KeyD);
+    input.focus |= keys.just_pressed(KeyCode::KeyG);
+}
+
+/// Runs last in `EconomySet::Input`, once every consumer has seen the actions
+pub fn clear_player_input(mut input: ResMut<PlayerInput>) {
+    *input = PlayerInput::default();
+}
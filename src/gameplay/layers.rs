@@ -1,7 +1,18 @@
+use super::ascension::AscensionState;
+use super::bignum::BigNum;
+use super::challenges::ChallengeState;
+use super::content::GameContent;
+use super::economy::truth_scaling;
+use super::ledger::{WisdomLedger, WisdomSource};
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
+use super::rng::{GameRng, RngStream};
+use super::schools::SchoolState;
+use super::shop::PurchaseTracker;
 use super::transcendence::TranscendenceState;
 use super::wisdom::{TruthGenerated, WisdomMeter};
 use crate::environment::daynight::DayNightCycle;
 use bevy::prelude::*;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 // ========== CONTENT LAYERS ==========
@@ -17,33 +28,6 @@ pub enum ContentLayer {
 impl ContentLayer {
     pub const ALL: [ContentLayer; 4] = [Self::Surface, Self::Astral, Self::Dream, Self::Void];
 
-    pub fn name(&self) -> &'static str {
-        match self {
-            Self::Surface => "Surface Plane",
-            Self::Astral => "Astral Plane",
-            Self::Dream => "Dream Plane",
-            Self::Void => "Void Plane",
-        }
-    }
-
-    pub fn description(&self) -> &'static str {
-        match self {
-            Self::Surface => "The familiar realm of waking thought.",
-            Self::Astral => "Night amplifies passive wisdom generation.",
-            Self::Dream => "Dream truths manifest at night. Wisdom flows stronger under the moon.",
-            Self::Void => "The space between spaces. Its secrets remain hidden... for now.",
-        }
-    }
-
-    pub fn required_transcendences(&self) -> u32 {
-        match self {
-            Self::Surface => 0,
-            Self::Astral => 1,
-            Self::Dream => 5,
-            Self::Void => 20,
-        }
-    }
-
     pub fn color(&self) -> Color {
         match self {
             Self::Surface => Color::srgb(0.6, 0.7, 0.6),
@@ -54,19 +38,38 @@ impl ContentLayer {
     }
 }
 
+/// A layer's text and unlock requirement, from the content file
+#[derive(Debug, Clone, Deserialize)]
+pub struct LayerDef {
+    pub id: ContentLayer,
+    pub name: String,
+    pub description: String,
+    pub required_transcendences: u32,
+}
+
 // ========== LAYER STATE ==========
 
-#[derive(Resource, Debug)]
+/// Every this many meter truths, the Void claims one
+pub const VOID_INTERVAL: u32 = 7;
+
+/// Passive wisdom bonus per point of void essence
+const VOID_ESSENCE_BONUS: f64 = 0.02;
+
+#[derive(Resource, Debug, Clone)]
 pub struct LayerState {
     pub unlocked: Vec<ContentLayer>,
-    pub notification_queue: Vec<ContentLayer>,
+    /// Left behind by voided truths; kept across transcendences
+    pub void_essence: u32,
+    /// Meter truths since the last voided one
+    pub void_charge: u32,
 }
 
 impl Default for LayerState {
     fn default() -> Self {
         Self {
             unlocked: vec![ContentLayer::Surface],
-            notification_queue: Vec::new(),
+            void_essence: 0,
+            void_charge: 0,
         }
     }
 }
@@ -86,11 +89,13 @@ impl LayerState {
         ContentLayer::Surface
     }
 
-    pub fn unlock(&mut self, layer: ContentLayer) {
-        if !self.has(layer) {
-            self.unlocked.push(layer);
-            self.notification_queue.push(layer);
+    /// Returns false if the layer was already open
+    pub fn unlock(&mut self, layer: ContentLayer) -> bool {
+        if self.has(layer) {
+            return false;
         }
+        self.unlocked.push(layer);
+        true
     }
 
     /// Night factor: 0.0 at day peak, 1.0 at night peak
@@ -107,6 +112,53 @@ impl LayerState {
         let nf = Self::night_factor(cycle);
         1.0 + nf * 0.5
     }
+
+    /// Meter truths left until the next one is voided
+    pub fn truths_until_void(&self) -> u32 {
+        VOID_INTERVAL.saturating_sub(self.void_charge)
+    }
+
+    /// Void layer passive multiplier from accumulated essence
+    pub fn void_multiplier(&self) -> f64 {
+        1.0 + self.void_essence as f64 * VOID_ESSENCE_BONUS
+    }
+
+    /// Count a meter truth toward the Void. Every `VOID_INTERVAL`th truth is voided:
+    /// the threshold growth it caused is inverted into a shrink, and it leaves one essence.
+    /// Call after `complete_truth` with the same scaling. Returns true if the truth was voided.
+    pub fn void_truth(&mut self, wisdom: &mut WisdomMeter, scaling: f32) -> bool {
+        if !self.has(ContentLayer::Void) {
+            return false;
+        }
+        self.void_charge += 1;
+        if self.void_charge < VOID_INTERVAL {
+            return false;
+        }
+        self.void_charge = 0;
+        self.void_essence += 1;
+        let scaling = scaling as f64;
+        let floor = WisdomMeter::default().max_wisdom;
+        wisdom.max_wisdom = (wisdom.max_wisdom / (scaling * scaling)).max(floor);
+        true
+    }
+}
+
+impl ModifierSource for LayerState {
+    fn contribute(&self, _content: &GameContent, modifiers: &mut WisdomModifiers) {
+        modifiers.mul("void", ModifierChannel::Passive, self.void_multiplier());
+    }
+}
+
+/// A content layer opened up
+#[derive(Message)]
+pub struct LayerUnlocked {
+    pub layer: ContentLayer,
+}
+
+/// The Void claimed a truth
+#[derive(Message)]
+pub struct TruthVoided {
+    pub essence: u32,
 }
 
 // ========== DREAM TRUTHS ==========
@@ -138,22 +190,40 @@ impl Default for DreamTruthTimer {
 /// Check transcendence count and unlock layers
 pub fn check_layer_unlocks(
     transcendence: Res<TranscendenceState>,
+    ascension: Res<AscensionState>,
+    content: Res<GameContent>,
     mut layers: ResMut<LayerState>,
+    mut unlocked: MessageWriter<LayerUnlocked>,
 ) {
+    // Also runs after a load, since saves from before layers existed have none open
+    if !transcendence.is_changed() && !ascension.is_changed() {
+        return;
+    }
     for &layer in &ContentLayer::ALL {
-        if transcendence.total_transcendences >= layer.required_transcendences()
-            && !layers.has(layer)
-        {
-            layers.unlock(layer);
+        let required = ascension.layer_requirement(content.layer(layer).required_transcendences);
+        if transcendence.total_transcendences >= required && layers.unlock(layer) {
+            unlocked.write(LayerUnlocked { layer });
         }
     }
 }
 
+/// Dream layer: feeds the night-scaled multiplier into the modifier registry
+pub fn register_dream_modifier(
+    layers: Res<LayerState>,
+    cycle: Res<DayNightCycle>,
+    mut modifiers: ResMut<WisdomModifiers>,
+) {
+    let mult = layers.dream_multiplier(&cycle) as f64;
+    modifiers.mul("dream", ModifierChannel::Click, mult);
+    modifiers.mul("dream", ModifierChannel::Passive, mult);
+}
+
 /// Astral layer: flat passive wisdom bonus scaled by night
 pub fn apply_astral_bonus(
     layers: Res<LayerState>,
     cycle: Res<DayNightCycle>,
     mut wisdom: ResMut<WisdomMeter>,
+    mut ledger: ResMut<WisdomLedger>,
     time: Res<Time>,
 ) {
     if !layers.has(ContentLayer::Astral) {
@@ -162,7 +232,7 @@ pub fn apply_astral_bonus(
     let nf = LayerState::night_factor(&cycle);
     // 0.05/s during day, up to 0.5/s at peak night
     let rate = 0.05 + nf * 0.45;
-    wisdom.current += rate * time.delta_secs();
+    ledger.gain(&mut wisdom, WisdomSource::Astral, BigNum::from(rate * time.delta_secs()));
 }
 
 /// Dream layer: periodic dream truths at night
@@ -171,6 +241,8 @@ pub fn dream_truth_generation(
     cycle: Res<DayNightCycle>,
     mut timer: ResMut<DreamTruthTimer>,
     mut wisdom: ResMut<WisdomMeter>,
+    mut ledger: ResMut<WisdomLedger>,
+    mut rng: ResMut<GameRng>,
     mut truth_messages: MessageWriter<TruthGenerated>,
     time: Res<Time>,
 ) {
@@ -186,10 +258,10 @@ pub fn dream_truth_generation(
     timer.0.tick(time.delta());
     if timer.0.just_finished() {
         // Grant bonus wisdom (equivalent to ~10 truths worth)
-        wisdom.current += 10.0;
+        ledger.gain(&mut wisdom, WisdomSource::Dream, BigNum::from(10.0));
 
         // Pick a random dream truth
-        let index = (wisdom.truths_generated as usize) % DREAM_TRUTHS.len();
+        let index = rng.stream(RngStream::DreamTruths).gen_range(0..DREAM_TRUTHS.len());
         truth_messages.write(TruthGenerated {
             text: DREAM_TRUTHS[index].to_string(),
             truth_index: usize::MAX, // Sentinel: not a codex truth
@@ -197,6 +269,29 @@ pub fn dream_truth_generation(
     }
 }
 
+/// Void layer: every `VOID_INTERVAL`th meter truth is voided
+pub fn void_truths(
+    mut truth_messages: MessageReader<TruthGenerated>,
+    mut layers: ResMut<LayerState>,
+    mut wisdom: ResMut<WisdomMeter>,
+    tracker: Res<PurchaseTracker>,
+    school: Res<SchoolState>,
+    challenges: Res<ChallengeState>,
+    mut voided: MessageWriter<TruthVoided>,
+) {
+    for msg in truth_messages.read() {
+        if !msg.is_meter_truth() {
+            continue;
+        }
+        let scaling = truth_scaling(&tracker, &school, &challenges);
+        if layers.void_truth(&mut wisdom, scaling) {
+            voided.write(TruthVoided {
+                essence: layers.void_essence,
+            });
+        }
+    }
+}
+
 // ========== NOTIFICATIONS ==========
 
 #[derive(Component)]
@@ -206,9 +301,11 @@ pub struct LayerNotification {
 
 pub fn spawn_layer_notifications(
     mut commands: Commands,
-    mut layers: ResMut<LayerState>,
+    mut unlocked: MessageReader<LayerUnlocked>,
+    content: Res<GameContent>,
 ) {
-    while let Some(layer) = layers.notification_queue.pop() {
+    for &LayerUnlocked { layer } in unlocked.read() {
+        let def = content.layer(layer);
         commands
             .spawn((
                 Node {
@@ -234,12 +331,12 @@ pub fn spawn_layer_notifications(
                     ))
                     .with_children(|badge| {
                         badge.spawn((
-                            Text::new(format!("Layer Unlocked: {}", layer.name())),
+                            Text::new(format!("Layer Unlocked: {}", def.name)),
                             TextFont { font_size: 20.0, ..default() },
                             TextColor(Color::srgb(1.0, 1.0, 1.0)),
                         ));
                         badge.spawn((
-                            Text::new(layer.description()),
+                            Text::new(def.description.clone()),
                             TextFont { font_size: 13.0, ..default() },
                             TextColor(Color::srgba(1.0, 1.0, 1.0, 0.8)),
                         ));
@@ -248,6 +345,35 @@ pub fn spawn_layer_notifications(
     }
 }
 
+pub fn spawn_void_notifications(mut commands: Commands, mut voided: MessageReader<TruthVoided>) {
+    for &TruthVoided { essence } in voided.read() {
+        commands
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(80.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                LayerNotification { timer: 3.0 },
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Text::new(format!("The Void claims a truth ({} essence)", essence)),
+                    TextFont { font_size: 16.0, ..default() },
+                    TextColor(Color::srgb(0.85, 0.6, 0.75)),
+                    BackgroundColor(ContentLayer::Void.color().with_alpha(0.9)),
+                    Node {
+                        padding: UiRect::axes(Val::Px(16.0), Val::Px(8.0)),
+                        border_radius: BorderRadius::all(Val::Px(6.0)),
+                        ..default()
+                    },
+                ));
+            });
+    }
+}
+
 pub fn update_layer_notifications(
     mut commands: Commands,
     mut query: Query<(Entity, &mut LayerNotification)>,
@@ -260,3 +386,74 @@ pub fn update_layer_notifications(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::testing::TestApp;
+    use crate::gameplay::wisdom::TruthGenerated;
+
+    fn void_open() -> LayerState {
+        let mut layers = LayerState::default();
+        for layer in ContentLayer::ALL {
+            layers.unlock(layer);
+        }
+        layers
+    }
+
+    #[test]
+    fn every_seventh_truth_is_voided_and_shrinks_the_threshold() {
+        let mut wisdom = WisdomMeter::default();
+        let mut layers = LayerState::default();
+        assert!(!layers.void_truth(&mut wisdom, 1.5));
+        assert_eq!(layers.void_charge, 0);
+
+        let mut layers = void_open();
+        wisdom.max_wisdom = BigNum::from(1000.0);
+        for _ in 1..VOID_INTERVAL {
+            assert!(!layers.void_truth(&mut wisdom, 1.5));
+        }
+        assert_eq!(layers.truths_until_void(), 1);
+        assert!(layers.void_truth(&mut wisdom, 2.0));
+        assert_eq!(wisdom.max_wisdom, BigNum::from(250.0));
+        assert_eq!((layers.void_essence, layers.void_charge), (1, 0));
+
+        // Never below a fresh meter's threshold
+        layers.void_charge = VOID_INTERVAL - 1;
+        layers.void_truth(&mut wisdom, 100.0);
+        assert_eq!(wisdom.max_wisdom, WisdomMeter::default().max_wisdom);
+    }
+
+    #[test]
+    fn void_essence_boosts_passive_wisdom_in_a_running_game() {
+        let mut game = TestApp::new();
+        *game.resource_mut::<LayerState>() = void_open();
+        game.resource_mut::<LayerState>().void_charge = VOID_INTERVAL - 1;
+
+        // Dream truths come from the night, not the meter, and must not count
+        game.app.world_mut().write_message(TruthGenerated {
+            text: String::new(),
+            truth_index: usize::MAX,
+        });
+        game.advance_secs(1);
+        assert_eq!(game.resource::<LayerState>().void_essence, 0);
+
+        game.app.world_mut().write_message(TruthGenerated {
+            text: String::new(),
+            truth_index: 0,
+        });
+        game.advance_secs(1);
+        assert_eq!(game.resource::<LayerState>().void_essence, 1);
+        let boosted = game
+            .resource::<WisdomModifiers>()
+            .passive_rate(1.0)
+            .to_f64();
+        game.resource_mut::<LayerState>().void_essence = 0;
+        game.advance_secs(1);
+        let plain = game
+            .resource::<WisdomModifiers>()
+            .passive_rate(1.0)
+            .to_f64();
+        assert!((boosted / plain - (1.0 + VOID_ESSENCE_BONUS)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,162 @@
+use super::bignum::BigNum;
+use super::content::GameContent;
+use super::generators::GeneratorType;
+use super::wisdom::WisdomMeter;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where a wisdom gain came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WisdomSource {
+    Click,
+    Acolyte,
+    Generator(GeneratorType),
+    Astral,
+    Dream,
+    Moment,
+    /// Bonus on top of the stored wisdom returned by a dispel
+    Dispel,
+    Offline,
+    /// Run wisdom carried over from saves made before the ledger existed
+    Unattributed,
+}
+
+impl WisdomSource {
+    pub fn name<'a>(&self, content: &'a GameContent) -> &'a str {
+        match self {
+            Self::Click => "Pondering",
+            Self::Acolyte => "Acolytes",
+            Self::Generator(gt) => &content.generator(*gt).name,
+            Self::Astral => "Astral Plane",
+            Self::Dream => "Dream Truths",
+            Self::Moment => "Moments of Clarity",
+            Self::Dispel => "Dispelled Shadows",
+            Self::Offline => "While Away",
+            Self::Unattributed => "Earlier Sessions",
+        }
+    }
+}
+
+/// Wisdom gained per source, in the order sources first appeared
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LedgerTotals(Vec<(WisdomSource, BigNum)>);
+
+impl LedgerTotals {
+    pub fn add(&mut self, source: WisdomSource, amount: BigNum) {
+        match self.0.iter_mut().find(|(s, _)| *s == source) {
+            Some((_, total)) => *total += amount,
+            None => self.0.push((source, amount)),
+        }
+    }
+
+    pub fn get(&self, source: WisdomSource) -> BigNum {
+        self.0
+            .iter()
+            .find(|(s, _)| *s == source)
+            .map_or(BigNum::ZERO, |&(_, total)| total)
+    }
+
+    pub fn total(&self) -> BigNum {
+        self.0.iter().map(|&(_, total)| total).sum()
+    }
+
+    /// Sources with their totals, largest first
+    pub fn ranked(&self) -> Vec<(WisdomSource, BigNum)> {
+        let mut entries = self.0.clone();
+        entries.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        entries
+    }
+}
+
+/// Every wisdom gain goes through here, so run and lifetime totals always
+/// agree with what the meter received
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct WisdomLedger {
+    pub run: LedgerTotals,
+    pub lifetime: LedgerTotals,
+    /// Gained during the current economy tick, for `siphon_wisdom`
+    #[serde(skip)]
+    tick: BigNum,
+}
+
+impl WisdomLedger {
+    /// Add `amount` to the meter and record it against `source`
+    pub fn gain(&mut self, wisdom: &mut WisdomMeter, source: WisdomSource, amount: BigNum) {
+        if !amount.is_positive() {
+            return;
+        }
+        wisdom.current += amount;
+        self.tick += amount;
+        self.record(source, amount);
+    }
+
+    /// Record wisdom that reached the meter some other way, such as offline progress
+    pub fn record(&mut self, source: WisdomSource, amount: BigNum) {
+        if !amount.is_positive() {
+            return;
+        }
+        self.run.add(source, amount);
+        self.lifetime.add(source, amount);
+    }
+
+    /// Wisdom gained since the last call; shadows skim a share of it each tick
+    pub fn take_tick(&mut self) -> BigNum {
+        std::mem::take(&mut self.tick)
+    }
+
+    /// Total wisdom gained this run, which insight is earned from
+    pub fn run_total(&self) -> BigNum {
+        self.run.total()
+    }
+
+    /// Clear the run totals on transcendence; lifetime totals are kept
+    pub fn start_run(&mut self) {
+        self.run = LedgerTotals::default();
+        self.tick = BigNum::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gains_reach_meter_and_both_totals() {
+        let mut ledger = WisdomLedger::default();
+        let mut wisdom = WisdomMeter::default();
+        ledger.gain(&mut wisdom, WisdomSource::Click, BigNum::from(3.0));
+        ledger.gain(&mut wisdom, WisdomSource::Generator(GeneratorType::Candle), BigNum::from(2.0));
+        ledger.gain(&mut wisdom, WisdomSource::Click, BigNum::from(1.0));
+
+        assert_eq!(wisdom.current, BigNum::from(6.0));
+        assert_eq!(ledger.run.get(WisdomSource::Click), BigNum::from(4.0));
+        assert_eq!(ledger.lifetime.total(), BigNum::from(6.0));
+        assert_eq!(ledger.take_tick(), BigNum::from(6.0));
+        assert_eq!(ledger.take_tick(), BigNum::ZERO);
+    }
+
+    #[test]
+    fn new_run_keeps_lifetime_totals() {
+        let mut ledger = WisdomLedger::default();
+        ledger.record(WisdomSource::Offline, BigNum::from(50.0));
+        ledger.start_run();
+        ledger.record(WisdomSource::Acolyte, BigNum::from(5.0));
+
+        assert_eq!(ledger.run_total(), BigNum::from(5.0));
+        assert_eq!(ledger.lifetime.total(), BigNum::from(55.0));
+        assert_eq!(ledger.lifetime.ranked()[0].0, WisdomSource::Offline);
+    }
+
+    #[test]
+    fn ledger_roundtrips_through_json() {
+        let mut ledger = WisdomLedger::default();
+        ledger.record(WisdomSource::Generator(GeneratorType::VoidGate), BigNum::new(2.5, 40));
+        ledger.record(WisdomSource::Dispel, BigNum::from(7.0));
+
+        let json = serde_json::to_string(&ledger).unwrap();
+        let back: WisdomLedger = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.run, ledger.run);
+        assert_eq!(back.lifetime, ledger.lifetime);
+    }
+}
@@ -0,0 +1,28 @@
+use super::wisdom::TruthGenerated;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogbookEntry {
+    pub text: String,
+    pub truth_number: u32,
+}
+
+#[derive(Resource, Default)]
+pub struct Logbook {
+    pub entries: Vec<LogbookEntry>,
+}
+
+pub fn record_truths(
+    mut logbook: ResMut<Logbook>,
+    mut truth_events: MessageReader<TruthGenerated>,
+) {
+    for event in truth_events.read() {
+        // Continue from the last entry so numbering survives trimmed saves
+        let num = logbook.entries.last().map_or(0, |e| e.truth_number) + 1;
+        logbook.entries.push(LogbookEntry {
+            text: event.text.clone(),
+            truth_number: num,
+        });
+    }
+}
@@ -1,21 +1,43 @@
 use bevy::prelude::*;
+use modifiers::ModifierAppExt;
 
 pub mod achievements;
 pub mod acolytes;
+pub mod advisor;
+pub mod ascension;
+pub mod balance;
+pub mod bignum;
 pub mod challenges;
 pub mod codex;
+pub mod content;
+pub mod data_dirs;
+pub mod economy;
 pub mod generators;
+pub mod input;
 pub mod layers;
+pub mod ledger;
+pub mod logbook;
+pub mod modifiers;
 pub mod moments;
 pub mod persistence;
 pub mod pondering;
+pub mod profiles;
 pub mod progression;
 pub mod resources;
+pub mod rng;
+pub mod save_code;
+pub mod save_files;
+pub mod save_migration;
+pub mod save_storage;
+pub mod save_transfer;
 pub mod schools;
 pub mod shadow_thoughts;
 pub mod shop;
+pub mod simulation;
 pub mod state;
 pub mod synergies;
+#[cfg(test)]
+pub mod testing;
 pub mod transcendence;
 pub mod wisdom;
 
@@ -24,6 +46,8 @@ pub struct GameplayPlugin;
 impl Plugin for GameplayPlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<state::GameState>()
+            .init_resource::<content::GameContent>()
+            .init_resource::<rng::GameRng>()
             .init_resource::<pondering::PonderState>()
             .init_resource::<wisdom::WisdomMeter>()
             .init_resource::<progression::ArcaneProgress>()
@@ -33,80 +57,189 @@ impl Plugin for GameplayPlugin {
             .init_resource::<schools::SchoolState>()
             .init_resource::<moments::MomentState>()
             .init_resource::<transcendence::TranscendenceState>()
+            .init_resource::<ascension::AscensionState>()
             .init_resource::<achievements::AchievementTracker>()
             .init_resource::<shadow_thoughts::ShadowState>()
             .init_resource::<challenges::ChallengeState>()
             .init_resource::<resources::SecondaryResources>()
             .init_resource::<codex::TruthCodex>()
+            .init_resource::<logbook::Logbook>()
             .init_resource::<layers::LayerState>()
             .init_resource::<layers::DreamTruthTimer>()
             .init_resource::<persistence::AutoSaveTimer>()
             .init_resource::<persistence::OfflineReport>()
+            .init_resource::<persistence::SaveLock>()
+            .init_resource::<data_dirs::DataDirs>()
+            .init_resource::<save_storage::SaveStore>()
+            .init_resource::<profiles::ActiveProfile>()
+            .init_resource::<persistence::PendingSaveNotice>()
+            .init_resource::<save_files::BackupSettings>()
+            .init_resource::<modifiers::WisdomModifiers>()
+            .init_resource::<input::PlayerInput>()
+            .init_resource::<ledger::WisdomLedger>()
             .add_message::<wisdom::TruthGenerated>()
+            .add_message::<generators::GeneratorPurchased>()
+            .add_message::<acolytes::AcolyteSummoned>()
+            .add_message::<shop::ShopItemBought>()
+            .add_message::<moments::MomentClaimed>()
+            .add_message::<shadow_thoughts::ShadowsDispelled>()
+            .add_message::<pondering::DeepFocusActivated>()
+            .add_message::<challenges::ChallengeCompleted>()
+            .add_message::<transcendence::Transcended>()
+            .add_message::<ascension::Ascended>()
+            .add_message::<layers::LayerUnlocked>()
+            .add_message::<layers::TruthVoided>()
+            // Economy tick
+            .insert_resource(Time::<Fixed>::from_hz(economy::TICKS_PER_SECOND))
+            .configure_sets(
+                FixedUpdate,
+                (
+                    economy::EconomySet::Input,
+                    modifiers::CollectModifiers,
+                    economy::EconomySet::Production,
+                    economy::EconomySet::Siphon,
+                    economy::EconomySet::TruthCheck,
+                    economy::EconomySet::Rewards,
+                )
+                    .chain(),
+            )
+            // Modifier pipeline
+            .register_modifier_source::<shop::PurchaseTracker>()
+            .register_modifier_source::<synergies::SynergyState>()
+            .register_modifier_source::<pondering::PonderState>()
+            .register_modifier_source::<moments::MomentState>()
+            .register_modifier_source::<transcendence::TranscendenceState>()
+            .register_modifier_source::<ascension::AscensionState>()
+            .register_modifier_source::<schools::SchoolState>()
+            .register_modifier_source::<achievements::AchievementTracker>()
+            .register_modifier_source::<challenges::ChallengeState>()
+            .register_modifier_source::<resources::SecondaryResources>()
+            .register_modifier_source::<codex::TruthCodex>()
+            .register_modifier_source::<layers::LayerState>()
+            .add_systems(
+                FixedUpdate,
+                layers::register_dream_modifier.in_set(modifiers::CollectModifiers),
+            )
+            // Profiles
+            .add_systems(
+                OnEnter(state::GameState::ProfileSelect),
+                profiles::open_profile_picker,
+            )
+            .add_systems(
+                Update,
+                (
+                    profiles::handle_profile_actions,
+                    profiles::handle_profile_keys,
+                    profiles::rebuild_profile_picker,
+                )
+                    .chain()
+                    .run_if(in_state(state::GameState::ProfileSelect)),
+            )
             // Save/Load
-            .add_systems(Startup, persistence::load_game)
-            .add_systems(PostStartup, persistence::show_welcome_back)
+            .add_systems(
+                OnExit(state::GameState::ProfileSelect),
+                (
+                    profiles::close_profile_picker,
+                    persistence::load_game,
+                    persistence::show_welcome_back,
+                    persistence::show_save_notice,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (persistence::auto_save, persistence::save_on_exit)
+                    .run_if(not(in_state(state::GameState::ProfileSelect))),
+            )
             .add_systems(
                 Update,
                 (
-                    persistence::auto_save,
-                    persistence::save_on_exit,
                     persistence::handle_welcome_dismiss,
                     persistence::auto_dismiss_welcome,
+                    persistence::handle_save_notice_dismiss,
+                    logbook::record_truths,
+                    challenges::fail_silence_on_purchase,
+                    achievements::latch_menu_triggers,
                 ),
             )
             .add_systems(
                 Update,
+                (input::gather_player_input, moments::queue_moment_click)
+                    .run_if(in_state(state::GameState::Playing)),
+            )
+            .add_systems(
+                FixedUpdate,
                 (
+                    challenges::update_challenges,
                     pondering::handle_click_ponder,
                     pondering::handle_deep_focus,
-                    pondering::update_ponder_visuals,
-                    acolytes::passive_wisdom,
-                    synergies::recalculate_synergies,
-                    generators::passive_generator_wisdom,
-                    moments::update_moments,
+                    acolytes::summon_acolyte,
                     moments::handle_moment_click,
-                    moments::render_moment_popup,
-                    moments::render_buff_indicator,
-                    shadow_thoughts::update_shadows,
-                    shadow_thoughts::siphon_wisdom,
                     shadow_thoughts::handle_dispel,
-                    shadow_thoughts::render_shadow_ui,
+                    resources::update_focus,
+                    synergies::recalculate_synergies,
+                    input::clear_player_input,
                 )
+                    .chain()
+                    .in_set(economy::EconomySet::Input)
                     .run_if(in_state(state::GameState::Playing)),
             )
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
-                    challenges::update_challenges,
-                    challenges::track_solitude_progress,
-                    challenges::render_challenge_indicator,
-                    resources::generate_serenity,
-                    resources::update_focus,
-                    codex::track_truth_discovery,
-                    layers::check_layer_unlocks,
+                    pondering::tick_deep_focus,
+                    moments::update_moments,
+                    shadow_thoughts::update_shadows,
+                    acolytes::passive_wisdom,
+                    generators::passive_generator_wisdom,
                     layers::apply_astral_bonus,
-                    layers::dream_truth_generation,
+                    resources::generate_serenity,
                 )
+                    .chain()
+                    .in_set(economy::EconomySet::Production)
                     .run_if(in_state(state::GameState::Playing)),
             )
             .add_systems(
-                Update,
-                acolytes::summon_acolyte.run_if(in_state(state::GameState::Playing)),
+                FixedUpdate,
+                shadow_thoughts::siphon_wisdom
+                    .in_set(economy::EconomySet::Siphon)
+                    .run_if(in_state(state::GameState::Playing)),
             )
             .add_systems(
-                Update,
-                wisdom::check_truth_generation.run_if(in_state(state::GameState::Playing)),
+                FixedUpdate,
+                (
+                    wisdom::check_truth_generation,
+                    layers::dream_truth_generation,
+                )
+                    .chain()
+                    .in_set(economy::EconomySet::TruthCheck)
+                    .run_if(in_state(state::GameState::Playing)),
             )
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
                     progression::award_points,
-                    transcendence::accumulate_run_wisdom,
                     schools::track_run_truths,
+                    challenges::track_solitude_progress,
+                    codex::track_truth_discovery,
                     achievements::track_achievement_stats,
                     achievements::track_deep_focus_uses,
                     achievements::check_achievements,
+                    layers::check_layer_unlocks,
+                    layers::void_truths,
+                )
+                    .chain()
+                    .in_set(economy::EconomySet::Rewards)
+                    .run_if(in_state(state::GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    pondering::update_ponder_visuals,
+                    moments::render_moment_popup,
+                    moments::render_buff_indicator,
+                    shadow_thoughts::render_shadow_ui,
+                    challenges::render_challenge_indicator,
                 )
                     .run_if(in_state(state::GameState::Playing)),
             )
@@ -139,9 +272,30 @@ impl Plugin for GameplayPlugin {
                 (
                     transcendence::handle_transcend_click,
                     transcendence::handle_enlightenment_buy,
+                    transcendence::handle_respec_click,
+                    transcendence::rebuild_enlightenment_tree,
                 )
                     .run_if(in_state(state::GameState::TranscendenceOpen)),
             )
+            // Ascension
+            .add_systems(Update, ascension::toggle_ascension)
+            .add_systems(
+                OnEnter(state::GameState::AscensionOpen),
+                ascension::open_ascension_ui,
+            )
+            .add_systems(
+                OnExit(state::GameState::AscensionOpen),
+                ascension::close_ascension_ui,
+            )
+            .add_systems(
+                Update,
+                (
+                    ascension::handle_ascend_click,
+                    ascension::handle_ascension_upgrade_buy,
+                    ascension::rebuild_ascension_panel,
+                )
+                    .run_if(in_state(state::GameState::AscensionOpen)),
+            )
             // Achievements
             .add_systems(Update, achievements::toggle_achievements)
             .add_systems(
@@ -167,6 +321,7 @@ impl Plugin for GameplayPlugin {
                     codex::spawn_codex_notifications,
                     codex::update_codex_notifications,
                     layers::spawn_layer_notifications,
+                    layers::spawn_void_notifications,
                     layers::update_layer_notifications,
                 ),
             )
@@ -187,13 +342,36 @@ impl Plugin for GameplayPlugin {
                 challenges::handle_challenge_begin
                     .run_if(in_state(state::GameState::ChallengesOpen)),
             )
+            // Save export/import
+            .add_systems(Update, save_transfer::toggle_save_transfer)
+            .add_systems(
+                OnEnter(state::GameState::SaveTransfer),
+                save_transfer::open_save_transfer,
+            )
+            .add_systems(
+                OnExit(state::GameState::SaveTransfer),
+                save_transfer::close_save_transfer,
+            )
+            .add_systems(
+                Update,
+                (
+                    save_transfer::handle_transfer_buttons,
+                    save_transfer::handle_code_entry,
+                    save_transfer::receive_clipboard,
+                    save_transfer::handle_dropped_code,
+                    save_transfer::apply_import,
+                    save_transfer::rebuild_save_transfer,
+                )
+                    .chain()
+                    .run_if(in_state(state::GameState::SaveTransfer)),
+            )
             // Pause
             .add_systems(Update, state::toggle_pause)
             .add_systems(OnEnter(state::GameState::Paused), state::show_pause_overlay)
             .add_systems(OnExit(state::GameState::Paused), state::hide_pause_overlay)
             // Shop
-            .init_resource::<shop::ShopCatalog>()
             .init_resource::<shop::PurchaseTracker>()
+            .init_resource::<shop::BuyQuantity>()
             .add_systems(Update, shop::toggle_shop)
             .add_systems(OnEnter(state::GameState::ShopOpen), shop::open_shop)
             .add_systems(OnExit(state::GameState::ShopOpen), shop::close_shop)
@@ -203,13 +381,38 @@ impl Plugin for GameplayPlugin {
                     shop::handle_category_click,
                     shop::handle_buy_click,
                     shop::handle_buy_generator,
+                    shop::handle_quantity_click,
                     shop::handle_equip_click,
                     shop::rebuild_item_list,
                     shop::update_tab_backgrounds,
                     shop::update_shop_buttons,
+                    shop::update_generator_buttons,
                     shop::update_shop_afp,
                 )
                     .run_if(in_state(state::GameState::ShopOpen)),
             );
+
+        // Content files load through the asset server and reload when edited;
+        // headless apps keep the built-in content
+        if app.is_plugin_added::<AssetPlugin>() {
+            app.init_asset::<content::GameContent>()
+                .init_asset::<balance::Balance>()
+                .init_asset_loader::<content::GameContentLoader>()
+                .init_asset_loader::<balance::BalanceLoader>()
+                .insert_resource(content::ContentWatcher::new(&[
+                    content::CONTENT_PATH,
+                    balance::BALANCE_PATH,
+                ]))
+                .add_systems(Startup, (content::load_game_content, balance::load_balance))
+                .add_systems(
+                    Update,
+                    (
+                        content::watch_content_files,
+                        content::apply_game_content,
+                        balance::apply_balance,
+                        content::show_content_errors,
+                    ),
+                );
+        }
     }
 }
@@ -0,0 +1,135 @@
+use super::bignum::BigNum;
+use super::content::GameContent;
+use bevy::prelude::*;
+
+/// Base wisdom granted by a single orb click, before modifiers
+pub const CLICK_BASE: f64 = 1.0;
+
+/// Base AFP awarded per truth, before modifiers
+pub const AFP_BASE: f64 = 10.0;
+
+/// The stat a modifier applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModifierChannel {
+    /// Wisdom gained per orb click
+    Click,
+    /// Wisdom/sec from acolytes and generators
+    Passive,
+    /// Arcane Focus Points awarded per truth
+    Afp,
+}
+
+/// How a modifier combines with the base value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModifierOp {
+    /// Added to the base before any multipliers are applied
+    Add(f64),
+    /// Multiplies the final value
+    Mul(f64),
+}
+
+#[derive(Debug, Clone)]
+pub struct Modifier {
+    /// Stable name of the contributing subsystem (e.g. "school")
+    pub source: &'static str,
+    pub channel: ModifierChannel,
+    pub op: ModifierOp,
+}
+
+/// Registry of every named bonus that feeds into click, passive and AFP rates.
+/// Subsystems overwrite their own entries each frame; consumers only ever call `resolve`.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct WisdomModifiers {
+    entries: Vec<Modifier>,
+}
+
+impl WisdomModifiers {
+    /// Insert or replace the modifier registered by `source` on `channel`
+    pub fn set(&mut self, source: &'static str, channel: ModifierChannel, op: ModifierOp) {
+        if let Some(existing) = self
+            .entries
+            .iter_mut()
+            .find(|m| m.source == source && m.channel == channel)
+        {
+            existing.op = op;
+        } else {
+            // Kept sorted so `resolve` multiplies in the same order whichever source ran first
+            let at = self.entries.partition_point(|m| m.source < source);
+            self.entries.insert(
+                at,
+                Modifier {
+                    source,
+                    channel,
+                    op,
+                },
+            );
+        }
+    }
+
+    /// Shorthand for a multiplicative modifier
+    pub fn mul(&mut self, source: &'static str, channel: ModifierChannel, factor: f64) {
+        self.set(source, channel, ModifierOp::Mul(factor));
+    }
+
+    /// Shorthand for an additive modifier
+    pub fn add(&mut self, source: &'static str, channel: ModifierChannel, amount: f64) {
+        self.set(source, channel, ModifierOp::Add(amount));
+    }
+
+    /// Final value for a channel: (base + additive bonuses) * product of multipliers
+    pub fn resolve(&self, channel: ModifierChannel, base: BigNum) -> BigNum {
+        let mut flat = base;
+        let mut mult = BigNum::from(1.0);
+        for m in self.entries.iter().filter(|m| m.channel == channel) {
+            match m.op {
+                ModifierOp::Add(v) => flat += BigNum::from(v),
+                ModifierOp::Mul(v) => mult *= v,
+            }
+        }
+        flat * mult
+    }
+
+    /// Wisdom gained from one orb click
+    pub fn click_wisdom(&self) -> BigNum {
+        self.resolve(ModifierChannel::Click, BigNum::from(CLICK_BASE))
+    }
+
+    /// Passive wisdom/sec for a given base production
+    pub fn passive_rate(&self, base: f64) -> BigNum {
+        self.resolve(ModifierChannel::Passive, BigNum::from(base))
+    }
+
+    /// Whole AFP awarded for a single truth
+    pub fn afp_per_truth(&self) -> BigNum {
+        self.resolve(ModifierChannel::Afp, BigNum::from(AFP_BASE)).floor()
+    }
+}
+
+/// A resource that feeds named modifiers into the `WisdomModifiers` registry.
+/// Implement this and call `register_modifier_source` to add a new bonus source.
+pub trait ModifierSource: Resource {
+    fn contribute(&self, content: &GameContent, modifiers: &mut WisdomModifiers);
+}
+
+/// All modifier collection runs in this set, between `EconomySet::Input` and
+/// `EconomySet::Production`
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CollectModifiers;
+
+pub fn collect_modifiers<T: ModifierSource>(
+    source: Res<T>,
+    content: Res<GameContent>,
+    mut modifiers: ResMut<WisdomModifiers>,
+) {
+    source.contribute(&content, &mut modifiers);
+}
+
+pub trait ModifierAppExt {
+    fn register_modifier_source<T: ModifierSource>(&mut self) -> &mut Self;
+}
+
+impl ModifierAppExt for App {
+    fn register_modifier_source<T: ModifierSource>(&mut self) -> &mut Self {
+        self.add_systems(FixedUpdate, collect_modifiers::<T>.in_set(CollectModifiers))
+    }
+}
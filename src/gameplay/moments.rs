@@ -1,15 +1,22 @@
+use super::bignum::BigNum;
+use super::content::GameContent;
 use super::generators::GeneratorState;
+use super::input::PlayerInput;
+use super::ledger::{WisdomLedger, WisdomSource};
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
 use super::progression::ArcaneProgress;
 use super::resources::SecondaryResources;
+use super::rng::{GameRng, RngStream};
 use super::schools::SchoolState;
 use super::shop::PurchaseTracker;
 use super::transcendence::TranscendenceState;
 use super::wisdom::WisdomMeter;
 use bevy::prelude::*;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 /// The different bonus effects a Moment of Clarity can grant
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum MomentEffect {
     /// Instantly adds a burst of wisdom
     WisdomBurst,
@@ -22,9 +29,8 @@ pub enum MomentEffect {
 }
 
 impl MomentEffect {
-    fn random() -> Self {
-        let mut rng = rand::thread_rng();
-        match rng.gen_range(0..4) {
+    fn random(rng: &mut GameRng) -> Self {
+        match rng.stream(RngStream::Moments).gen_range(0..4) {
             0 => Self::WisdomBurst,
             1 => Self::WisdomMultiplier,
             2 => Self::AfpBonus,
@@ -81,20 +87,25 @@ pub struct ActiveBuff {
     pub timer: Timer,
 }
 
-impl Default for MomentState {
-    fn default() -> Self {
-        let initial_delay = rand::thread_rng().gen_range(180.0..420.0);
+impl FromWorld for MomentState {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(&mut world.get_resource_or_init::<GameRng>())
+    }
+}
+
+impl MomentState {
+    /// Fresh state for a new run, with the first moment a few minutes away
+    pub fn new(rng: &mut GameRng) -> Self {
+        let initial_delay = rng.stream(RngStream::Moments).gen_range(180.0..420.0);
         Self {
             spawn_timer: Timer::from_seconds(initial_delay, TimerMode::Once),
             pending: None,
             active_buff: None,
         }
     }
-}
 
-impl MomentState {
-    fn reset_spawn_timer(&mut self, frequency_multiplier: f32) {
-        let delay = rand::thread_rng().gen_range(300.0..900.0) / frequency_multiplier;
+    fn reset_spawn_timer(&mut self, rng: &mut GameRng, frequency_multiplier: f32) {
+        let delay = rng.stream(RngStream::Moments).gen_range(300.0..900.0) / frequency_multiplier;
         self.spawn_timer = Timer::from_seconds(delay, TimerMode::Once);
     }
 
@@ -115,10 +126,18 @@ impl MomentState {
     }
 }
 
+impl ModifierSource for MomentState {
+    fn contribute(&self, _content: &GameContent, modifiers: &mut WisdomModifiers) {
+        modifiers.mul("moment", ModifierChannel::Click, self.click_multiplier() as f64);
+        modifiers.mul("moment", ModifierChannel::Passive, self.wisdom_multiplier() as f64);
+    }
+}
+
 /// Ticks timers and spawns new moments
 pub fn update_moments(
     mut moments: ResMut<MomentState>,
     mut resources: ResMut<SecondaryResources>,
+    mut rng: ResMut<GameRng>,
     time: Res<Time>,
     transcendence: Res<TranscendenceState>,
     school: Res<SchoolState>,
@@ -132,7 +151,7 @@ pub fn update_moments(
     if moments.pending.is_none() {
         moments.spawn_timer.tick(time.delta());
         if moments.spawn_timer.just_finished() {
-            let effect = MomentEffect::random();
+            let effect = MomentEffect::random(&mut rng);
             moments.pending = Some(PendingMoment {
                 effect,
                 lifetime: Timer::from_seconds(30.0, TimerMode::Once),
@@ -152,7 +171,7 @@ pub fn update_moments(
     }
     if expired {
         moments.pending = None;
-        moments.reset_spawn_timer(freq_mult);
+        moments.reset_spawn_timer(&mut rng, freq_mult);
     }
 
     // Tick active buff timer
@@ -168,6 +187,12 @@ pub fn update_moments(
     }
 }
 
+/// The player clicked a Moment of Clarity before it faded
+#[derive(Message)]
+pub struct MomentClaimed {
+    pub effect: MomentEffect,
+}
+
 // ========== UI ==========
 
 #[derive(Component)]
@@ -287,62 +312,76 @@ pub fn render_moment_popup(
         });
 }
 
-/// Handles clicking the Moment of Clarity button
-pub fn handle_moment_click(
+/// Queues a click on the Moment of Clarity button for the next economy tick
+pub fn queue_moment_click(
     interactions: Query<&Interaction, (Changed<Interaction>, With<MomentClickArea>)>,
+    mut input: ResMut<PlayerInput>,
+) {
+    if interactions.iter().any(|i| *i == Interaction::Pressed) {
+        input.claim_moment = true;
+    }
+}
+
+/// Claims the pending moment when its button was clicked
+pub fn handle_moment_click(
+    input: Res<PlayerInput>,
     mut moments: ResMut<MomentState>,
     mut wisdom: ResMut<WisdomMeter>,
+    mut ledger: ResMut<WisdomLedger>,
     mut progress: ResMut<ArcaneProgress>,
     generators: Res<GeneratorState>,
     tracker: Res<PurchaseTracker>,
     transcendence: Res<TranscendenceState>,
     school: Res<SchoolState>,
+    content: Res<GameContent>,
+    mut rng: ResMut<GameRng>,
+    mut claimed: MessageWriter<MomentClaimed>,
 ) {
+    if !input.claim_moment {
+        return;
+    }
+    let Some(pending) = moments.pending.take() else {
+        return;
+    };
+
     let freq_mult =
         transcendence.clarity_frequency_multiplier() * school.moment_frequency_multiplier();
     let dur_mult = school.moment_duration_multiplier();
     let burst_mult = school.moment_burst_multiplier();
 
-    for interaction in &interactions {
-        if *interaction != Interaction::Pressed {
-            continue;
+    match pending.effect {
+        MomentEffect::WisdomBurst => {
+            // Grant 10x current per-second production as instant wisdom, minimum 5.0
+            let base_prod = generators.total_base_production(&content);
+            let rate = base_prod
+                * (1.0 + tracker.efficiency_bonus as f64)
+                * tracker.wisdom_speed_bonus as f64;
+            let burst = (rate * 10.0 * burst_mult).max(5.0);
+            ledger.gain(&mut wisdom, WisdomSource::Moment, BigNum::from(burst));
         }
-
-        let Some(pending) = moments.pending.take() else {
-            continue;
-        };
-
-        match pending.effect {
-            MomentEffect::WisdomBurst => {
-                // Grant 10x current per-second production as instant wisdom, minimum 5.0
-                let base_prod = generators.total_base_production();
-                let rate = base_prod
-                    * (1.0 + tracker.efficiency_bonus as f64)
-                    * tracker.wisdom_speed_bonus as f64;
-                let burst = (rate * 10.0 * burst_mult).max(5.0);
-                wisdom.current += burst as f32;
-            }
-            MomentEffect::WisdomMultiplier => {
-                moments.active_buff = Some(ActiveBuff {
-                    effect: MomentEffect::WisdomMultiplier,
-                    timer: Timer::from_seconds(30.0 * dur_mult, TimerMode::Once),
-                });
-            }
-            MomentEffect::AfpBonus => {
-                // Grant 20% of current AFP or minimum 15
-                let bonus = (progress.focus_points / 5).max(15);
-                progress.focus_points += bonus;
-            }
-            MomentEffect::ClickFrenzy => {
-                moments.active_buff = Some(ActiveBuff {
-                    effect: MomentEffect::ClickFrenzy,
-                    timer: Timer::from_seconds(20.0 * dur_mult, TimerMode::Once),
-                });
-            }
+        MomentEffect::WisdomMultiplier => {
+            moments.active_buff = Some(ActiveBuff {
+                effect: MomentEffect::WisdomMultiplier,
+                timer: Timer::from_seconds(30.0 * dur_mult, TimerMode::Once),
+            });
+        }
+        MomentEffect::AfpBonus => {
+            // Grant 20% of current AFP or minimum 15
+            let bonus = (progress.focus_points / 5.0).floor().max(BigNum::from(15.0));
+            progress.focus_points += bonus;
+        }
+        MomentEffect::ClickFrenzy => {
+            moments.active_buff = Some(ActiveBuff {
+                effect: MomentEffect::ClickFrenzy,
+                timer: Timer::from_seconds(20.0 * dur_mult, TimerMode::Once),
+            });
         }
-
-        moments.reset_spawn_timer(freq_mult);
     }
+
+    moments.reset_spawn_timer(&mut rng, freq_mult);
+    claimed.write(MomentClaimed {
+        effect: pending.effect,
+    });
 }
 
 /// Shows a buff indicator in the HUD when a buff is active
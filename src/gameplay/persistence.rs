@@ -1,38 +1,53 @@
 use super::achievements::{AchievementId, AchievementTracker};
 use super::acolytes::AcolyteState;
-use super::challenges::{ChallengeId, ChallengeState};
+use super::ascension::{AscensionState, AscensionUpgradeId};
+use super::bignum::BigNum;
+use super::challenges::{ActiveChallenge, ChallengeId, ChallengeState};
 use super::codex::{TruthCategory, TruthCodex};
+use super::content::GameContent;
+use super::economy::EconomyModel;
 use super::generators::GeneratorState;
-use super::layers::{ContentLayer, LayerState};
+use super::layers::{ContentLayer, LayerState, VOID_INTERVAL};
+use super::logbook::{Logbook, LogbookEntry};
+use super::ledger::{WisdomLedger, WisdomSource};
+use super::moments::{ActiveBuff, MomentEffect, MomentState};
+use super::pondering::PonderState;
+use super::profiles::{ActiveProfile, Profile};
 use super::progression::ArcaneProgress;
 use super::resources::SecondaryResources;
+use super::rng::{GameRng, RngSnapshot};
+use super::save_files::{read_save, write_save, BackupSettings, LoadedSave};
+use super::save_storage::{SaveStorage, SaveStore};
+use super::save_migration::{SaveLoadError, SAVE_VERSION};
 use super::schools::{SchoolOfThought, SchoolState};
 use super::shadow_thoughts::ShadowState;
 use super::shop::{PurchaseTracker, ShopItemId};
-use super::synergies::SynergyState;
 use super::transcendence::{EnlightenmentId, TranscendenceState};
 use super::wisdom::WisdomMeter;
+use crate::environment::daynight::DayNightCycle;
 use crate::orb::types::{EquippedOrb, OrbType};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 // ========== SAVE DATA ==========
 
 #[derive(Serialize, Deserialize)]
 pub struct SaveData {
+    /// Schema version, see `save_migration`
     pub version: u32,
     pub timestamp: u64,
 
     // Wisdom
-    pub wisdom_current: f32,
-    pub wisdom_max: f32,
+    pub wisdom_current: BigNum,
+    pub wisdom_max: BigNum,
     pub truths_generated: u32,
 
     // Progression
-    pub focus_points: u64,
+    pub focus_points: BigNum,
     pub total_truths: u32,
 
     // Acolytes
@@ -48,8 +63,18 @@ pub struct SaveData {
     // Transcendence (permanent)
     pub insight: u32,
     pub total_transcendences: u32,
-    pub purchased_enlightenments: Vec<EnlightenmentId>,
-    pub run_wisdom_accumulated: f64,
+    /// Learned enlightenments and their ranks, in tree order
+    pub enlightenment_ranks: Vec<(EnlightenmentId, u32)>,
+    /// Insight paid for those ranks; `None` in saves from before it was recorded
+    pub enlightenment_spent: Option<u32>,
+
+    // Ascension (permanent)
+    pub starlight: u32,
+    pub total_ascensions: u32,
+    pub ascension_upgrades: Vec<AscensionUpgradeId>,
+
+    // Wisdom gained by source, this run and lifetime
+    pub wisdom_ledger: WisdomLedger,
 
     // School
     pub school: SchoolOfThought,
@@ -60,41 +85,87 @@ pub struct SaveData {
     pub lifetime_truths: u32,
 
     // Per-run achievement tracking
-    pub achievement_peak_afp: u64,
+    pub achievement_peak_afp: BigNum,
     pub achievement_deep_focus_uses: u32,
     pub achievement_run_elapsed: f32,
     pub achievement_run_truths: u32,
 
     // Shadow thoughts
-    #[serde(default)]
     pub shadow_count: u32,
-    #[serde(default)]
-    pub shadow_stored_wisdom: f64,
+    pub shadow_stored_wisdom: BigNum,
 
     // Challenges (permanent)
-    #[serde(default)]
     pub completed_challenges: Vec<ChallengeId>,
 
     // Secondary resources (per-run)
-    #[serde(default)]
     pub serenity: f64,
-    #[serde(default)]
     pub curiosity: f64,
-    #[serde(default)]
     pub focus: f64,
 
     // Truth Codex (permanent)
-    #[serde(default)]
     pub discovered_truths: Vec<usize>,
-    #[serde(default)]
     pub completed_codex_categories: Vec<TruthCategory>,
 
     // Content Layers (permanent)
-    #[serde(default)]
     pub unlocked_layers: Vec<ContentLayer>,
+    pub void_essence: u32,
+    pub void_charge: u32,
+
+    // Session state (per-run)
+    pub logbook: Vec<LogbookEntry>,
+    pub active_challenge: Option<ActiveChallenge>,
+    /// Seconds until the next shadow thought; paused while the game is closed
+    pub shadow_spawn_secs: Option<f32>,
+    pub moment_buff: Option<SavedBuff>,
+    /// Seconds until the next moment of clarity; paused while the game is closed
+    pub moment_spawn_secs: Option<f32>,
+    pub deep_focus_remaining: f32,
+    pub deep_focus_cooldown: f32,
+    pub time_of_day: Option<f32>,
+    pub rng: Option<RngSnapshot>,
+}
+
+/// A claimed moment's buff and the seconds it had left
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedBuff {
+    pub effect: MomentEffect,
+    pub remaining_secs: f32,
 }
 
+/// Learned enlightenments in a stable order for the save file
+pub fn enlightenment_ranks(transcendence: &TranscendenceState) -> Vec<(EnlightenmentId, u32)> {
+    EnlightenmentId::ALL
+        .into_iter()
+        .filter(|&id| transcendence.has(id))
+        .map(|id| (id, transcendence.rank(id)))
+        .collect()
+}
+
+/// Most recent logbook entries kept in a save
+const MAX_SAVED_LOG_ENTRIES: usize = 1000;
+
 impl SaveData {
+    /// Save of a brand-new game
+    pub fn new_game() -> Self {
+        Self::capture(
+            &WisdomMeter::default(),
+            &ArcaneProgress::default(),
+            &AcolyteState::default(),
+            &GeneratorState::default(),
+            &PurchaseTracker::default(),
+            &EquippedOrb(OrbType::Crystal),
+            &TranscendenceState::default(),
+            &AscensionState::default(),
+            &SchoolState::default(),
+            &AchievementTracker::default(),
+            &ShadowState::new(&mut GameRng::from_seed(0)),
+            &ChallengeState::default(),
+            &SecondaryResources::default(),
+            &TruthCodex::default(),
+            &LayerState::default(),
+        )
+    }
+
     pub fn capture(
         wisdom: &WisdomMeter,
         progress: &ArcaneProgress,
@@ -103,6 +174,7 @@ impl SaveData {
         tracker: &PurchaseTracker,
         equipped: &EquippedOrb,
         transcendence: &TranscendenceState,
+        ascension: &AscensionState,
         school: &SchoolState,
         achievements: &AchievementTracker,
         shadows: &ShadowState,
@@ -112,7 +184,7 @@ impl SaveData {
         layers: &LayerState,
     ) -> Self {
         Self {
-            version: 1,
+            version: SAVE_VERSION,
             timestamp: now_secs(),
             wisdom_current: wisdom.current,
             wisdom_max: wisdom.max_wisdom,
@@ -121,12 +193,16 @@ impl SaveData {
             total_truths: progress.total_truths,
             acolyte_count: acolytes.count,
             generators_owned: generators.owned,
-            purchased_items: tracker.purchased.iter().copied().collect(),
+            purchased_items: tracker.purchased.iter().cloned().collect(),
             equipped_orb: equipped.0,
             insight: transcendence.insight,
             total_transcendences: transcendence.total_transcendences,
-            purchased_enlightenments: transcendence.purchased_enlightenments.clone(),
-            run_wisdom_accumulated: transcendence.run_wisdom_accumulated,
+            enlightenment_ranks: enlightenment_ranks(transcendence),
+            enlightenment_spent: Some(transcendence.spent),
+            starlight: ascension.starlight,
+            total_ascensions: ascension.total_ascensions,
+            ascension_upgrades: ascension.purchased_upgrades.clone(),
+            wisdom_ledger: WisdomLedger::default(),
             school: school.active,
             school_run_truths: school.run_truths,
             unlocked_achievements: achievements.unlocked.clone(),
@@ -144,9 +220,48 @@ impl SaveData {
             discovered_truths: codex.discovered.iter().copied().collect(),
             completed_codex_categories: codex.completed_categories.clone(),
             unlocked_layers: layers.unlocked.clone(),
+            void_essence: layers.void_essence,
+            void_charge: layers.void_charge,
+            logbook: Vec::new(),
+            active_challenge: challenges.active.clone(),
+            shadow_spawn_secs: Some(shadows.spawn_timer.remaining_secs()),
+            moment_buff: None,
+            moment_spawn_secs: None,
+            deep_focus_remaining: 0.0,
+            deep_focus_cooldown: 0.0,
+            time_of_day: None,
+            rng: None,
         }
     }
 
+    /// Record the logbook, buffs and cooldowns, the time of day and the RNG
+    pub fn capture_session(
+        &mut self,
+        logbook: Option<&Logbook>,
+        moments: &MomentState,
+        ponder: &PonderState,
+        cycle: Option<&DayNightCycle>,
+        rng: &GameRng,
+    ) {
+        if let Some(logbook) = logbook {
+            let skip = logbook.entries.len().saturating_sub(MAX_SAVED_LOG_ENTRIES);
+            self.logbook = logbook.entries[skip..].to_vec();
+        }
+        self.moment_buff = moments.active_buff.as_ref().map(|buff| SavedBuff {
+            effect: buff.effect,
+            remaining_secs: buff.timer.remaining_secs(),
+        });
+        self.moment_spawn_secs = Some(moments.spawn_timer.remaining_secs());
+        self.deep_focus_remaining = if ponder.deep_focus_active {
+            ponder.deep_focus_timer
+        } else {
+            0.0
+        };
+        self.deep_focus_cooldown = ponder.deep_focus_cooldown;
+        self.time_of_day = cycle.map(|c| c.time_of_day);
+        self.rng = Some(rng.snapshot());
+    }
+
     pub fn restore(
         &self,
         wisdom: &mut WisdomMeter,
@@ -156,14 +271,15 @@ impl SaveData {
         tracker: &mut PurchaseTracker,
         equipped: &mut EquippedOrb,
         transcendence: &mut TranscendenceState,
+        ascension: &mut AscensionState,
         school: &mut SchoolState,
         achievements: &mut AchievementTracker,
-        synergies: &mut SynergyState,
         shadows: &mut ShadowState,
         challenges: &mut ChallengeState,
         resources: &mut SecondaryResources,
         codex: &mut TruthCodex,
         layers: &mut LayerState,
+        content: &GameContent,
     ) {
         wisdom.current = self.wisdom_current;
         wisdom.max_wisdom = self.wisdom_max;
@@ -177,14 +293,21 @@ impl SaveData {
         generators.owned = self.generators_owned;
 
         // Restore shop purchases and recalculate bonuses
-        tracker.purchased = self.purchased_items.iter().copied().collect::<HashSet<_>>();
-        tracker.recalculate(self.equipped_orb);
+        tracker.purchased = self.purchased_items.iter().cloned().collect::<HashSet<_>>();
+        tracker.recalculate(self.equipped_orb, content);
         equipped.0 = self.equipped_orb;
 
         transcendence.insight = self.insight;
         transcendence.total_transcendences = self.total_transcendences;
-        transcendence.purchased_enlightenments = self.purchased_enlightenments.clone();
-        transcendence.run_wisdom_accumulated = self.run_wisdom_accumulated;
+        transcendence.ranks = self.enlightenment_ranks.iter().copied().collect();
+
+        ascension.starlight = self.starlight;
+        ascension.total_ascensions = self.total_ascensions;
+        ascension.purchased_upgrades = self.ascension_upgrades.clone();
+
+        transcendence.spent = self
+            .enlightenment_spent
+            .unwrap_or_else(|| transcendence.ranks_at_current_prices(content, ascension));
 
         school.active = self.school;
         school.run_truths = self.school_run_truths;
@@ -195,12 +318,17 @@ impl SaveData {
         achievements.deep_focus_uses = self.achievement_deep_focus_uses;
         achievements.run_elapsed = self.achievement_run_elapsed;
         achievements.run_truths = self.achievement_run_truths;
+        // Older saves may already meet conditions that no message will re-announce
+        achievements.check_unlocks(generators, acolytes, transcendence);
 
         shadows.count = self.shadow_count;
         shadows.stored_wisdom = self.shadow_stored_wisdom;
 
         challenges.completed = self.completed_challenges.clone();
-        challenges.active = None;
+        challenges.active = self.active_challenge.clone();
+        if let Some(secs) = self.shadow_spawn_secs {
+            shadows.spawn_timer = Timer::from_seconds(secs, TimerMode::Once);
+        }
 
         resources.serenity = self.serenity;
         resources.curiosity = self.curiosity;
@@ -214,78 +342,92 @@ impl SaveData {
         if !self.unlocked_layers.is_empty() {
             layers.unlocked = self.unlocked_layers.clone();
         }
-
-        // Recalculate synergies from restored generator state
-        synergies.recalculate(generators);
+        layers.void_essence = self.void_essence;
+        // A hand-edited save could hold any count; past the interval the next truth voids
+        layers.void_charge = self.void_charge.min(VOID_INTERVAL - 1);
     }
-}
 
-// ========== FILE I/O ==========
+    /// Restore session state. Buffs, cooldowns and the day keep running while the
+    /// game is closed, so they are advanced by the `elapsed` seconds away.
+    pub fn restore_session(
+        &self,
+        elapsed: f32,
+        logbook: Option<&mut Logbook>,
+        moments: &mut MomentState,
+        ponder: &mut PonderState,
+        cycle: Option<&mut DayNightCycle>,
+        rng: &mut GameRng,
+    ) {
+        if let Some(logbook) = logbook {
+            logbook.entries = self.logbook.clone();
+        }
+        if let Some(ref snapshot) = self.rng {
+            *rng = GameRng::from_snapshot(snapshot);
+        }
+        if let Some(secs) = self.moment_spawn_secs {
+            moments.spawn_timer = Timer::from_seconds(secs, TimerMode::Once);
+        }
+
+        moments.active_buff = self.moment_buff.as_ref().and_then(|buff| {
+            let remaining = buff.remaining_secs - elapsed;
+            (remaining > 0.0).then(|| ActiveBuff {
+                effect: buff.effect,
+                timer: Timer::from_seconds(remaining, TimerMode::Once),
+            })
+        });
 
-fn save_path() -> PathBuf {
-    let mut path = dirs_or_fallback();
-    path.push("orb_pondering_save.json");
-    path
-}
+        ponder.deep_focus_timer = (self.deep_focus_remaining - elapsed).max(0.0);
+        ponder.deep_focus_active = ponder.deep_focus_timer > 0.0;
+        ponder.deep_focus_cooldown = (self.deep_focus_cooldown - elapsed).max(0.0);
 
-fn dirs_or_fallback() -> PathBuf {
-    if let Some(data_dir) = std::env::var_os("APPDATA") {
-        let mut p = PathBuf::from(data_dir);
-        p.push("OrbPonderingSimulator");
-        let _ = std::fs::create_dir_all(&p);
-        p
-    } else {
-        PathBuf::from(".")
+        if let (Some(cycle), Some(time_of_day)) = (cycle, self.time_of_day) {
+            cycle.time_of_day = (time_of_day + cycle.cycle_speed * elapsed) % 1.0;
+        }
     }
 }
 
-fn now_secs() -> u64 {
+// ========== SAVE I/O ==========
+
+pub fn now_secs() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_secs())
         .unwrap_or(0)
 }
 
-pub fn save_to_disk(data: &SaveData) {
-    let path = save_path();
-    match serde_json::to_string_pretty(data) {
-        Ok(json) => {
-            if let Err(e) = std::fs::write(&path, json) {
-                warn!("Failed to write save file: {}", e);
-            }
-        }
-        Err(e) => warn!("Failed to serialize save: {}", e),
+pub fn save_to_storage(
+    storage: &dyn SaveStorage,
+    profile: &Profile,
+    data: &SaveData,
+    backups: &BackupSettings,
+) {
+    if let Err(e) = write_save(storage, &profile.dir, data, backups) {
+        warn!("Failed to write save file: {}", e);
     }
 }
 
-pub fn load_from_disk() -> Option<SaveData> {
-    let path = save_path();
-    let data = std::fs::read_to_string(&path).ok()?;
-    match serde_json::from_str(&data) {
-        Ok(save) => Some(save),
-        Err(e) => {
-            warn!("Failed to parse save file: {}", e);
-            None
-        }
-    }
+/// Reads and migrates the profile's save file. `Ok(None)` means there is no save yet.
+pub fn load_from_storage(
+    storage: &dyn SaveStorage,
+    profile: &Profile,
+) -> Result<Option<LoadedSave>, SaveLoadError> {
+    read_save(storage, &profile.dir)
 }
 
 // ========== OFFLINE PROGRESSION ==========
 
-/// Maximum offline time in seconds (12 hours)
-const MAX_OFFLINE_SECS: f64 = 12.0 * 60.0 * 60.0;
-
-/// Offline production rate (50% of normal)
-const OFFLINE_RATE: f64 = 0.5;
-
 pub struct OfflineGains {
-    pub wisdom_gained: f32,
+    pub wisdom_gained: BigNum,
     pub truths_earned: u32,
-    pub afp_earned: u64,
+    pub afp_earned: BigNum,
     pub elapsed_secs: u64,
 }
 
-pub fn calculate_offline_gains(save: &SaveData) -> Option<OfflineGains> {
+/// Works out how long the player was away and advances the save accordingly
+pub fn calculate_offline_gains(
+    save: &mut SaveData,
+    content: &GameContent,
+) -> Option<OfflineGains> {
     let now = now_secs();
     if now <= save.timestamp {
         return None;
@@ -296,68 +438,32 @@ pub fn calculate_offline_gains(save: &SaveData) -> Option<OfflineGains> {
         // Less than 1 minute away — skip
         return None;
     }
-    let elapsed = raw_elapsed.min(MAX_OFFLINE_SECS);
-
-    // Calculate passive rate from generators + acolytes
-    // We approximate the rate at save time without synergy recalculation
-    let mut gen_base: f64 = 0.0;
-    for (i, &count) in save.generators_owned.iter().enumerate() {
-        if count > 0 {
-            let base_prod = match i {
-                0 => 0.1,
-                1 => 1.0,
-                2 => 8.0,
-                3 => 47.0,
-                4 => 260.0,
-                5 => 1_400.0,
-                6 => 7_800.0,
-                7 => 44_000.0,
-                _ => 0.0,
-            };
-            gen_base += base_prod * count as f64;
-        }
-    }
+    let elapsed = raw_elapsed.min(content.balance.offline.max_secs);
+    apply_offline_progress(save, elapsed, content)
+}
 
-    let acolyte_rate = save.acolyte_count as f64 * 0.2;
-    let total_passive = gen_base + acolyte_rate;
-    if total_passive <= 0.0 {
+/// Runs the saved economy for `elapsed` seconds at the offline rate and writes the result
+/// back into `save`. Uses the same rules as live play, minus transient buffs.
+pub fn apply_offline_progress(
+    save: &mut SaveData,
+    elapsed: f64,
+    content: &GameContent,
+) -> Option<OfflineGains> {
+    let mut model = EconomyModel::from_save(save, content);
+    model.production_rate = content.balance.offline.rate;
+    if !model.passive_rate().is_positive() {
         return None;
     }
 
-    // Apply offline rate
-    let wisdom_per_sec = total_passive * OFFLINE_RATE;
-    let total_wisdom = wisdom_per_sec * elapsed;
-
-    // Calculate how many truths this would generate
-    let mut wisdom_acc = save.wisdom_current as f64;
-    let mut max_wisdom = save.wisdom_max as f64;
-    let mut truths = 0u32;
-    let mut remaining = total_wisdom;
-
-    while remaining > 0.0 {
-        let needed = max_wisdom - wisdom_acc;
-        if needed <= 0.0 || remaining < needed {
-            wisdom_acc += remaining;
-            remaining = 0.0;
-        } else {
-            remaining -= needed;
-            wisdom_acc = 0.0;
-            truths += 1;
-            max_wisdom *= 1.1; // Default scaling
-        }
-        // Safety cap
-        if truths > 1000 {
-            break;
-        }
-    }
-
-    let afp_earned = truths as u64 * 10;
+    let report = model.advance(elapsed);
+    model.write_to_save(save);
+    save.wisdom_ledger.record(WisdomSource::Offline, report.wisdom_produced);
 
     Some(OfflineGains {
-        wisdom_gained: wisdom_acc as f32 - save.wisdom_current,
-        truths_earned: truths,
-        afp_earned,
-        elapsed_secs: raw_elapsed.min(MAX_OFFLINE_SECS) as u64,
+        wisdom_gained: report.wisdom_produced,
+        truths_earned: report.truths,
+        afp_earned: report.afp_earned,
+        elapsed_secs: elapsed as u64,
     })
 }
 
@@ -375,159 +481,277 @@ impl Default for AutoSaveTimer {
 #[derive(Resource, Default)]
 pub struct OfflineReport(pub Option<OfflineGains>);
 
+/// Set when the save on disk must not be overwritten this session
+#[derive(Resource, Default)]
+pub struct SaveLock(pub bool);
+
+/// Something the player should know about how their save was loaded
+pub struct SaveNotice {
+    pub title: &'static str,
+    pub body: String,
+}
+
+#[derive(Resource, Default)]
+pub struct PendingSaveNotice(pub Option<SaveNotice>);
+
+// ========== SYSTEM PARAMS ==========
+
+/// Every resource that is written into a save
+#[derive(SystemParam)]
+pub struct SaveSources<'w> {
+    pub wisdom: Res<'w, WisdomMeter>,
+    pub progress: Res<'w, ArcaneProgress>,
+    pub acolytes: Res<'w, AcolyteState>,
+    pub generators: Res<'w, GeneratorState>,
+    pub tracker: Res<'w, PurchaseTracker>,
+    pub equipped: Res<'w, EquippedOrb>,
+    pub transcendence: Res<'w, TranscendenceState>,
+    pub ascension: Res<'w, AscensionState>,
+    pub school: Res<'w, SchoolState>,
+    pub achievements: Res<'w, AchievementTracker>,
+    pub shadows: Res<'w, ShadowState>,
+    pub challenges: Res<'w, ChallengeState>,
+    pub resources: Res<'w, SecondaryResources>,
+    pub codex: Res<'w, TruthCodex>,
+    pub layers: Res<'w, LayerState>,
+    pub session: SessionSources<'w>,
+}
+
+/// Session state and the wisdom ledger written into a save; the sky is absent in
+/// headless apps
+#[derive(SystemParam)]
+pub struct SessionSources<'w> {
+    pub logbook: Res<'w, Logbook>,
+    pub moments: Res<'w, MomentState>,
+    pub ponder: Res<'w, PonderState>,
+    pub cycle: Option<Res<'w, DayNightCycle>>,
+    pub rng: Res<'w, GameRng>,
+    pub ledger: Res<'w, WisdomLedger>,
+}
+
+impl SaveSources<'_> {
+    pub fn capture(&self) -> SaveData {
+        let mut save = SaveData::capture(
+            &self.wisdom,
+            &self.progress,
+            &self.acolytes,
+            &self.generators,
+            &self.tracker,
+            &self.equipped,
+            &self.transcendence,
+            &self.ascension,
+            &self.school,
+            &self.achievements,
+            &self.shadows,
+            &self.challenges,
+            &self.resources,
+            &self.codex,
+            &self.layers,
+        );
+        let session = &self.session;
+        save.capture_session(
+            Some(&session.logbook),
+            &session.moments,
+            &session.ponder,
+            session.cycle.as_deref(),
+            &session.rng,
+        );
+        save.wisdom_ledger = session.ledger.clone();
+        save
+    }
+}
+
+/// Every resource a save is restored into
+#[derive(SystemParam)]
+pub struct SaveTargets<'w> {
+    pub wisdom: ResMut<'w, WisdomMeter>,
+    pub progress: ResMut<'w, ArcaneProgress>,
+    pub acolytes: ResMut<'w, AcolyteState>,
+    pub generators: ResMut<'w, GeneratorState>,
+    pub tracker: ResMut<'w, PurchaseTracker>,
+    pub equipped: ResMut<'w, EquippedOrb>,
+    pub transcendence: ResMut<'w, TranscendenceState>,
+    pub ascension: ResMut<'w, AscensionState>,
+    pub school: ResMut<'w, SchoolState>,
+    pub achievements: ResMut<'w, AchievementTracker>,
+    pub shadows: ResMut<'w, ShadowState>,
+    pub challenges: ResMut<'w, ChallengeState>,
+    pub resources: ResMut<'w, SecondaryResources>,
+    pub codex: ResMut<'w, TruthCodex>,
+    pub layers: ResMut<'w, LayerState>,
+    pub content: Res<'w, GameContent>,
+    pub session: SessionTargets<'w>,
+}
+
+/// Session state and the wisdom ledger a save is restored into
+#[derive(SystemParam)]
+pub struct SessionTargets<'w> {
+    pub logbook: ResMut<'w, Logbook>,
+    pub moments: ResMut<'w, MomentState>,
+    pub ponder: ResMut<'w, PonderState>,
+    pub cycle: Option<ResMut<'w, DayNightCycle>>,
+    pub rng: ResMut<'w, GameRng>,
+    pub ledger: ResMut<'w, WisdomLedger>,
+}
+
+impl SaveTargets<'_> {
+    /// Restore `save`, advancing real-time session clocks by `elapsed` seconds
+    pub fn restore(&mut self, save: &SaveData, elapsed: f32) {
+        save.restore(
+            &mut self.wisdom,
+            &mut self.progress,
+            &mut self.acolytes,
+            &mut self.generators,
+            &mut self.tracker,
+            &mut self.equipped,
+            &mut self.transcendence,
+            &mut self.ascension,
+            &mut self.school,
+            &mut self.achievements,
+            &mut self.shadows,
+            &mut self.challenges,
+            &mut self.resources,
+            &mut self.codex,
+            &mut self.layers,
+            &self.content,
+        );
+        let session = &mut self.session;
+        save.restore_session(
+            elapsed,
+            Some(&mut session.logbook),
+            &mut session.moments,
+            &mut session.ponder,
+            session.cycle.as_deref_mut(),
+            &mut session.rng,
+        );
+        *session.ledger = save.wisdom_ledger.clone();
+    }
+}
+
 // ========== SYSTEMS ==========
 
-/// On startup, load save and calculate offline gains
+/// Once a profile is chosen, load its save and calculate offline gains
 pub fn load_game(
-    mut wisdom: ResMut<WisdomMeter>,
-    mut progress: ResMut<ArcaneProgress>,
-    mut acolytes: ResMut<AcolyteState>,
-    mut generators: ResMut<GeneratorState>,
-    mut tracker: ResMut<PurchaseTracker>,
-    mut equipped: ResMut<EquippedOrb>,
-    mut transcendence: ResMut<TranscendenceState>,
-    mut school: ResMut<SchoolState>,
-    mut achievements: ResMut<AchievementTracker>,
-    mut synergies: ResMut<SynergyState>,
-    mut shadows: ResMut<ShadowState>,
-    mut challenges: ResMut<ChallengeState>,
-    mut resources: ResMut<SecondaryResources>,
-    mut codex: ResMut<TruthCodex>,
-    mut layers: ResMut<LayerState>,
+    active: Res<ActiveProfile>,
+    storage: Res<SaveStore>,
+    mut targets: SaveTargets,
     mut offline_report: ResMut<OfflineReport>,
+    mut save_lock: ResMut<SaveLock>,
+    mut notice: ResMut<PendingSaveNotice>,
 ) {
-    let Some(save) = load_from_disk() else {
+    let Some(ref profile) = active.0 else {
         return;
     };
-
-    // Calculate offline gains before restoring
-    let gains = calculate_offline_gains(&save);
-
-    // Restore game state
-    save.restore(
-        &mut wisdom,
-        &mut progress,
-        &mut acolytes,
-        &mut generators,
-        &mut tracker,
-        &mut equipped,
-        &mut transcendence,
-        &mut school,
-        &mut achievements,
-        &mut synergies,
-        &mut shadows,
-        &mut challenges,
-        &mut resources,
-        &mut codex,
-        &mut layers,
-    );
-
-    // Apply offline gains
-    if let Some(ref g) = gains {
-        wisdom.current += g.wisdom_gained;
-        // Process truths earned offline
-        for _ in 0..g.truths_earned {
-            wisdom.truths_generated += 1;
-            progress.total_truths += 1;
-            achievements.lifetime_truths += 1;
-            achievements.run_truths += 1;
+    let loaded = match load_from_storage(&**storage, profile) {
+        Ok(Some(loaded)) => loaded,
+        Ok(None) => return,
+        Err(SaveLoadError::Damaged { reason, kept_at }) => {
+            warn!("Failed to read save file: {}", reason);
+            let body = match kept_at {
+                Some(path) => format!(
+                    "Your save file was damaged and no usable backup was found. \
+                     The damaged file was kept as {}, and a new game has begun.",
+                    file_name(&path)
+                ),
+                None => {
+                    // Saving would overwrite the only copy
+                    save_lock.0 = true;
+                    "Your save file could not be read and no usable backup was found. \
+                     It has been left untouched. Progress made in this session will not be saved."
+                        .to_string()
+                }
+            };
+            notice.0 = Some(SaveNotice {
+                title: "Save Could Not Be Read",
+                body,
+            });
+            return;
         }
-        progress.focus_points += g.afp_earned;
-        // Set wisdom to accumulated amount after truths
-        if g.truths_earned > 0 {
-            // Recalculate max_wisdom after truths
-            let scaling = school.scaling_override().unwrap_or(tracker.scaling_factor);
-            for _ in 0..g.truths_earned {
-                wisdom.max_wisdom *= scaling;
-            }
+        Err(e) => {
+            // Keep the file intact so a newer build can still pick it up
+            warn!("Not loading save: {}", e);
+            save_lock.0 = true;
+            notice.0 = Some(SaveNotice {
+                title: "Save Not Loaded",
+                body: format!(
+                    "The {}. Your save has been left untouched. \
+                     Progress made in this session will not be saved.",
+                    e
+                ),
+            });
+            return;
         }
+    };
+
+    if let Some(backup) = &loaded.recovered_from {
+        let age_mins = now_secs().saturating_sub(backup.timestamp) / 60;
+        let mut body = format!(
+            "Your save file was damaged, so progress was restored from a backup saved {} minutes ago.",
+            age_mins
+        );
+        if loaded.damaged_kept_at.is_none() {
+            // The unreadable file is still in place; don't save over it
+            save_lock.0 = true;
+            body.push_str(" The damaged file could not be moved aside, so progress made in this session will not be saved.");
+        }
+        notice.0 = Some(SaveNotice {
+            title: "Save Restored From Backup",
+            body,
+        });
     }
 
+    // Advance the save through the time spent away before restoring it
+    let mut save = loaded.data;
+    let elapsed = now_secs().saturating_sub(save.timestamp) as f32;
+    let gains = calculate_offline_gains(&mut save, &targets.content);
+    targets.restore(&save, elapsed);
+
     offline_report.0 = gains;
 }
 
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
 /// Auto-save on a timer
 pub fn auto_save(
     mut timer: ResMut<AutoSaveTimer>,
     time: Res<Time>,
-    wisdom: Res<WisdomMeter>,
-    progress: Res<ArcaneProgress>,
-    acolytes: Res<AcolyteState>,
-    generators: Res<GeneratorState>,
-    tracker: Res<PurchaseTracker>,
-    equipped: Res<EquippedOrb>,
-    transcendence: Res<TranscendenceState>,
-    school: Res<SchoolState>,
-    achievements: Res<AchievementTracker>,
-    shadows: Res<ShadowState>,
-    challenges: Res<ChallengeState>,
-    resources: Res<SecondaryResources>,
-    codex: Res<TruthCodex>,
-    layers: Res<LayerState>,
+    sources: SaveSources,
+    save_lock: Res<SaveLock>,
+    backups: Res<BackupSettings>,
+    storage: Res<SaveStore>,
+    active: Res<ActiveProfile>,
 ) {
     timer.0.tick(time.delta());
-    if !timer.0.just_finished() {
+    if !timer.0.just_finished() || save_lock.0 {
         return;
     }
 
-    let data = SaveData::capture(
-        &wisdom,
-        &progress,
-        &acolytes,
-        &generators,
-        &tracker,
-        &equipped,
-        &transcendence,
-        &school,
-        &achievements,
-        &shadows,
-        &challenges,
-        &resources,
-        &codex,
-        &layers,
-    );
-    save_to_disk(&data);
+    if let Some(ref profile) = active.0 {
+        save_to_storage(&**storage, profile, &sources.capture(), &backups);
+    }
 }
 
 /// Save when the app is about to exit
 pub fn save_on_exit(
     mut exit_messages: MessageReader<AppExit>,
-    wisdom: Res<WisdomMeter>,
-    progress: Res<ArcaneProgress>,
-    acolytes: Res<AcolyteState>,
-    generators: Res<GeneratorState>,
-    tracker: Res<PurchaseTracker>,
-    equipped: Res<EquippedOrb>,
-    transcendence: Res<TranscendenceState>,
-    school: Res<SchoolState>,
-    achievements: Res<AchievementTracker>,
-    shadows: Res<ShadowState>,
-    challenges: Res<ChallengeState>,
-    resources: Res<SecondaryResources>,
-    codex: Res<TruthCodex>,
-    layers: Res<LayerState>,
+    sources: SaveSources,
+    save_lock: Res<SaveLock>,
+    backups: Res<BackupSettings>,
+    storage: Res<SaveStore>,
+    active: Res<ActiveProfile>,
 ) {
-    if exit_messages.read().next().is_none() {
+    if exit_messages.read().next().is_none() || save_lock.0 {
         return;
     }
 
-    let data = SaveData::capture(
-        &wisdom,
-        &progress,
-        &acolytes,
-        &generators,
-        &tracker,
-        &equipped,
-        &transcendence,
-        &school,
-        &achievements,
-        &shadows,
-        &challenges,
-        &resources,
-        &codex,
-        &layers,
-    );
-    save_to_disk(&data);
+    if let Some(ref profile) = active.0 {
+        save_to_storage(&**storage, profile, &sources.capture(), &backups);
+    }
 }
 
 // ========== WELCOME-BACK UI ==========
@@ -542,6 +766,7 @@ pub struct WelcomeBackDismiss;
 pub fn show_welcome_back(
     mut commands: Commands,
     report: Res<OfflineReport>,
+    content: Res<GameContent>,
 ) {
     let Some(ref gains) = report.0 else {
         return;
@@ -554,6 +779,12 @@ pub fn show_welcome_back(
     } else {
         format!("{}m", minutes)
     };
+    let offline = &content.balance.offline;
+    let rules = format!(
+        "(Offline production: {:.0}% rate, max {:.0} hours)",
+        offline.rate * 100.0,
+        offline.max_secs / 3600.0
+    );
 
     commands
         .spawn((
@@ -610,15 +841,15 @@ pub fn show_welcome_back(
                         ));
                     }
 
-                    if gains.afp_earned > 0 {
+                    if gains.afp_earned.is_positive() {
                         panel.spawn((
-                            Text::new(format!("+{} Arcane Focus", gains.afp_earned)),
+                            Text::new(format!("+{} Arcane Focus", gains.afp_earned.format_short())),
                             TextFont { font_size: 18.0, ..default() },
                             TextColor(Color::srgb(1.0, 0.85, 0.3)),
                         ));
                     }
 
-                    if gains.wisdom_gained > 0.1 {
+                    if gains.wisdom_gained > BigNum::from(0.1) {
                         panel.spawn((
                             Text::new(format!("+{:.1} wisdom accumulated", gains.wisdom_gained)),
                             TextFont { font_size: 16.0, ..default() },
@@ -627,7 +858,7 @@ pub fn show_welcome_back(
                     }
 
                     panel.spawn((
-                        Text::new("(Offline production: 50% rate, max 12 hours)"),
+                        Text::new(rules),
                         TextFont { font_size: 12.0, ..default() },
                         TextColor(Color::srgba(0.5, 0.5, 0.6, 0.5)),
                     ));
@@ -692,3 +923,398 @@ pub fn auto_dismiss_welcome(
         report.0 = None;
     }
 }
+
+// ========== SAVE NOTICE ==========
+
+#[derive(Component)]
+pub struct SaveNoticePanel;
+
+#[derive(Component)]
+pub struct SaveNoticeDismiss;
+
+/// Tells the player about a refused, recovered or lost save
+pub fn show_save_notice(mut commands: Commands, mut pending: ResMut<PendingSaveNotice>) {
+    let Some(notice) = pending.0.take() else {
+        return;
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.05, 0.85)),
+            SaveNoticePanel,
+        ))
+        .with_children(|backdrop| {
+            backdrop
+                .spawn((
+                    Node {
+                        width: Val::Px(460.0),
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(28.0)),
+                        row_gap: Val::Px(12.0),
+                        border_radius: BorderRadius::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.14, 0.04, 0.06, 0.95)),
+                ))
+                .with_children(|panel| {
+                    panel.spawn((
+                        Text::new(notice.title),
+                        TextFont { font_size: 26.0, ..default() },
+                        TextColor(Color::srgb(1.0, 0.6, 0.5)),
+                    ));
+
+                    panel.spawn((
+                        Text::new(notice.body),
+                        TextFont { font_size: 14.0, ..default() },
+                        TextColor(Color::srgba(0.85, 0.75, 0.75, 0.9)),
+                    ));
+
+                    panel
+                        .spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(28.0), Val::Px(10.0)),
+                                border_radius: BorderRadius::all(Val::Px(4.0)),
+                                margin: UiRect::top(Val::Px(8.0)),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgba(0.8, 0.4, 0.4, 0.8)),
+                            SaveNoticeDismiss,
+                        ))
+                        .with_children(|btn| {
+                            btn.spawn((
+                                Text::new("Understood"),
+                                TextFont { font_size: 16.0, ..default() },
+                                TextColor(Color::srgb(1.0, 1.0, 1.0)),
+                            ));
+                        });
+                });
+        });
+}
+
+/// Closes the save notice; a save lock stays in place
+pub fn handle_save_notice_dismiss(
+    interactions: Query<&Interaction, (Changed<Interaction>, With<SaveNoticeDismiss>)>,
+    mut commands: Commands,
+    panels: Query<Entity, With<SaveNoticePanel>>,
+) {
+    for interaction in &interactions {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        for entity in &panels {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frame-by-frame replay of the live systems: produce, then check for a truth
+    fn run_online(model: &mut EconomyModel, secs: f64) -> u32 {
+        let dt = 1.0 / 60.0;
+        let mut truths = 0;
+        for _ in 0..(secs / dt).round() as u64 {
+            model.wisdom.current += model.passive_rate() * dt;
+            if model.wisdom.current >= model.wisdom.max_wisdom {
+                model.generate_truth();
+                truths += 1;
+            }
+        }
+        truths
+    }
+
+    /// Offline time `t` at the offline rate should land where `t * rate` of live play does
+    fn assert_offline_matches_online(save: SaveData, offline_secs: f64) {
+        let content = GameContent::default();
+        let mut online = EconomyModel::from_save(&save, &content);
+        let online_secs = offline_secs * content.balance.offline.rate;
+        let online_truths = run_online(&mut online, online_secs);
+        let started = save.achievement_run_elapsed;
+
+        let mut offline_save = save;
+        let gains = apply_offline_progress(&mut offline_save, offline_secs, &content)
+            .expect("save has passive production");
+        let offline = EconomyModel::from_save(&offline_save, &content);
+
+        assert!(online_truths > 0, "scenario should produce truths");
+        assert!(
+            gains.truths_earned.abs_diff(online_truths) <= 1,
+            "truths: offline {} vs online {}",
+            gains.truths_earned,
+            online_truths
+        );
+        let afp_ratio = (offline.progress.focus_points / online.progress.focus_points).to_f64();
+        assert!((afp_ratio - 1.0).abs() < 0.05, "AFP ratio {afp_ratio}");
+        assert_eq!(offline.school.run_truths, gains.truths_earned);
+        // Time away counts toward the run
+        assert_eq!(
+            offline_save.achievement_run_elapsed,
+            started + offline_secs as f32
+        );
+    }
+
+    #[test]
+    fn offline_matches_online_with_generators_and_acolytes() {
+        let mut save = SaveData::new_game();
+        save.acolyte_count = 10;
+        save.generators_owned = [30, 12, 5, 0, 0, 0, 0, 0];
+        assert_offline_matches_online(save, 4.0 * 3600.0);
+    }
+
+    #[test]
+    fn offline_uses_school_scaling_override_and_shop_afp_bonus() {
+        let mut save = SaveData::new_game();
+        save.school = SchoolOfThought::Stoicism;
+        save.purchased_items = vec![ShopItemId::from("ArcaneAmplifier"), ShopItemId::from("CrystalResonance")];
+        save.generators_owned = [20, 10, 2, 0, 0, 0, 0, 0];
+
+        let mut offline_save = SaveData::new_game();
+        offline_save.school = save.school;
+        offline_save.purchased_items = save.purchased_items.clone();
+        offline_save.generators_owned = save.generators_owned;
+        let gains =
+            apply_offline_progress(&mut offline_save, 3600.0, &GameContent::default()).unwrap();
+
+        // Stoicism scales the threshold by 1.07 per truth, not the default 1.1
+        let expected_max = 10.0 * (1.07f32 as f64).powi(gains.truths_earned as i32);
+        let actual_max = offline_save.wisdom_max.to_f64();
+        assert!((actual_max / expected_max - 1.0).abs() < 1e-6);
+
+        // Base 10 AFP plus 15 from the two AFP upgrades
+        let expected_afp = BigNum::from(25.0 * gains.truths_earned as f64);
+        assert_eq!(gains.afp_earned.to_f64(), expected_afp.to_f64());
+
+        assert_offline_matches_online(save, 2.0 * 3600.0);
+    }
+
+    #[test]
+    fn offline_applies_nihilism_ramp() {
+        let mut save = SaveData::new_game();
+        save.school = SchoolOfThought::Nihilism;
+        save.generators_owned = [25, 8, 0, 0, 0, 0, 0, 0];
+
+        let mut ramped = SaveData::new_game();
+        ramped.school = save.school;
+        ramped.generators_owned = save.generators_owned;
+        let gains = apply_offline_progress(&mut ramped, 3600.0, &GameContent::default()).unwrap();
+
+        // Each truth adds +5% generation, starting from 0.5x
+        let model = EconomyModel::from_save(&ramped, &GameContent::default());
+        assert_eq!(model.school.run_truths, gains.truths_earned);
+        let expected = 0.5 + 0.05 * gains.truths_earned as f32;
+        assert!((model.school.passive_multiplier() - expected).abs() < 1e-5);
+
+        assert_offline_matches_online(save, 3.0 * 3600.0);
+    }
+
+    #[test]
+    fn offline_without_production_reports_nothing() {
+        let mut save = SaveData::new_game();
+        assert!(apply_offline_progress(&mut save, 3600.0, &GameContent::default()).is_none());
+    }
+
+    /// A session mid-run: buff and deep focus running, a challenge underway
+    fn session_save() -> SaveData {
+        let mut logbook = Logbook::default();
+        for n in 1..=3 {
+            logbook.entries.push(LogbookEntry {
+                text: format!("Truth {}", n),
+                truth_number: n,
+            });
+        }
+        let mut rng = GameRng::from_seed(1);
+        let moments = MomentState {
+            active_buff: Some(ActiveBuff {
+                effect: MomentEffect::ClickFrenzy,
+                timer: Timer::from_seconds(30.0, TimerMode::Once),
+            }),
+            ..MomentState::new(&mut rng)
+        };
+        let ponder = PonderState {
+            deep_focus_active: true,
+            deep_focus_timer: 8.0,
+            deep_focus_cooldown: 58.0,
+            ..default()
+        };
+        let cycle = DayNightCycle {
+            time_of_day: 0.5,
+            cycle_speed: 0.01,
+        };
+
+        let mut save = SaveData::new_game();
+        save.active_challenge = Some(ActiveChallenge {
+            id: ChallengeId::Silence,
+            elapsed: 120.0,
+            failed: false,
+            progress: 0,
+        });
+        save.shadow_spawn_secs = Some(45.0);
+        save.capture_session(Some(&logbook), &moments, &ponder, Some(&cycle), &rng);
+        save
+    }
+
+    fn restore_session_after(
+        save: &SaveData,
+        elapsed: f32,
+    ) -> (Logbook, MomentState, PonderState, DayNightCycle) {
+        let mut logbook = Logbook::default();
+        let mut rng = GameRng::from_seed(2);
+        let mut moments = MomentState::new(&mut rng);
+        let mut ponder = PonderState::default();
+        let mut cycle = DayNightCycle::default();
+        save.restore_session(
+            elapsed,
+            Some(&mut logbook),
+            &mut moments,
+            &mut ponder,
+            Some(&mut cycle),
+            &mut rng,
+        );
+        (logbook, moments, ponder, cycle)
+    }
+
+    #[test]
+    fn session_survives_a_quick_restart() {
+        let save: SaveData =
+            serde_json::from_str(&serde_json::to_string(&session_save()).unwrap()).unwrap();
+        let (logbook, moments, ponder, cycle) = restore_session_after(&save, 5.0);
+
+        assert_eq!(logbook.entries.len(), 3);
+        assert_eq!(logbook.entries[2].truth_number, 3);
+        let buff = moments.active_buff.expect("buff should still be running");
+        assert!(matches!(buff.effect, MomentEffect::ClickFrenzy));
+        assert!((buff.timer.remaining_secs() - 25.0).abs() < 1e-3);
+        assert!(ponder.deep_focus_active);
+        assert!((ponder.deep_focus_timer - 3.0).abs() < 1e-3);
+        assert!((ponder.deep_focus_cooldown - 53.0).abs() < 1e-3);
+        assert!((cycle.time_of_day - 0.55).abs() < 1e-4);
+    }
+
+    #[test]
+    fn time_away_expires_buffs_but_not_challenges() {
+        let save = session_save();
+        let (_, moments, ponder, cycle) = restore_session_after(&save, 3600.0);
+        assert!(moments.active_buff.is_none());
+        assert!(!ponder.deep_focus_active);
+        assert_eq!(ponder.deep_focus_cooldown, 0.0);
+        assert!(cycle.time_of_day < 1.0);
+
+        // The challenge and shadow timer only advance while playing
+        let mut model = EconomyModel::from_save(&save, &GameContent::default());
+        let active = model.challenges.active.take().expect("challenge should be restored");
+        assert_eq!(active.elapsed, 120.0);
+        let mut shadows = ShadowState::new(&mut GameRng::from_seed(3));
+        save.restore(
+            &mut WisdomMeter::default(),
+            &mut ArcaneProgress::default(),
+            &mut AcolyteState::default(),
+            &mut GeneratorState::default(),
+            &mut PurchaseTracker::default(),
+            &mut EquippedOrb(OrbType::Crystal),
+            &mut TranscendenceState::default(),
+            &mut AscensionState::default(),
+            &mut SchoolState::default(),
+            &mut AchievementTracker::default(),
+            &mut shadows,
+            &mut ChallengeState::default(),
+            &mut SecondaryResources::default(),
+            &mut TruthCodex::default(),
+            &mut LayerState::default(),
+            &GameContent::default(),
+        );
+        assert_eq!(shadows.spawn_timer.remaining_secs(), 45.0);
+    }
+
+    #[test]
+    fn restore_unlocks_achievements_the_save_already_earned() {
+        let mut save = SaveData::new_game();
+        save.generators_owned[0] = 3;
+        save.acolyte_count = 10;
+        let mut achievements = AchievementTracker::default();
+        save.restore(
+            &mut WisdomMeter::default(),
+            &mut ArcaneProgress::default(),
+            &mut AcolyteState::default(),
+            &mut GeneratorState::default(),
+            &mut PurchaseTracker::default(),
+            &mut EquippedOrb(OrbType::Crystal),
+            &mut TranscendenceState::default(),
+            &mut AscensionState::default(),
+            &mut SchoolState::default(),
+            &mut achievements,
+            &mut ShadowState::new(&mut GameRng::from_seed(3)),
+            &mut ChallengeState::default(),
+            &mut SecondaryResources::default(),
+            &mut TruthCodex::default(),
+            &mut LayerState::default(),
+            &GameContent::default(),
+        );
+        assert!(achievements.has(AchievementId::FirstGenerator));
+        assert!(achievements.has(AchievementId::TenAcolytes));
+        assert!(!achievements.has(AchievementId::FirstTruth));
+    }
+
+    #[test]
+    fn out_of_range_void_charge_is_clamped() {
+        let mut save = SaveData::new_game();
+        save.void_charge = 1_000;
+        let model = EconomyModel::from_save(&save, &GameContent::default());
+        assert_eq!(model.layers.void_charge, VOID_INTERVAL - 1);
+        assert_eq!(model.layers.truths_until_void(), 1);
+    }
+
+    #[test]
+    fn loaded_game_draws_the_same_randomness() {
+        let save = session_save();
+        let (_, moments, _, _) = restore_session_after(&save, 0.0);
+        let spawn_secs = save.moment_spawn_secs.unwrap();
+        assert!((moments.spawn_timer.remaining_secs() - spawn_secs).abs() < 1e-3);
+
+        let mut played_on = GameRng::from_snapshot(save.rng.as_ref().unwrap());
+        let mut reloaded = GameRng::from_seed(2);
+        save.restore_session(
+            0.0,
+            None,
+            &mut MomentState::new(&mut GameRng::from_seed(5)),
+            &mut PonderState::default(),
+            None,
+            &mut reloaded,
+        );
+        let next = |rng: &mut GameRng| MomentState::new(rng).spawn_timer.duration();
+        assert_eq!(next(&mut played_on), next(&mut reloaded));
+    }
+
+    #[test]
+    fn saved_logbook_keeps_most_recent_entries() {
+        let mut logbook = Logbook::default();
+        for n in 1..=(MAX_SAVED_LOG_ENTRIES as u32 + 10) {
+            logbook.entries.push(LogbookEntry {
+                text: String::new(),
+                truth_number: n,
+            });
+        }
+        let mut save = SaveData::new_game();
+        let mut rng = GameRng::from_seed(4);
+        save.capture_session(
+            Some(&logbook),
+            &MomentState::new(&mut rng),
+            &PonderState::default(),
+            None,
+            &rng,
+        );
+        assert_eq!(save.logbook.len(), MAX_SAVED_LOG_ENTRIES);
+        assert_eq!(save.logbook[0].truth_number, 11);
+    }
+}
@@ -1,15 +1,10 @@
-use super::achievements::AchievementTracker;
 use super::acolytes::AcolyteState;
-use super::challenges::ChallengeState;
-use super::codex::TruthCodex;
-use super::layers::LayerState;
-use super::moments::MomentState;
+use super::content::GameContent;
+use super::input::PlayerInput;
+use super::ledger::{WisdomLedger, WisdomSource};
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
 use super::resources::SecondaryResources;
-use super::schools::SchoolState;
-use super::shop::PurchaseTracker;
-use super::transcendence::TranscendenceState;
 use super::wisdom::WisdomMeter;
-use crate::environment::daynight::DayNightCycle;
 use crate::orb::types::Orb;
 use bevy::prelude::*;
 
@@ -32,77 +27,57 @@ impl Default for PonderState {
     }
 }
 
+/// Deep Focus was started
+#[derive(Message)]
+pub struct DeepFocusActivated;
+
+impl ModifierSource for PonderState {
+    fn contribute(&self, content: &GameContent, modifiers: &mut WisdomModifiers) {
+        let mult = if self.deep_focus_active {
+            content.balance.deep_focus.click_multiplier
+        } else {
+            1.0
+        };
+        modifiers.mul("deep_focus", ModifierChannel::Click, mult);
+    }
+}
+
 pub fn handle_click_ponder(
-    mouse: Res<ButtonInput<MouseButton>>,
+    input: Res<PlayerInput>,
     mut wisdom: ResMut<WisdomMeter>,
+    mut ledger: ResMut<WisdomLedger>,
     mut ponder: ResMut<PonderState>,
-    tracker: Res<PurchaseTracker>,
-    moments: Res<MomentState>,
-    transcendence: Res<TranscendenceState>,
-    school: Res<SchoolState>,
-    achievements: Res<AchievementTracker>,
-    challenges: Res<ChallengeState>,
+    modifiers: Res<WisdomModifiers>,
     mut resources: ResMut<SecondaryResources>,
-    codex: Res<TruthCodex>,
-    layers: Res<LayerState>,
-    cycle: Res<DayNightCycle>,
-    interactions: Query<&Interaction>,
 ) {
-    if !mouse.just_pressed(MouseButton::Left) {
-        return;
-    }
-
-    // Don't ponder if clicking a UI button
-    for interaction in &interactions {
-        if *interaction == Interaction::Pressed {
-            return;
-        }
+    for _ in 0..input.orb_clicks {
+        ledger.gain(&mut wisdom, WisdomSource::Click, modifiers.click_wisdom());
+        ponder.ponder_intensity = 1.0;
+        resources.curiosity += 1.0;
     }
-
-    let deep_focus_mult = if ponder.deep_focus_active { 3.0 } else { 1.0 };
-    let moment_click_mult = moments.click_multiplier();
-    let enlightenment_mult = transcendence.click_multiplier();
-    let school_click_mult = school.click_multiplier();
-    let achievement_mult = achievements.wisdom_multiplier();
-    let challenge_click_mult = challenges.click_multiplier();
-    let gain = 1.0
-        * (1.0 + tracker.efficiency_bonus)
-        * tracker.wisdom_speed_bonus
-        * deep_focus_mult
-        * moment_click_mult
-        * enlightenment_mult
-        * school_click_mult
-        * achievement_mult
-        * challenge_click_mult
-        * resources.focus_mult()
-        * codex.wisdom_multiplier()
-        * layers.dream_multiplier(&cycle);
-
-    wisdom.current += gain;
-    ponder.ponder_intensity = 1.0;
-    resources.curiosity += 1.0;
 }
 
-pub fn handle_deep_focus(keys: Res<ButtonInput<KeyCode>>, mut ponder: ResMut<PonderState>) {
-    if keys.just_pressed(KeyCode::Space)
+pub fn handle_deep_focus(
+    input: Res<PlayerInput>,
+    mut ponder: ResMut<PonderState>,
+    mut activated: MessageWriter<DeepFocusActivated>,
+    content: Res<GameContent>,
+) {
+    if input.deep_focus
         && ponder.deep_focus_cooldown <= 0.0
         && !ponder.deep_focus_active
     {
         ponder.deep_focus_active = true;
-        ponder.deep_focus_timer = 10.0;
-        ponder.deep_focus_cooldown = 60.0;
+        let balance = &content.balance.deep_focus;
+        ponder.deep_focus_timer = balance.duration_secs;
+        ponder.deep_focus_cooldown = balance.cooldown_secs;
+        activated.write(DeepFocusActivated);
     }
 }
 
-pub fn update_ponder_visuals(
-    mut ponder: ResMut<PonderState>,
-    acolytes: Res<AcolyteState>,
-    time: Res<Time>,
-    mut orb_query: Query<&mut Orb>,
-) {
+/// Ticks the Deep Focus duration and cooldown
+pub fn tick_deep_focus(mut ponder: ResMut<PonderState>, time: Res<Time>) {
     let dt = time.delta_secs();
-
-    // Tick Deep Focus timers
     if ponder.deep_focus_active {
         ponder.deep_focus_timer -= dt;
         if ponder.deep_focus_timer <= 0.0 {
@@ -113,6 +88,15 @@ pub fn update_ponder_visuals(
     if ponder.deep_focus_cooldown > 0.0 {
         ponder.deep_focus_cooldown = (ponder.deep_focus_cooldown - dt).max(0.0);
     }
+}
+
+pub fn update_ponder_visuals(
+    mut ponder: ResMut<PonderState>,
+    acolytes: Res<AcolyteState>,
+    time: Res<Time>,
+    mut orb_query: Query<&mut Orb>,
+) {
+    let dt = time.delta_secs();
 
     // Base glow from acolytes
     let acolyte_glow = 0.1 + 0.03 * (acolytes.count.min(15) as f32);
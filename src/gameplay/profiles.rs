@@ -0,0 +1,745 @@
+use super::data_dirs::DataDirs;
+use super::save_files::{self, BackupSettings};
+use super::save_storage::{SaveStorage, SaveStore};
+use super::state::GameState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+
+const PROFILES_DIR: &str = "profiles";
+const PROFILE_FILE: &str = "profile.json";
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Longest profile name the picker accepts
+pub const MAX_NAME_LEN: usize = 24;
+
+/// Name given to the profile created on first launch or from a pre-profile save
+const DEFAULT_PROFILE_NAME: &str = "Ponderer";
+
+// ========== PROFILE DATA ==========
+
+/// A named player with its own save, settings and backups
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    /// Holds the save file, profile metadata and the backup ring
+    pub dir: PathBuf,
+    /// Holds `settings.json`; the same as `dir` when data and config share a root
+    pub config_dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProfileMeta {
+    name: String,
+}
+
+/// Settings stored per profile
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ProfileSettings {
+    pub backups: BackupSettings,
+}
+
+/// The profile being played. Saving is skipped while this is `None`.
+#[derive(Resource, Default)]
+pub struct ActiveProfile(pub Option<Profile>);
+
+// ========== STORAGE ==========
+
+/// Trim a typed name and check it fits; `None` if it is unusable
+pub fn clean_name(raw: &str) -> Option<String> {
+    let name = raw.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// Every profile in `dirs`, sorted by name
+pub fn list_profiles(storage: &dyn SaveStorage, dirs: &DataDirs) -> Vec<Profile> {
+    let root = dirs.data.join(PROFILES_DIR);
+    let mut profiles: Vec<Profile> = storage
+        .list_dirs(&root)
+        .into_iter()
+        .filter_map(|id| {
+            let dir = root.join(&id);
+            let json = storage.read(&dir.join(PROFILE_FILE)).ok()?;
+            let meta: ProfileMeta = serde_json::from_slice(&json).ok()?;
+            Some(Profile {
+                name: meta.name,
+                config_dir: dirs.config.join(PROFILES_DIR).join(&id),
+                dir,
+            })
+        })
+        .collect();
+    profiles.sort_by_key(|p| p.name.to_lowercase());
+    profiles
+}
+
+pub fn create_profile(storage: &dyn SaveStorage, dirs: &DataDirs, name: &str) -> io::Result<Profile> {
+    let profile = unused_profile(storage, dirs, name);
+    write_meta(storage, &profile)?;
+    save_settings(storage, &profile, &ProfileSettings::default())?;
+    Ok(profile)
+}
+
+pub fn rename_profile(storage: &dyn SaveStorage, profile: &mut Profile, name: &str) -> io::Result<()> {
+    profile.name = name.to_string();
+    write_meta(storage, profile)
+}
+
+/// Copy a profile's save, settings and backups into a new profile
+pub fn duplicate_profile(
+    storage: &dyn SaveStorage,
+    dirs: &DataDirs,
+    source: &Profile,
+) -> io::Result<Profile> {
+    let name: String = format!("{} (copy)", source.name)
+        .chars()
+        .take(MAX_NAME_LEN)
+        .collect();
+    let profile = unused_profile(storage, dirs, &name);
+    storage.copy_dir(&source.dir, &profile.dir)?;
+    if source.config_dir != source.dir {
+        storage.copy_dir(&source.config_dir, &profile.config_dir)?;
+    }
+    write_meta(storage, &profile)?;
+    Ok(profile)
+}
+
+pub fn delete_profile(storage: &dyn SaveStorage, profile: &Profile) -> io::Result<()> {
+    storage.remove_dir(&profile.dir)?;
+    if profile.config_dir != profile.dir {
+        storage.remove_dir(&profile.config_dir)?;
+    }
+    Ok(())
+}
+
+pub fn load_settings(storage: &dyn SaveStorage, profile: &Profile) -> ProfileSettings {
+    storage
+        .read(&profile.config_dir.join(SETTINGS_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_settings(
+    storage: &dyn SaveStorage,
+    profile: &Profile,
+    settings: &ProfileSettings,
+) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(settings)?;
+    storage.write(&profile.config_dir.join(SETTINGS_FILE), json.as_bytes())
+}
+
+/// Move a save from before profiles existed, found directly in `from`, into its own profile
+pub fn adopt_legacy_save(
+    storage: &dyn SaveStorage,
+    from: &Path,
+    dirs: &DataDirs,
+) -> io::Result<Option<Profile>> {
+    let legacy = save_files::save_file_path(from);
+    if !storage.exists(&legacy) {
+        return Ok(None);
+    }
+    let profile = create_profile(storage, dirs, DEFAULT_PROFILE_NAME)?;
+    move_file(storage, &legacy, &save_files::save_file_path(&profile.dir))?;
+    let backups = save_files::backup_dir(from);
+    if !storage.list(&backups).is_empty() {
+        storage.copy_dir(&backups, &save_files::backup_dir(&profile.dir))?;
+        storage.remove_dir(&backups)?;
+    }
+    info!("Moved save from {} into profile {}", from.display(), profile.name);
+    Ok(Some(profile))
+}
+
+/// Rename, or copy and remove when a rename can't cross filesystems
+fn move_file(storage: &dyn SaveStorage, from: &Path, to: &Path) -> io::Result<()> {
+    if storage.rename(from, to).is_ok() {
+        return Ok(());
+    }
+    storage.copy(from, to)?;
+    storage.remove(from)
+}
+
+/// Places saves used to live before profiles and platform directories
+fn legacy_locations(dirs: &DataDirs) -> Vec<PathBuf> {
+    let mut locations = vec![dirs.data.clone()];
+    let cwd = PathBuf::from(".");
+    let same = match (cwd.canonicalize(), dirs.data.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    };
+    if !same {
+        locations.push(cwd);
+    }
+    locations
+}
+
+fn write_meta(storage: &dyn SaveStorage, profile: &Profile) -> io::Result<()> {
+    let meta = ProfileMeta {
+        name: profile.name.clone(),
+    };
+    let json = serde_json::to_string_pretty(&meta)?;
+    storage.write(&profile.dir.join(PROFILE_FILE), json.as_bytes())
+}
+
+/// A profile with fresh directories derived from `name`
+fn unused_profile(storage: &dyn SaveStorage, dirs: &DataDirs, name: &str) -> Profile {
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    let slug = if slug.trim_matches('_').is_empty() {
+        "profile".to_string()
+    } else {
+        slug
+    };
+    let mut taken_ids = storage.list_dirs(&dirs.data.join(PROFILES_DIR));
+    taken_ids.extend(storage.list_dirs(&dirs.config.join(PROFILES_DIR)));
+    let taken = |id: &str| taken_ids.iter().any(|t| t == id);
+    let mut id = slug.clone();
+    let mut n = 2;
+    while taken(&id) {
+        id = format!("{}_{}", slug, n);
+        n += 1;
+    }
+    Profile {
+        name: name.to_string(),
+        dir: dirs.data.join(PROFILES_DIR).join(&id),
+        config_dir: dirs.config.join(PROFILES_DIR).join(&id),
+    }
+}
+
+// ========== PICKER STATE ==========
+
+#[derive(Resource)]
+pub struct ProfilePicker {
+    dirs: DataDirs,
+    storage: SaveStore,
+    profiles: Vec<Profile>,
+    selected: usize,
+    /// Name being typed for the selected profile
+    editing: Option<String>,
+    /// Delete was pressed once; the next press deletes
+    confirm_delete: bool,
+    error: Option<String>,
+}
+
+impl ProfilePicker {
+    fn refresh(&mut self, select: Option<&Path>) {
+        self.profiles = list_profiles(&*self.storage, &self.dirs);
+        if let Some(dir) = select {
+            self.selected = self.profiles.iter().position(|p| p.dir == dir).unwrap_or(0);
+        }
+        self.selected = self.selected.min(self.profiles.len().saturating_sub(1));
+    }
+
+    fn report(&mut self, result: io::Result<()>) {
+        self.error = result.err().map(|e| format!("Could not update profiles: {}", e));
+    }
+}
+
+#[derive(Component)]
+pub struct ProfilePickerPanel;
+
+#[derive(Component)]
+pub struct ProfilePickerBody;
+
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum ProfileAction {
+    Select(usize),
+    Play,
+    New,
+    Rename,
+    Duplicate,
+    Delete,
+}
+
+// ========== PICKER SYSTEMS ==========
+
+/// Adopts any legacy save into a profile, then shows the picker
+pub fn open_profile_picker(
+    mut commands: Commands,
+    dirs: Res<DataDirs>,
+    storage: Res<SaveStore>,
+) {
+    let mut picker = ProfilePicker {
+        dirs: dirs.clone(),
+        storage: storage.clone(),
+        profiles: Vec::new(),
+        selected: 0,
+        editing: None,
+        confirm_delete: false,
+        error: None,
+    };
+
+    let mut result = Ok(());
+    for location in legacy_locations(&dirs) {
+        if let Err(e) = adopt_legacy_save(&**storage, &location, &dirs) {
+            result = Err(e);
+        }
+    }
+    if result.is_ok() && list_profiles(&**storage, &dirs).is_empty() {
+        result = create_profile(&**storage, &dirs, DEFAULT_PROFILE_NAME).map(|_| ());
+    }
+    picker.report(result);
+    picker.refresh(None);
+    commands.insert_resource(picker);
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.05, 0.97)),
+            ProfilePickerPanel,
+        ))
+        .with_children(|backdrop| {
+            backdrop.spawn((
+                Node {
+                    width: Val::Px(460.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(24.0)),
+                    row_gap: Val::Px(12.0),
+                    border_radius: BorderRadius::all(Val::Px(10.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.06, 0.04, 0.14, 0.95)),
+                ProfilePickerBody,
+            ));
+        });
+}
+
+pub fn close_profile_picker(
+    mut commands: Commands,
+    panels: Query<Entity, With<ProfilePickerPanel>>,
+) {
+    for entity in &panels {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<ProfilePicker>();
+}
+
+/// Redraws the picker whenever the profile list, selection or edit buffer changes
+pub fn rebuild_profile_picker(
+    mut commands: Commands,
+    picker: Res<ProfilePicker>,
+    bodies: Query<Entity, With<ProfilePickerBody>>,
+) {
+    if !picker.is_changed() {
+        return;
+    }
+
+    for body in &bodies {
+        commands.entity(body).despawn_related::<Children>();
+        commands.entity(body).with_children(|panel| {
+            panel.spawn((
+                Text::new("Who Is Pondering?"),
+                TextFont { font_size: 26.0, ..default() },
+                TextColor(Color::srgb(0.8, 0.7, 1.0)),
+            ));
+
+            panel.spawn((
+                Text::new("Each profile keeps its own save, settings and backups."),
+                TextFont { font_size: 13.0, ..default() },
+                TextColor(Color::srgba(0.7, 0.65, 0.8, 0.7)),
+            ));
+
+            // Profile rows
+            panel
+                .spawn(Node {
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    margin: UiRect::vertical(Val::Px(6.0)),
+                    ..default()
+                })
+                .with_children(|list| {
+                    for (i, profile) in picker.profiles.iter().enumerate() {
+                        let selected = i == picker.selected;
+                        let label = match (&picker.editing, selected) {
+                            (Some(buffer), true) => format!("{}_", buffer),
+                            _ => profile.name.clone(),
+                        };
+                        list.spawn((
+                            Button,
+                            Node {
+                                width: Val::Percent(100.0),
+                                padding: UiRect::axes(Val::Px(14.0), Val::Px(8.0)),
+                                border_radius: BorderRadius::all(Val::Px(4.0)),
+                                ..default()
+                            },
+                            BackgroundColor(if selected {
+                                Color::srgba(0.45, 0.3, 0.7, 0.7)
+                            } else {
+                                Color::srgba(0.15, 0.1, 0.25, 0.6)
+                            }),
+                            ProfileAction::Select(i),
+                        ))
+                        .with_children(|row| {
+                            row.spawn((
+                                Text::new(label),
+                                TextFont { font_size: 16.0, ..default() },
+                                TextColor(Color::srgb(0.95, 0.9, 1.0)),
+                            ));
+                        });
+                    }
+                });
+
+            if let Some(ref error) = picker.error {
+                panel.spawn((
+                    Text::new(error.clone()),
+                    TextFont { font_size: 13.0, ..default() },
+                    TextColor(Color::srgb(1.0, 0.5, 0.45)),
+                ));
+            }
+
+            let hint = if picker.editing.is_some() {
+                "Type a name, Enter to confirm, Esc to cancel"
+            } else {
+                "Up/Down to choose, Enter to play"
+            };
+            panel.spawn((
+                Text::new(hint),
+                TextFont { font_size: 12.0, ..default() },
+                TextColor(Color::srgba(0.5, 0.5, 0.6, 0.6)),
+            ));
+
+            // Actions
+            let has_profile = !picker.profiles.is_empty();
+            let delete_label = if picker.confirm_delete {
+                "Confirm Delete"
+            } else {
+                "Delete"
+            };
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(8.0),
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    let actions = [
+                        (ProfileAction::Play, "Play", has_profile),
+                        (ProfileAction::New, "New", true),
+                        (ProfileAction::Rename, "Rename", has_profile),
+                        (ProfileAction::Duplicate, "Duplicate", has_profile),
+                        (ProfileAction::Delete, delete_label, has_profile),
+                    ];
+                    for (action, label, enabled) in actions {
+                        let color = match (action, enabled) {
+                            (_, false) => Color::srgba(0.3, 0.3, 0.35, 0.5),
+                            (ProfileAction::Play, _) => Color::srgba(0.7, 0.5, 1.0, 0.85),
+                            (ProfileAction::Delete, _) if picker.confirm_delete => {
+                                Color::srgba(0.8, 0.3, 0.3, 0.85)
+                            }
+                            _ => Color::srgba(0.25, 0.18, 0.4, 0.8),
+                        };
+                        row.spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(16.0), Val::Px(8.0)),
+                                border_radius: BorderRadius::all(Val::Px(4.0)),
+                                ..default()
+                            },
+                            BackgroundColor(color),
+                            action,
+                        ))
+                        .with_children(|btn| {
+                            btn.spawn((
+                                Text::new(label),
+                                TextFont { font_size: 15.0, ..default() },
+                                TextColor(Color::srgb(1.0, 1.0, 1.0)),
+                            ));
+                        });
+                    }
+                });
+        });
+    }
+}
+
+pub fn handle_profile_actions(
+    interactions: Query<(&Interaction, &ProfileAction), Changed<Interaction>>,
+    mut picker: ResMut<ProfilePicker>,
+    mut active: ResMut<ActiveProfile>,
+    mut backups: ResMut<BackupSettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, action) in &interactions {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        apply_action(*action, &mut picker, &mut active, &mut backups, &mut next_state);
+    }
+}
+
+/// Keyboard control: typing while renaming, otherwise arrows and Enter
+pub fn handle_profile_keys(
+    mut keyboard: MessageReader<KeyboardInput>,
+    mut picker: ResMut<ProfilePicker>,
+    mut active: ResMut<ActiveProfile>,
+    mut backups: ResMut<BackupSettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for input in keyboard.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+
+        if let Some(buffer) = picker.editing.as_mut() {
+            match &input.logical_key {
+                Key::Character(text) => {
+                    for c in text.chars().filter(|c| !c.is_control()) {
+                        if buffer.chars().count() < MAX_NAME_LEN {
+                            buffer.push(c);
+                        }
+                    }
+                }
+                Key::Space if buffer.chars().count() < MAX_NAME_LEN => buffer.push(' '),
+                Key::Backspace => {
+                    buffer.pop();
+                }
+                Key::Enter => commit_rename(&mut picker),
+                Key::Escape => picker.editing = None,
+                _ => {}
+            }
+            continue;
+        }
+
+        let count = picker.profiles.len();
+        match input.logical_key {
+            Key::ArrowUp if count > 0 => {
+                picker.selected = (picker.selected + count - 1) % count;
+                picker.confirm_delete = false;
+            }
+            Key::ArrowDown if count > 0 => {
+                picker.selected = (picker.selected + 1) % count;
+                picker.confirm_delete = false;
+            }
+            Key::Enter => apply_action(
+                ProfileAction::Play,
+                &mut picker,
+                &mut active,
+                &mut backups,
+                &mut next_state,
+            ),
+            _ => {}
+        }
+    }
+}
+
+fn apply_action(
+    action: ProfileAction,
+    picker: &mut ProfilePicker,
+    active: &mut ActiveProfile,
+    backups: &mut BackupSettings,
+    next_state: &mut NextState<GameState>,
+) {
+    // A half-typed name is committed by any other action
+    if picker.editing.is_some() && action != ProfileAction::Rename {
+        commit_rename(picker);
+    }
+    let confirm_delete = std::mem::take(&mut picker.confirm_delete);
+    let selected = picker.profiles.get(picker.selected).cloned();
+
+    match (action, selected) {
+        (ProfileAction::Select(i), _) => picker.selected = i,
+        (ProfileAction::New, _) => {
+            let name = format!("Ponderer {}", picker.profiles.len() + 1);
+            match create_profile(&*picker.storage, &picker.dirs, &name) {
+                Ok(profile) => {
+                    picker.refresh(Some(&profile.dir));
+                    picker.editing = Some(profile.name);
+                    picker.error = None;
+                }
+                Err(e) => picker.report(Err(e)),
+            }
+        }
+        (ProfileAction::Play, Some(profile)) => {
+            backups.clone_from(&load_settings(&*picker.storage, &profile).backups);
+            active.0 = Some(profile);
+            next_state.set(GameState::Playing);
+        }
+        (ProfileAction::Rename, Some(profile)) => {
+            if picker.editing.is_some() {
+                commit_rename(picker);
+            } else {
+                picker.editing = Some(profile.name);
+            }
+        }
+        (ProfileAction::Duplicate, Some(profile)) => {
+            match duplicate_profile(&*picker.storage, &picker.dirs, &profile) {
+                Ok(copy) => {
+                    picker.refresh(Some(&copy.dir));
+                    picker.error = None;
+                }
+                Err(e) => picker.report(Err(e)),
+            }
+        }
+        (ProfileAction::Delete, Some(profile)) => {
+            if confirm_delete {
+                let result = delete_profile(&*picker.storage, &profile);
+                picker.report(result);
+                picker.refresh(None);
+            } else {
+                picker.confirm_delete = true;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn commit_rename(picker: &mut ProfilePicker) {
+    let Some(buffer) = picker.editing.take() else {
+        return;
+    };
+    let Some(name) = clean_name(&buffer) else {
+        picker.error = Some(format!("Names must be 1-{} characters", MAX_NAME_LEN));
+        return;
+    };
+    let index = picker.selected;
+    let Some(profile) = picker.profiles.get_mut(index) else {
+        return;
+    };
+    let dir = profile.dir.clone();
+    let result = rename_profile(&*picker.storage, profile, &name);
+    picker.report(result);
+    picker.refresh(Some(&dir));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::persistence::SaveData;
+    use crate::gameplay::save_files::{read_save, write_save};
+    use crate::gameplay::save_storage::{DirectorySync, MemoryStorage};
+    use std::fs;
+
+    /// Separate data and config roots, like an XDG layout. Nothing is created
+    /// on disk unless a test goes through a filesystem backend.
+    fn dirs_under(root: &Path) -> DataDirs {
+        DataDirs {
+            data: root.join("data"),
+            config: root.join("config"),
+        }
+    }
+
+    #[test]
+    fn profiles_keep_separate_saves() {
+        let storage = MemoryStorage::default();
+        let dirs = dirs_under(Path::new("separate"));
+        let alice = create_profile(&storage, &dirs, "Alice").unwrap();
+        let bob = create_profile(&storage, &dirs, "Bob").unwrap();
+
+        let mut save = SaveData::new_game();
+        save.truths_generated = 42;
+        write_save(&storage, &alice.dir, &save, &BackupSettings::default()).unwrap();
+
+        assert_eq!(read_save(&storage, &alice.dir).unwrap().unwrap().data.truths_generated, 42);
+        assert!(read_save(&storage, &bob.dir).unwrap().is_none());
+        assert_eq!(list_profiles(&storage, &dirs), vec![alice, bob]);
+        assert!(!Path::new("separate").exists());
+    }
+
+    #[test]
+    fn same_name_gets_its_own_directory() {
+        let storage = MemoryStorage::default();
+        let dirs = dirs_under(Path::new("same_name"));
+        let a = create_profile(&storage, &dirs, "Test Team").unwrap();
+        let b = create_profile(&storage, &dirs, "Test Team").unwrap();
+        assert_ne!(a.dir, b.dir);
+        assert_eq!(list_profiles(&storage, &dirs).len(), 2);
+    }
+
+    #[test]
+    fn rename_duplicate_and_delete() {
+        let storage = MemoryStorage::default();
+        let dirs = dirs_under(Path::new("actions"));
+        let mut original = create_profile(&storage, &dirs, "Original").unwrap();
+        let mut settings = ProfileSettings::default();
+        settings.backups.keep = 9;
+        save_settings(&storage, &original, &settings).unwrap();
+        write_save(&storage, &original.dir, &SaveData::new_game(), &BackupSettings::default()).unwrap();
+
+        rename_profile(&storage, &mut original, "Renamed").unwrap();
+        let copy = duplicate_profile(&storage, &dirs, &original).unwrap();
+        assert_eq!(copy.name, "Renamed (copy)");
+        assert_eq!(load_settings(&storage, &copy).backups.keep, 9);
+        assert!(read_save(&storage, &copy.dir).unwrap().is_some());
+
+        delete_profile(&storage, &original).unwrap();
+        let names: Vec<String> = list_profiles(&storage, &dirs).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["Renamed (copy)".to_string()]);
+        assert!(storage.list(&original.dir).is_empty());
+    }
+
+    #[test]
+    fn legacy_save_is_adopted_into_a_profile() {
+        let storage = MemoryStorage::default();
+        let dirs = dirs_under(Path::new("legacy"));
+        let old_cwd = Path::new("old_cwd");
+        let mut save = SaveData::new_game();
+        save.truths_generated = 7;
+        write_save(&storage, old_cwd, &save, &BackupSettings::default()).unwrap();
+
+        let profile = adopt_legacy_save(&storage, old_cwd, &dirs)
+            .unwrap()
+            .expect("legacy save should move");
+        assert_eq!(profile.name, DEFAULT_PROFILE_NAME);
+        assert!(profile.dir.starts_with(&dirs.data));
+        assert_eq!(read_save(&storage, &profile.dir).unwrap().unwrap().data.truths_generated, 7);
+        assert!(read_save(&storage, old_cwd).unwrap().is_none());
+        assert!(adopt_legacy_save(&storage, old_cwd, &dirs).unwrap().is_none());
+    }
+
+    #[test]
+    fn settings_live_in_the_config_directory() {
+        let storage = MemoryStorage::default();
+        let dirs = dirs_under(Path::new("config"));
+        let profile = create_profile(&storage, &dirs, "Luna").unwrap();
+        assert!(storage.exists(&profile.config_dir.join(SETTINGS_FILE)));
+        assert!(!storage.exists(&profile.dir.join(SETTINGS_FILE)));
+
+        delete_profile(&storage, &profile).unwrap();
+        assert!(storage.list(&profile.config_dir).is_empty());
+    }
+
+    #[test]
+    fn synced_profiles_mirror_renames_and_deletes() {
+        let root = std::env::temp_dir().join(format!("orb_profiles_sync_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let dirs = dirs_under(&root);
+        let mirror = root.join("mirror");
+        let storage = DirectorySync::new(&dirs.data, &mirror);
+
+        let mut luna = create_profile(&storage, &dirs, "Luna").unwrap();
+        let mirrored = mirror.join(luna.dir.strip_prefix(&dirs.data).unwrap());
+        rename_profile(&storage, &mut luna, "Sol").unwrap();
+        let meta = fs::read_to_string(mirrored.join(PROFILE_FILE)).unwrap();
+        assert!(meta.contains("Sol"));
+
+        delete_profile(&storage, &luna).unwrap();
+        assert!(!mirrored.exists());
+        assert!(list_profiles(&storage, &dirs).is_empty());
+    }
+
+    #[test]
+    fn names_are_trimmed_and_bounded() {
+        assert_eq!(clean_name("  Luna  ").as_deref(), Some("Luna"));
+        assert_eq!(clean_name("   "), None);
+        assert_eq!(clean_name(&"x".repeat(MAX_NAME_LEN + 1)), None);
+    }
+}
@@ -1,13 +1,13 @@
-use super::challenges::ChallengeState;
-use super::schools::SchoolState;
-use super::shop::PurchaseTracker;
+use super::bignum::BigNum;
+use super::economy;
+use super::modifiers::WisdomModifiers;
 use super::wisdom::TruthGenerated;
 use crate::orb::types::OrbType;
 use bevy::prelude::*;
 
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Clone)]
 pub struct ArcaneProgress {
-    pub focus_points: u64,
+    pub focus_points: BigNum,
     pub total_truths: u32,
     pub unlocked_orbs: Vec<OrbType>,
 }
@@ -15,7 +15,7 @@ pub struct ArcaneProgress {
 impl Default for ArcaneProgress {
     fn default() -> Self {
         Self {
-            focus_points: 0,
+            focus_points: BigNum::ZERO,
             total_truths: 0,
             unlocked_orbs: vec![OrbType::Crystal],
         }
@@ -25,13 +25,9 @@ impl Default for ArcaneProgress {
 pub fn award_points(
     mut progress: ResMut<ArcaneProgress>,
     mut truth_messages: MessageReader<TruthGenerated>,
-    tracker: Res<PurchaseTracker>,
-    school: Res<SchoolState>,
-    challenges: Res<ChallengeState>,
+    modifiers: Res<WisdomModifiers>,
 ) {
     for _msg in truth_messages.read() {
-        let base_afp = 10 + tracker.afp_bonus as u64 + school.afp_bonus_per_truth();
-        progress.focus_points += (base_afp as f32 * challenges.afp_multiplier()) as u64;
-        progress.total_truths += 1;
+        economy::award_truth(&mut progress, &modifiers);
     }
 }
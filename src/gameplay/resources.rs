@@ -1,9 +1,12 @@
 use super::acolytes::AcolyteState;
+use super::content::GameContent;
 use super::generators::GeneratorState;
+use super::input::PlayerInput;
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
 use bevy::prelude::*;
 
 /// Three secondary resources that create strategic tension
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Clone)]
 pub struct SecondaryResources {
     /// Patience-gated resource needed for high-tier generators
     pub serenity: f64,
@@ -34,23 +37,31 @@ impl Default for SecondaryResources {
 }
 
 impl SecondaryResources {
-    pub fn focus_mult(&self) -> f32 {
+    pub fn focus_mult(&self) -> f64 {
         if self.focus_active {
-            self.focus_multiplier as f32
+            self.focus_multiplier
         } else {
             1.0
         }
     }
+}
 
-    pub fn focus_mult_f64(&self) -> f64 {
-        if self.focus_active {
-            self.focus_multiplier
-        } else {
-            1.0
-        }
+impl ModifierSource for SecondaryResources {
+    fn contribute(&self, _content: &GameContent, modifiers: &mut WisdomModifiers) {
+        let mult = self.focus_mult();
+        modifiers.mul("focus", ModifierChannel::Click, mult);
+        modifiers.mul("focus", ModifierChannel::Passive, mult);
     }
 }
 
+/// Serenity/sec: a small base plus a trickle per acolyte and generator
+pub fn serenity_rate(acolytes: &AcolyteState, generators: &GeneratorState) -> f64 {
+    let base_rate = 0.01;
+    let acolyte_bonus = 0.005 * acolytes.count as f64;
+    let generator_bonus = 0.001 * generators.owned.iter().sum::<u32>() as f64;
+    base_rate + acolyte_bonus + generator_bonus
+}
+
 /// Serenity accumulates passively from acolytes and generators
 pub fn generate_serenity(
     mut resources: ResMut<SecondaryResources>,
@@ -58,22 +69,19 @@ pub fn generate_serenity(
     generators: Res<GeneratorState>,
     time: Res<Time>,
 ) {
-    let base_rate = 0.01;
-    let acolyte_bonus = 0.005 * acolytes.count as f64;
-    let generator_bonus = 0.001 * generators.owned.iter().sum::<u32>() as f64;
-    let rate = base_rate + acolyte_bonus + generator_bonus;
+    let rate = serenity_rate(&acolytes, &generators);
     resources.serenity += rate * time.delta_secs() as f64;
 }
 
 /// Focus regens while inactive, drains while active. [G] to toggle.
 pub fn update_focus(
     mut resources: ResMut<SecondaryResources>,
-    keys: Res<ButtonInput<KeyCode>>,
+    input: Res<PlayerInput>,
     time: Res<Time>,
 ) {
     let dt = time.delta_secs() as f64;
 
-    if keys.just_pressed(KeyCode::KeyG) && !resources.focus_active && resources.focus >= 10.0 {
+    if input.focus && !resources.focus_active && resources.focus >= 10.0 {
         resources.focus_active = true;
     }
 
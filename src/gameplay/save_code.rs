@@ -0,0 +1,157 @@
+use super::persistence::SaveData;
+use super::save_migration::{parse_save, SaveLoadError};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::fmt;
+use std::io::{Read, Write};
+
+/// Every code starts with this, so pasted text from elsewhere is rejected early
+const CODE_PREFIX: &str = "OPS1-";
+
+/// Decompressed saves larger than this are rejected rather than inflated
+const MAX_SAVE_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum SaveCodeError {
+    /// Not one of our codes, or cut short while copying
+    Malformed(&'static str),
+    /// The code decoded but its contents don't match the checksum
+    ChecksumMismatch,
+    /// The embedded save could not be loaded
+    Save(SaveLoadError),
+}
+
+impl fmt::Display for SaveCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(reason) => write!(f, "not a valid save code ({})", reason),
+            Self::ChecksumMismatch => {
+                write!(f, "save code is damaged (checksum mismatch); copy it again")
+            }
+            Self::Save(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SaveCodeError {}
+
+/// Compact text form of a save: prefix, then base64 of a CRC-32 of the JSON
+/// followed by the deflated JSON
+pub fn encode(save: &SaveData) -> String {
+    let json = serde_json::to_vec(save).expect("save data always serializes");
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&json).expect("writing to memory cannot fail");
+    let deflated = encoder.finish().expect("writing to memory cannot fail");
+
+    let mut bytes = crc32fast::hash(&json).to_be_bytes().to_vec();
+    bytes.extend(deflated);
+    format!("{}{}", CODE_PREFIX, URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Validate a code and load the save inside, running save migrations.
+/// Whitespace is ignored so codes wrapped across lines still work.
+pub fn decode(code: &str) -> Result<SaveData, SaveCodeError> {
+    let compact: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    let body = compact
+        .strip_prefix(CODE_PREFIX)
+        .ok_or(SaveCodeError::Malformed("missing OPS1 prefix"))?;
+    let bytes = URL_SAFE_NO_PAD
+        .decode(body)
+        .map_err(|_| SaveCodeError::Malformed("invalid characters"))?;
+    if bytes.len() < 4 {
+        return Err(SaveCodeError::Malformed("too short"));
+    }
+    let (checksum, deflated) = bytes.split_at(4);
+
+    let mut json = Vec::new();
+    DeflateDecoder::new(deflated)
+        .take(MAX_SAVE_BYTES + 1)
+        .read_to_end(&mut json)
+        .map_err(|_| SaveCodeError::ChecksumMismatch)?;
+    if json.len() as u64 > MAX_SAVE_BYTES {
+        return Err(SaveCodeError::Malformed("too large"));
+    }
+    if crc32fast::hash(&json).to_be_bytes() != checksum {
+        return Err(SaveCodeError::ChecksumMismatch);
+    }
+
+    let json = String::from_utf8(json).map_err(|_| SaveCodeError::ChecksumMismatch)?;
+    parse_save(&json).map_err(SaveCodeError::Save)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::achievements::AchievementId;
+    use crate::gameplay::bignum::BigNum;
+    use crate::gameplay::save_migration::SAVE_VERSION;
+
+    fn sample_save() -> SaveData {
+        let mut save = SaveData::new_game();
+        save.insight = 12;
+        save.total_transcendences = 3;
+        save.focus_points = BigNum::new(4.2, 350);
+        save.unlocked_achievements = vec![AchievementId::FirstTruth];
+        save
+    }
+
+    #[test]
+    fn roundtrip_preserves_save() {
+        let code = encode(&sample_save());
+        assert!(code.starts_with(CODE_PREFIX));
+
+        let decoded = decode(&code).unwrap();
+        assert_eq!(decoded.insight, 12);
+        assert_eq!(decoded.total_transcendences, 3);
+        assert_eq!(decoded.focus_points, BigNum::new(4.2, 350));
+        assert_eq!(decoded.unlocked_achievements, vec![AchievementId::FirstTruth]);
+    }
+
+    #[test]
+    fn code_is_smaller_than_json() {
+        let json = serde_json::to_string(&sample_save()).unwrap();
+        assert!(encode(&sample_save()).len() < json.len());
+    }
+
+    #[test]
+    fn wrapped_code_still_decodes() {
+        let code = encode(&sample_save());
+        let wrapped: String = code
+            .as_bytes()
+            .chunks(40)
+            .map(|c| format!("  {}\n", std::str::from_utf8(c).unwrap()))
+            .collect();
+        assert_eq!(decode(&wrapped).unwrap().insight, 12);
+    }
+
+    #[test]
+    fn tampered_code_is_rejected() {
+        let mut code = encode(&sample_save()).into_bytes();
+        let i = code.len() - 3;
+        code[i] = if code[i] == b'A' { b'B' } else { b'A' };
+        let code = String::from_utf8(code).unwrap();
+        assert!(matches!(
+            decode(&code),
+            Err(SaveCodeError::ChecksumMismatch) | Err(SaveCodeError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn foreign_text_is_rejected() {
+        assert!(matches!(decode("hello"), Err(SaveCodeError::Malformed(_))));
+        assert!(matches!(decode("OPS1-!!"), Err(SaveCodeError::Malformed(_))));
+    }
+
+    #[test]
+    fn code_from_newer_build_is_refused() {
+        let mut save = sample_save();
+        save.version = SAVE_VERSION + 1;
+        assert!(matches!(
+            decode(&encode(&save)),
+            Err(SaveCodeError::Save(SaveLoadError::TooNew { .. }))
+        ));
+    }
+}
@@ -0,0 +1,363 @@
+use super::persistence::{now_secs, SaveData};
+use super::save_migration::{parse_save, SaveLoadError};
+use super::save_storage::SaveStorage;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+
+const SAVE_FILE: &str = "orb_pondering_save.json";
+const SAVE_STEM: &str = "orb_pondering_save";
+const BACKUP_DIR: &str = "backups";
+
+/// How many backups of the save file to keep and how often to take one
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct BackupSettings {
+    /// Backups kept in the ring; 0 disables backups
+    pub keep: usize,
+    /// Minimum seconds between two backups, so auto-saves don't flush the ring
+    pub min_interval_secs: u64,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            keep: 5,
+            min_interval_secs: 10 * 60,
+        }
+    }
+}
+
+/// A save read from storage, possibly from a backup
+pub struct LoadedSave {
+    pub data: SaveData,
+    /// Set when the main file was unreadable and this backup was used instead
+    pub recovered_from: Option<Backup>,
+    /// Where the unreadable main file was moved; `None` if it couldn't be moved
+    /// and still sits where the next save would overwrite it
+    pub damaged_kept_at: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// Unix seconds the backup was taken
+    pub timestamp: u64,
+}
+
+/// Path of the save file inside `dir`
+pub fn save_file_path(dir: &Path) -> PathBuf {
+    dir.join(SAVE_FILE)
+}
+
+/// Directory holding the backup ring for the save in `dir`
+pub fn backup_dir(dir: &Path) -> PathBuf {
+    dir.join(BACKUP_DIR)
+}
+
+// ========== WRITING ==========
+
+/// Write `data` as the save in `dir`, backing up the previous save if due.
+/// The storage replaces the file whole, so a crash never leaves a partial save.
+pub fn write_save(
+    storage: &dyn SaveStorage,
+    dir: &Path,
+    data: &SaveData,
+    settings: &BackupSettings,
+) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(data)?;
+    let path = dir.join(SAVE_FILE);
+
+    if storage.exists(&path) {
+        rotate_backups(storage, dir, &path, settings, data.timestamp)
+            .unwrap_or_else(|e| warn!("Failed to back up save file: {}", e));
+    }
+
+    storage.write(&path, json.as_bytes())
+}
+
+/// Back up the current save right away, ignoring `min_interval_secs`.
+/// `now` names the backup only if the save's own timestamp can't be read.
+pub fn force_backup(
+    storage: &dyn SaveStorage,
+    dir: &Path,
+    settings: &BackupSettings,
+    now: u64,
+) -> io::Result<()> {
+    let path = dir.join(SAVE_FILE);
+    if !storage.exists(&path) {
+        return Ok(());
+    }
+    let settings = BackupSettings {
+        min_interval_secs: 0,
+        ..settings.clone()
+    };
+    rotate_backups(storage, dir, &path, &settings, now)
+}
+
+/// When the save in `bytes` was written, so a backup's name tells its real age
+fn saved_at(bytes: &[u8]) -> Option<u64> {
+    #[derive(Deserialize)]
+    struct Stamp {
+        timestamp: u64,
+    }
+    serde_json::from_slice::<Stamp>(bytes).ok().map(|s| s.timestamp)
+}
+
+/// Copy the current save into the backup ring and drop the oldest entries.
+/// The backup is named after the copied save's own timestamp, falling back to `now`.
+fn rotate_backups(
+    storage: &dyn SaveStorage,
+    dir: &Path,
+    save: &Path,
+    settings: &BackupSettings,
+    now: u64,
+) -> io::Result<()> {
+    if settings.keep == 0 {
+        return Ok(());
+    }
+    let bytes = storage.read(save)?;
+    let taken = saved_at(&bytes).unwrap_or(now);
+    let newest = list_backups(storage, dir).first().map(|b| b.timestamp);
+    if newest.is_some_and(|t| taken < t + settings.min_interval_secs || taken <= t) {
+        return Ok(());
+    }
+
+    let backup = dir.join(BACKUP_DIR).join(format!("{}.{}.json", SAVE_STEM, taken));
+    storage.write(&backup, &bytes)?;
+
+    for old in list_backups(storage, dir).iter().skip(settings.keep) {
+        storage.remove(&old.path)?;
+    }
+    Ok(())
+}
+
+/// Backups in `dir`, newest first
+pub fn list_backups(storage: &dyn SaveStorage, dir: &Path) -> Vec<Backup> {
+    let backup_dir = dir.join(BACKUP_DIR);
+    let mut backups: Vec<Backup> = storage
+        .list(&backup_dir)
+        .into_iter()
+        .filter_map(|name| {
+            let timestamp = name
+                .strip_prefix(SAVE_STEM)?
+                .strip_prefix('.')?
+                .strip_suffix(".json")?
+                .parse()
+                .ok()?;
+            Some(Backup {
+                path: backup_dir.join(&name),
+                timestamp,
+            })
+        })
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
+    backups
+}
+
+// ========== READING ==========
+
+/// Read the save in `dir`. `Ok(None)` means there is no save yet.
+/// If the main file can't be read or decoded it is moved aside and the newest
+/// readable backup is used.
+pub fn read_save(
+    storage: &dyn SaveStorage,
+    dir: &Path,
+) -> Result<Option<LoadedSave>, SaveLoadError> {
+    let path = dir.join(SAVE_FILE);
+    if !storage.exists(&path) {
+        return Ok(None);
+    }
+
+    let parsed = read_string(storage, &path)
+        .map_err(|e| SaveLoadError::Unreadable(e.to_string()))
+        .and_then(|json| parse_save(&json));
+    let reason = match parsed {
+        Ok(data) => {
+            return Ok(Some(LoadedSave {
+                data,
+                recovered_from: None,
+                damaged_kept_at: None,
+            }));
+        }
+        Err(SaveLoadError::Unreadable(reason)) => reason,
+        Err(e) => return Err(e),
+    };
+    warn!("Save file is damaged ({}), trying backups", reason);
+
+    // Keep the damaged file around instead of letting the next save overwrite it
+    let kept_at = set_aside(storage, dir, &path);
+
+    for backup in list_backups(storage, dir) {
+        let Ok(json) = read_string(storage, &backup.path) else {
+            continue;
+        };
+        match parse_save(&json) {
+            Ok(data) => {
+                return Ok(Some(LoadedSave {
+                    data,
+                    recovered_from: Some(backup),
+                    damaged_kept_at: kept_at,
+                }));
+            }
+            Err(e) => warn!("Skipping backup {}: {}", backup.path.display(), e),
+        }
+    }
+
+    Err(SaveLoadError::Damaged { reason, kept_at })
+}
+
+/// Move a damaged save to a name of its own, stamped with the time it was found
+fn set_aside(storage: &dyn SaveStorage, dir: &Path, path: &Path) -> Option<PathBuf> {
+    let stamp = now_secs();
+    let mut damaged = dir.join(format!("{}.damaged.{}.json", SAVE_STEM, stamp));
+    let mut n = 2;
+    while storage.exists(&damaged) {
+        damaged = dir.join(format!("{}.damaged.{}_{}.json", SAVE_STEM, stamp, n));
+        n += 1;
+    }
+    match storage.rename(path, &damaged) {
+        Ok(()) => Some(damaged),
+        Err(e) => {
+            warn!("Failed to move damaged save aside: {}", e);
+            None
+        }
+    }
+}
+
+fn read_string(storage: &dyn SaveStorage, path: &Path) -> io::Result<String> {
+    String::from_utf8(storage.read(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::save_storage::{FsStorage, MemoryStorage};
+    use std::fs;
+
+    /// Empty scratch directory unique to one test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("orb_save_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn save_at(timestamp: u64, truths: u32) -> SaveData {
+        let mut save = SaveData::new_game();
+        save.timestamp = timestamp;
+        save.truths_generated = truths;
+        save
+    }
+
+    fn every_save() -> BackupSettings {
+        BackupSettings {
+            keep: 3,
+            min_interval_secs: 0,
+        }
+    }
+
+    #[test]
+    fn write_then_read_roundtrips_without_temp_file() {
+        let dir = scratch_dir("roundtrip");
+        write_save(&FsStorage, &dir, &save_at(100, 7), &every_save()).unwrap();
+
+        let loaded = read_save(&FsStorage, &dir).unwrap().unwrap();
+        assert_eq!(loaded.data.truths_generated, 7);
+        assert!(loaded.recovered_from.is_none());
+        assert!(!dir.join(format!("{}.tmp", SAVE_FILE)).exists());
+    }
+
+    #[test]
+    fn backup_ring_keeps_newest_entries() {
+        let dir = scratch_dir("ring");
+        for t in 0..6 {
+            write_save(&FsStorage, &dir, &save_at(100 + t, t as u32), &every_save()).unwrap();
+        }
+
+        let stamps: Vec<u64> = list_backups(&FsStorage, &dir).iter().map(|b| b.timestamp).collect();
+        // Each backup holds the save written before it, named by that save's time
+        assert_eq!(stamps, vec![104, 103, 102]);
+    }
+
+    #[test]
+    fn backups_respect_min_interval() {
+        let dir = scratch_dir("interval");
+        let settings = BackupSettings {
+            keep: 5,
+            min_interval_secs: 60,
+        };
+        for t in [0, 10, 30, 70, 90] {
+            write_save(&FsStorage, &dir, &save_at(1000 + t, 0), &settings).unwrap();
+        }
+
+        let stamps: Vec<u64> = list_backups(&FsStorage, &dir).iter().map(|b| b.timestamp).collect();
+        assert_eq!(stamps, vec![1070, 1000]);
+    }
+
+    #[test]
+    fn damaged_save_falls_back_to_newest_valid_backup() {
+        let dir = scratch_dir("recover");
+        for t in 0..3 {
+            write_save(&FsStorage, &dir, &save_at(100 + t, t as u32 + 1), &every_save()).unwrap();
+        }
+        // Newest backup is damaged too, so the one before it should be used
+        let backups = list_backups(&FsStorage, &dir);
+        fs::write(&backups[0].path, "{ not json").unwrap();
+        fs::write(dir.join(SAVE_FILE), "{\"version\": 2, \"trunc").unwrap();
+
+        let loaded = read_save(&FsStorage, &dir).unwrap().unwrap();
+        let backup = loaded.recovered_from.expect("should report the backup used");
+        assert_eq!(backup.timestamp, backups[1].timestamp);
+        assert_eq!(loaded.data.truths_generated, 1);
+        let kept = loaded.damaged_kept_at.expect("damaged save should be kept");
+        assert!(kept.starts_with(&dir) && kept.exists());
+        assert!(!dir.join(SAVE_FILE).exists());
+    }
+
+    #[test]
+    fn damaged_save_without_backups_is_an_error() {
+        let dir = scratch_dir("no_backup");
+        fs::write(dir.join(SAVE_FILE), "garbage").unwrap();
+
+        let Err(SaveLoadError::Damaged {
+            kept_at: Some(first),
+            ..
+        }) = read_save(&FsStorage, &dir)
+        else {
+            panic!("expected a damaged save that was moved aside");
+        };
+        assert!(read_save(&FsStorage, &dir).unwrap().is_none());
+
+        // A second damaged save gets its own name instead of replacing the first
+        fs::write(dir.join(SAVE_FILE), "more garbage").unwrap();
+        let Err(SaveLoadError::Damaged {
+            kept_at: Some(second),
+            ..
+        }) = read_save(&FsStorage, &dir)
+        else {
+            panic!("expected a damaged save that was moved aside");
+        };
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(first).unwrap(), "garbage");
+    }
+
+    #[test]
+    fn undecodable_save_is_damaged_not_missing() {
+        let storage = MemoryStorage::default();
+        let dir = Path::new("profile");
+        write_save(&storage, dir, &save_at(100, 4), &every_save()).unwrap();
+        write_save(&storage, dir, &save_at(200, 5), &every_save()).unwrap();
+        storage
+            .write(&dir.join(SAVE_FILE), &[0xff, 0xfe, 0x00])
+            .unwrap();
+
+        let loaded = read_save(&storage, dir)
+            .unwrap()
+            .expect("a backup should load");
+        assert_eq!(loaded.data.truths_generated, 4);
+        assert!(loaded.damaged_kept_at.is_some());
+    }
+}
@@ -0,0 +1,348 @@
+use super::persistence::SaveData;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Schema version written by this build. Bump it together with a new entry in `MIGRATIONS`.
+pub const SAVE_VERSION: u32 = 9;
+
+/// Saves written before the `version` field was read are treated as this version
+const UNVERSIONED: u32 = 1;
+
+/// Rewrites a save object from one version to the next
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[i]` upgrades a save from version `i + 1` to `i + 2`
+const MIGRATIONS: &[Migration] = &[
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+    migrate_v6_to_v7,
+    migrate_v7_to_v8,
+    migrate_v8_to_v9,
+];
+
+// ========== ERRORS ==========
+
+#[derive(Debug)]
+pub enum SaveLoadError {
+    /// The file is not valid JSON or does not match the current schema after migration
+    Unreadable(String),
+    /// The save was written by a newer build; loading it would drop data we don't know about
+    TooNew { found: u32, supported: u32 },
+    /// The save file was unreadable and no backup could stand in for it
+    Damaged {
+        reason: String,
+        /// Where the file was moved; `None` if it is still in place
+        kept_at: Option<PathBuf>,
+    },
+}
+
+impl fmt::Display for SaveLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreadable(reason) | Self::Damaged { reason, .. } => {
+                write!(f, "save file could not be read: {}", reason)
+            }
+            Self::TooNew { found, supported } => write!(
+                f,
+                "save file is format v{} but this build only understands up to v{}; \
+                 update the game to continue this save",
+                found, supported
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveLoadError {}
+
+// ========== LOADING ==========
+
+/// Parse a save file, upgrading older formats to `SAVE_VERSION`
+pub fn parse_save(json: &str) -> Result<SaveData, SaveLoadError> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| SaveLoadError::Unreadable(e.to_string()))?;
+    let Value::Object(mut fields) = value else {
+        return Err(SaveLoadError::Unreadable("top level is not an object".into()));
+    };
+
+    let found = save_version(&fields)?;
+    if found > SAVE_VERSION {
+        return Err(SaveLoadError::TooNew {
+            found,
+            supported: SAVE_VERSION,
+        });
+    }
+
+    for migration in &MIGRATIONS[(found - 1) as usize..] {
+        migration(&mut fields);
+    }
+    fields.insert("version".into(), json!(SAVE_VERSION));
+
+    serde_json::from_value(Value::Object(fields))
+        .map_err(|e| SaveLoadError::Unreadable(e.to_string()))
+}
+
+fn save_version(fields: &Map<String, Value>) -> Result<u32, SaveLoadError> {
+    match fields.get("version") {
+        None => Ok(UNVERSIONED),
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|&v| v >= UNVERSIONED)
+            .ok_or_else(|| SaveLoadError::Unreadable(format!("invalid version {}", v))),
+    }
+}
+
+// ========== MIGRATIONS ==========
+
+/// v1 saves may predate shadows, challenges, secondary resources, the codex and layers.
+/// Fill those in explicitly so the schema no longer needs per-field defaults.
+fn migrate_v1_to_v2(save: &mut Map<String, Value>) {
+    let added = [
+        ("shadow_count", json!(0)),
+        ("shadow_stored_wisdom", json!(0.0)),
+        ("completed_challenges", json!([])),
+        ("serenity", json!(0.0)),
+        ("curiosity", json!(0.0)),
+        ("focus", json!(0.0)),
+        ("discovered_truths", json!([])),
+        ("completed_codex_categories", json!([])),
+        ("unlocked_layers", json!([])),
+    ];
+    for (key, default) in added {
+        save.entry(key).or_insert(default);
+    }
+}
+
+/// v3 saves the session: logbook, active challenge, buffs, cooldowns and clocks.
+/// Older saves start those fresh, as loading used to.
+fn migrate_v2_to_v3(save: &mut Map<String, Value>) {
+    let added = [
+        ("logbook", json!([])),
+        ("active_challenge", Value::Null),
+        ("shadow_spawn_secs", Value::Null),
+        ("moment_buff", Value::Null),
+        ("deep_focus_remaining", json!(0.0)),
+        ("deep_focus_cooldown", json!(0.0)),
+        ("time_of_day", Value::Null),
+    ];
+    for (key, default) in added {
+        save.entry(key).or_insert(default);
+    }
+}
+
+/// v4 stores the run's RNG and the moment timer. Older saves keep the random seed
+/// the game started with.
+fn migrate_v3_to_v4(save: &mut Map<String, Value>) {
+    save.entry("rng").or_insert(Value::Null);
+    save.entry("moment_spawn_secs").or_insert(Value::Null);
+}
+
+/// v5 replaces the single run wisdom total with the wisdom ledger. The old total
+/// can't be split by source, so it carries over as unattributed.
+fn migrate_v4_to_v5(save: &mut Map<String, Value>) {
+    let run_wisdom = save.remove("run_wisdom_accumulated").unwrap_or(json!(0.0));
+    let totals = json!([["Unattributed", run_wisdom]]);
+    save.entry("wisdom_ledger")
+        .or_insert(json!({ "run": totals, "lifetime": totals }));
+}
+
+/// v6 adds the Void layer's essence and its count toward the next voided truth
+fn migrate_v5_to_v6(save: &mut Map<String, Value>) {
+    save.entry("void_essence").or_insert(json!(0));
+    save.entry("void_charge").or_insert(json!(0));
+}
+
+/// v7 adds ascension, the prestige tier above transcendence
+fn migrate_v6_to_v7(save: &mut Map<String, Value>) {
+    save.entry("starlight").or_insert(json!(0));
+    save.entry("total_ascensions").or_insert(json!(0));
+    save.entry("ascension_upgrades").or_insert(json!([]));
+}
+
+/// Exclusive choices introduced in v8: the side a v7 save keeps when it learned
+/// both, the side it loses and that side's v7 Insight price
+const V8_EXCLUSIVE_CHOICES: &[(&str, &str, u64)] = &[("CosmicResonance", "ClarityAffinity", 8)];
+
+/// v8 stores a rank per enlightenment. Everything learned before ranks existed is
+/// rank 1. Where a save learned both sides of what is now an exclusive choice, it
+/// keeps the first and gets back the Insight it paid for the other.
+fn migrate_v7_to_v8(save: &mut Map<String, Value>) {
+    let learned = save.remove("purchased_enlightenments").unwrap_or(json!([]));
+    let mut learned: Vec<Value> = learned.as_array().cloned().unwrap_or_default();
+
+    let frugal = save
+        .get("ascension_upgrades")
+        .and_then(Value::as_array)
+        .is_some_and(|owned| owned.iter().any(|id| id == "FrugalEnlightenment"));
+    let mut refund = 0;
+    for &(keep, drop, price) in V8_EXCLUSIVE_CHOICES {
+        if learned.iter().any(|id| id == keep) && learned.iter().any(|id| id == drop) {
+            learned.retain(|id| id != drop);
+            // Frugal Enlightenment took a quarter off, rounded in the player's favour
+            refund += if frugal { (price * 3).div_ceil(4) } else { price };
+        }
+    }
+    if refund > 0 {
+        let insight = save.get("insight").and_then(Value::as_u64).unwrap_or(0);
+        save.insert("insight".to_string(), json!(insight + refund));
+    }
+
+    let ranks: Vec<Value> = learned.into_iter().map(|id| json!([id, 1])).collect();
+    save.entry("enlightenment_ranks").or_insert(Value::Array(ranks));
+}
+
+/// v9 records the Insight paid for enlightenments. Older saves didn't, so their
+/// ranks are priced when the save is restored.
+fn migrate_v8_to_v9(save: &mut Map<String, Value>) {
+    save.entry("enlightenment_spent").or_insert(Value::Null);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::bignum::BigNum;
+    use crate::gameplay::ledger::WisdomSource;
+    use crate::gameplay::content::GameContent;
+    use crate::gameplay::transcendence::{EnlightenmentDef, EnlightenmentId};
+
+    /// A save as written by the first release, before shadows and later systems existed
+    fn v1_save() -> Value {
+        json!({
+            "version": 1,
+            "timestamp": 1_700_000_000u64,
+            "wisdom_current": 4.5,
+            "wisdom_max": 12.0,
+            "truths_generated": 3,
+            "focus_points": 30.0,
+            "total_truths": 3,
+            "acolyte_count": 2,
+            "generators_owned": [1, 0, 0, 0, 0, 0, 0, 0],
+            "purchased_items": [],
+            "equipped_orb": "Crystal",
+            "insight": 0,
+            "total_transcendences": 0,
+            "purchased_enlightenments": [],
+            "run_wisdom_accumulated": 30.0,
+            "school": "None",
+            "school_run_truths": 3,
+            "unlocked_achievements": [],
+            "lifetime_truths": 3,
+            "achievement_peak_afp": 30.0,
+            "achievement_deep_focus_uses": 0,
+            "achievement_run_elapsed": 120.0,
+            "achievement_run_truths": 3
+        })
+    }
+
+    #[test]
+    fn every_version_has_a_migration() {
+        assert_eq!(MIGRATIONS.len() as u32, SAVE_VERSION - UNVERSIONED);
+    }
+
+    #[test]
+    fn v1_save_migrates_to_current() {
+        let save = parse_save(&v1_save().to_string()).expect("v1 save should load");
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.truths_generated, 3);
+        assert_eq!(save.acolyte_count, 2);
+        assert_eq!(save.generators_owned[0], 1);
+        assert_eq!(save.shadow_count, 0);
+        assert!(save.unlocked_layers.is_empty());
+        assert_eq!((save.void_essence, save.void_charge), (0, 0));
+        assert_eq!((save.starlight, save.total_ascensions), (0, 0));
+        assert!(save.logbook.is_empty());
+        assert!(save.active_challenge.is_none());
+        assert!(save.time_of_day.is_none());
+    }
+
+    #[test]
+    fn run_wisdom_carries_into_ledger() {
+        let save = parse_save(&v1_save().to_string()).unwrap();
+        let ledger = &save.wisdom_ledger;
+        assert_eq!(ledger.run.get(WisdomSource::Unattributed), BigNum::from(30.0));
+        assert_eq!(ledger.run_total(), BigNum::from(30.0));
+        assert_eq!(ledger.lifetime.total(), BigNum::from(30.0));
+    }
+
+    #[test]
+    fn learned_enlightenments_become_rank_one() {
+        let mut value = v1_save();
+        value["purchased_enlightenments"] = json!(["DeepRoots", "HeadStart"]);
+        let save = parse_save(&value.to_string()).unwrap();
+        assert_eq!(
+            save.enlightenment_ranks,
+            vec![(EnlightenmentId::DeepRoots, 1), (EnlightenmentId::HeadStart, 1)]
+        );
+        // What they cost is unknown until the save is restored
+        assert_eq!(save.enlightenment_spent, None);
+    }
+
+    #[test]
+    fn exclusive_choices_keep_one_side_and_refund_the_other() {
+        let content = GameContent::default();
+        let mut value = v1_save();
+        value["insight"] = json!(3);
+        value["purchased_enlightenments"] =
+            json!(["ClarityAffinity", "DeepRoots", "CosmicResonance"]);
+        let save = parse_save(&value.to_string()).unwrap();
+        assert_eq!(
+            save.enlightenment_ranks,
+            vec![(EnlightenmentId::DeepRoots, 1), (EnlightenmentId::CosmicResonance, 1)]
+        );
+        assert_eq!(save.insight, 3 + 8);
+
+        // No exclusive group may hold more than one learned enlightenment
+        let learned: Vec<&EnlightenmentDef> = save
+            .enlightenment_ranks
+            .iter()
+            .map(|&(id, _)| content.enlightenment(id))
+            .collect();
+        for def in &learned {
+            let rivals = learned
+                .iter()
+                .filter(|other| def.exclusive.is_some() && other.exclusive == def.exclusive)
+                .count();
+            assert!(rivals <= 1, "{:?} shares its group", def.id);
+        }
+
+        // The refund matches what Frugal Enlightenment charged
+        value["ascension_upgrades"] = json!(["FrugalEnlightenment"]);
+        let save = parse_save(&value.to_string()).unwrap();
+        assert_eq!(save.insight, 3 + 6);
+    }
+
+    #[test]
+    fn missing_version_is_treated_as_v1() {
+        let mut value = v1_save();
+        value.as_object_mut().unwrap().remove("version");
+        let save = parse_save(&value.to_string()).expect("unversioned save should load");
+        assert_eq!(save.version, SAVE_VERSION);
+    }
+
+    #[test]
+    fn newer_save_is_refused() {
+        let mut value = v1_save();
+        value["version"] = json!(SAVE_VERSION + 1);
+        match parse_save(&value.to_string()) {
+            Err(SaveLoadError::TooNew { found, supported }) => {
+                assert_eq!(found, SAVE_VERSION + 1);
+                assert_eq!(supported, SAVE_VERSION);
+            }
+            other => panic!("expected TooNew, got {:?}", other.map(|s| s.version)),
+        }
+    }
+
+    #[test]
+    fn migration_keeps_existing_values() {
+        let mut value = v1_save();
+        value["shadow_count"] = json!(4);
+        value["serenity"] = json!(12.5);
+        let save = parse_save(&value.to_string()).unwrap();
+        assert_eq!(save.shadow_count, 4);
+        assert_eq!(save.serenity, 12.5);
+    }
+}
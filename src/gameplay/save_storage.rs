@@ -0,0 +1,465 @@
+use super::data_dirs::{flag_value, DataDirs};
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Environment variable naming a folder that every save is mirrored into
+pub const SYNC_DIR_ENV: &str = "ORB_SIM_SYNC_DIR";
+
+/// Command-line flag with the same effect as `SYNC_DIR_ENV`; takes precedence over it
+pub const SYNC_DIR_FLAG: &str = "--sync-dir";
+
+// ========== STORAGE ==========
+
+/// Where save files and their backups live. Paths are the ones the filesystem
+/// would use, so a backend can store them as-is, mirror them or keep them in memory.
+pub trait SaveStorage: Send + Sync {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// Replace the whole file; a crash leaves the old contents or the new, never a mix
+    fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()>;
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+    fn remove(&self, path: &Path) -> io::Result<()>;
+
+    /// Remove `dir` and everything under it; a missing directory is not an error
+    fn remove_dir(&self, dir: &Path) -> io::Result<()>;
+
+    /// Names of the files directly inside `dir`; empty if it doesn't exist
+    fn list(&self, dir: &Path) -> Vec<String>;
+
+    /// Names of the directories directly inside `dir`; empty if it doesn't exist
+    fn list_dirs(&self, dir: &Path) -> Vec<String>;
+
+    fn exists(&self, path: &Path) -> bool {
+        self.read(path).is_ok()
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.write(to, &self.read(from)?)
+    }
+
+    /// Copy every file under `from` to the same place under `to`
+    fn copy_dir(&self, from: &Path, to: &Path) -> io::Result<()> {
+        for name in self.list(from) {
+            self.copy(&from.join(&name), &to.join(&name))?;
+        }
+        for name in self.list_dirs(from) {
+            self.copy_dir(&from.join(&name), &to.join(&name))?;
+        }
+        Ok(())
+    }
+}
+
+/// The storage saves go through, chosen once at startup
+#[derive(Resource, Clone)]
+pub struct SaveStore(pub Arc<dyn SaveStorage>);
+
+impl SaveStore {
+    /// Nothing touches the disk; for tests and throwaway sessions
+    pub fn memory() -> Self {
+        Self(Arc::new(MemoryStorage::default()))
+    }
+}
+
+impl FromWorld for SaveStore {
+    /// Mirrors saves when a sync folder is given, otherwise writes plain files
+    fn from_world(world: &mut World) -> Self {
+        let args = std::env::args().skip(1);
+        match sync_dir(args, |key| std::env::var_os(key)) {
+            Some(mirror) => {
+                let data = world.get_resource_or_init::<DataDirs>().data.clone();
+                info!("Mirroring saves into {}", mirror.display());
+                Self(Arc::new(DirectorySync::new(data, mirror)))
+            }
+            None => Self(Arc::new(FsStorage)),
+        }
+    }
+}
+
+/// The folder given by `--sync-dir`, then `ORB_SIM_SYNC_DIR`
+pub fn sync_dir(
+    args: impl IntoIterator<Item = String>,
+    env: impl Fn(&str) -> Option<OsString>,
+) -> Option<PathBuf> {
+    flag_value(args, SYNC_DIR_FLAG).or_else(|| {
+        env(SYNC_DIR_ENV)
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+impl std::ops::Deref for SaveStore {
+    type Target = dyn SaveStorage;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+// ========== FILESYSTEM ==========
+
+/// Plain files at the given paths
+pub struct FsStorage;
+
+impl SaveStorage for FsStorage {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    /// Writes a temp file and renames it over the old one
+    fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        {
+            let mut file = fs::File::create(&tmp)?;
+            file.write_all(bytes)?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp, path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn remove_dir(&self, dir: &Path) -> io::Result<()> {
+        match fs::remove_dir_all(dir) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    fn list(&self, dir: &Path) -> Vec<String> {
+        entries_of(dir, |t| t.is_file())
+    }
+
+    fn list_dirs(&self, dir: &Path) -> Vec<String> {
+        entries_of(dir, |t| t.is_dir())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        if let Some(dir) = to.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+/// Names of the entries in `dir` whose type passes `keep`
+fn entries_of(dir: &Path, keep: impl Fn(fs::FileType) -> bool) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(&keep))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect()
+}
+
+// ========== MEMORY ==========
+
+/// Files held in memory for the life of the process
+#[derive(Default)]
+pub struct MemoryStorage {
+    files: Mutex<HashMap<PathBuf, Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    fn files(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, Vec<u8>>> {
+        self.files
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, path.display().to_string())
+}
+
+impl SaveStorage for MemoryStorage {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        self.files().insert(path.to_path_buf(), bytes.to_vec());
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let mut files = self.files();
+        let bytes = files.remove(from).ok_or_else(|| not_found(from))?;
+        files.insert(to.to_path_buf(), bytes);
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        self.files()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn remove_dir(&self, dir: &Path) -> io::Result<()> {
+        self.files().retain(|path, _| !path.starts_with(dir));
+        Ok(())
+    }
+
+    fn list(&self, dir: &Path) -> Vec<String> {
+        self.files()
+            .keys()
+            .filter(|path| path.parent() == Some(dir))
+            .filter_map(|path| path.file_name()?.to_str().map(String::from))
+            .collect()
+    }
+
+    /// Directories only exist while they hold a file
+    fn list_dirs(&self, dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .files()
+            .keys()
+            .filter(|path| path.parent() != Some(dir))
+            .filter_map(|path| path.strip_prefix(dir).ok()?.iter().next()?.to_str().map(String::from))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+// ========== DIRECTORY SYNC ==========
+
+/// Plain files under `local`, each copied to the same place under `mirror`
+/// (e.g. a folder another machine or a sync client also writes to).
+/// Reads take whichever copy was written last; a missing or failing mirror
+/// never blocks a save.
+pub struct DirectorySync {
+    local: PathBuf,
+    mirror: PathBuf,
+}
+
+impl DirectorySync {
+    pub fn new(local: impl Into<PathBuf>, mirror: impl Into<PathBuf>) -> Self {
+        Self {
+            local: local.into(),
+            mirror: mirror.into(),
+        }
+    }
+
+    /// Where `path` lives in the mirror; `None` for paths outside the local root
+    fn mirrored(&self, path: &Path) -> Option<PathBuf> {
+        path.strip_prefix(&self.local)
+            .ok()
+            .map(|rel| self.mirror.join(rel))
+    }
+
+    /// Names `list` finds in the local folder or its mirror
+    fn either_copy(&self, dir: &Path, list: impl Fn(&Path) -> Vec<String>) -> Vec<String> {
+        let mut names = list(dir);
+        if let Some(copy) = self.mirrored(dir) {
+            names.extend(list(&copy));
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Apply `op` to the mirror copy too, warning rather than failing
+    fn mirror(&self, path: &Path, op: impl FnOnce(&Path) -> io::Result<()>) {
+        let Some(copy) = self.mirrored(path) else {
+            return;
+        };
+        if let Err(e) = op(&copy) {
+            if e.kind() != io::ErrorKind::NotFound {
+                warn!("Failed to sync {}: {}", copy.display(), e);
+            }
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl SaveStorage for DirectorySync {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let newer_mirror = self
+            .mirrored(path)
+            .filter(|copy| modified(copy) > modified(path));
+        match newer_mirror {
+            Some(copy) => FsStorage.read(&copy),
+            None => FsStorage.read(path),
+        }
+    }
+
+    fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        FsStorage.write(path, bytes)?;
+        self.mirror(path, |copy| FsStorage.write(copy, bytes));
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        FsStorage.rename(from, to)?;
+        if let Some(to) = self.mirrored(to) {
+            self.mirror(from, |copy| FsStorage.rename(copy, &to));
+        }
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        FsStorage.remove(path)?;
+        self.mirror(path, |copy| FsStorage.remove(copy));
+        Ok(())
+    }
+
+    fn remove_dir(&self, dir: &Path) -> io::Result<()> {
+        FsStorage.remove_dir(dir)?;
+        self.mirror(dir, |copy| FsStorage.remove_dir(copy));
+        Ok(())
+    }
+
+    /// Files in either copy
+    fn list(&self, dir: &Path) -> Vec<String> {
+        self.either_copy(dir, |d| FsStorage.list(d))
+    }
+
+    /// Directories in either copy
+    fn list_dirs(&self, dir: &Path) -> Vec<String> {
+        self.either_copy(dir, |d| FsStorage.list_dirs(d))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.is_file() || self.mirrored(path).is_some_and(|copy| copy.is_file())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        self.write(to, &self.read(from)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Empty scratch directory unique to one test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("orb_storage_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn memory_storage_lists_only_direct_children() {
+        let storage = MemoryStorage::default();
+        let dir = Path::new("profiles/luna");
+        storage.write(&dir.join("save.json"), b"{}").unwrap();
+        storage
+            .write(&dir.join("backups/save.1.json"), b"{}")
+            .unwrap();
+        storage
+            .rename(&dir.join("save.json"), &dir.join("old.json"))
+            .unwrap();
+
+        assert_eq!(storage.list(dir), vec!["old.json".to_string()]);
+        assert_eq!(storage.list(&dir.join("backups")).len(), 1);
+        assert!(!storage.exists(&dir.join("save.json")));
+        assert!(storage.remove(&dir.join("save.json")).is_err());
+    }
+
+    #[test]
+    fn memory_storage_copies_and_removes_directories() {
+        let storage = MemoryStorage::default();
+        let (luna, copy) = (Path::new("profiles/luna"), Path::new("profiles/copy"));
+        storage.write(&luna.join("save.json"), b"{}").unwrap();
+        storage.write(&luna.join("backups/save.1.json"), b"{}").unwrap();
+
+        storage.copy_dir(luna, copy).unwrap();
+        assert_eq!(storage.list_dirs(Path::new("profiles")), vec!["copy", "luna"]);
+        assert_eq!(storage.list_dirs(copy), vec!["backups"]);
+
+        storage.remove_dir(luna).unwrap();
+        assert_eq!(storage.list_dirs(Path::new("profiles")), vec!["copy"]);
+        assert!(storage.exists(&copy.join("backups/save.1.json")));
+        assert!(storage.remove_dir(luna).is_ok());
+    }
+
+    #[test]
+    fn directory_sync_mirrors_writes_and_reads_the_newer_copy() {
+        let root = scratch_dir("sync");
+        let (local, mirror) = (root.join("local"), root.join("mirror"));
+        let storage = DirectorySync::new(&local, &mirror);
+        let save = local.join("luna/save.json");
+
+        storage.write(&save, b"from here").unwrap();
+        assert_eq!(
+            fs::read(mirror.join("luna/save.json")).unwrap(),
+            b"from here"
+        );
+
+        // Another machine saves into the mirror later on
+        fs::write(mirror.join("luna/save.json"), b"from there").unwrap();
+        let later = SystemTime::now() + Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(mirror.join("luna/save.json"))
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert_eq!(storage.read(&save).unwrap(), b"from there");
+
+        fs::write(mirror.join("luna/extra.json"), b"").unwrap();
+        assert_eq!(
+            storage.list(&local.join("luna")),
+            vec!["extra.json", "save.json"]
+        );
+    }
+
+    #[test]
+    fn directory_sync_mirrors_directory_removal() {
+        let root = scratch_dir("sync_remove");
+        let (local, mirror) = (root.join("local"), root.join("mirror"));
+        let storage = DirectorySync::new(&local, &mirror);
+        storage.write(&local.join("profiles/luna/save.json"), b"{}").unwrap();
+        storage.write(&local.join("profiles/sol/save.json"), b"{}").unwrap();
+        assert_eq!(storage.list_dirs(&local.join("profiles")), vec!["luna", "sol"]);
+
+        storage.remove_dir(&local.join("profiles/luna")).unwrap();
+        assert!(!mirror.join("profiles/luna").exists());
+        assert_eq!(storage.list_dirs(&local.join("profiles")), vec!["sol"]);
+    }
+
+    #[test]
+    fn sync_dir_comes_from_flag_then_environment() {
+        let env = |key: &str| (key == SYNC_DIR_ENV).then(|| OsString::from("/from/env"));
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let flagged = sync_dir(args(&["--sync-dir=/from/flag"]), env);
+        assert_eq!(flagged, Some(PathBuf::from("/from/flag")));
+        assert_eq!(sync_dir(args(&[]), env), Some(PathBuf::from("/from/env")));
+        assert_eq!(sync_dir(args(&[]), |_| None), None);
+    }
+}
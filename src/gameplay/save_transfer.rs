@@ -0,0 +1,598 @@
+use super::achievements::AchievementId;
+use super::content::GameContent;
+use super::persistence::{now_secs, SaveData, SaveLock, SaveSources, SaveTargets};
+use super::profiles::ActiveProfile;
+use super::save_code;
+use super::save_files::{self, BackupSettings};
+use super::save_storage::SaveStore;
+use super::state::GameState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::tasks::{futures::check_ready, IoTaskPool, Task};
+use bevy::window::FileDragAndDrop;
+use std::fs;
+use std::process::Command;
+
+/// Written into the profile folder on export
+const EXPORT_FILE: &str = "save_code.txt";
+
+/// Read from the profile folder by the "Read import.txt" button
+const IMPORT_FILE: &str = "import.txt";
+
+// ========== RESOURCES ==========
+
+/// State of the open export/import screen
+#[derive(Resource, Default)]
+pub struct SaveTransfer {
+    status: Option<String>,
+    error: Option<String>,
+    /// Decoded save waiting for the player to confirm the overwrite
+    pending: Option<SaveData>,
+    /// Code being typed or pasted into the entry field
+    entry: Option<String>,
+    /// Clipboard read in flight; its text joins the entry field when it lands
+    clipboard: Option<Task<Option<String>>>,
+}
+
+impl SaveTransfer {
+    /// Decode a code and hold it for preview; `source` says where it came from
+    fn stage(&mut self, code: &str, source: &str) {
+        self.status = None;
+        match save_code::decode(code) {
+            Ok(save) => {
+                self.error = None;
+                self.status = Some(format!("Read save code from {}", source));
+                self.pending = Some(save);
+            }
+            Err(e) => {
+                self.error = Some(format!("Could not import: {}", e));
+                self.pending = None;
+            }
+        }
+    }
+
+    /// Whether the paste field has the keyboard
+    pub fn is_typing(&self) -> bool {
+        self.entry.is_some()
+    }
+
+    /// Start reading the clipboard unless a read is already running
+    fn request_clipboard(&mut self) {
+        if self.clipboard.is_none() {
+            self.clipboard = Some(IoTaskPool::get().spawn(async { read_clipboard() }));
+        }
+    }
+
+    /// Clipboard text pasted into the entry field, without line breaks or spaces
+    fn paste(&mut self, text: &str) {
+        if let Some(buffer) = self.entry.as_mut() {
+            buffer.extend(text.chars().filter(|c| !c.is_whitespace()));
+        }
+    }
+
+    /// One key pressed in the paste field
+    fn type_key(&mut self, key: &Key) {
+        let Some(buffer) = self.entry.as_mut() else {
+            return;
+        };
+        match key {
+            Key::Character(text) => buffer.extend(text.chars().filter(|c| !c.is_control())),
+            Key::Backspace => {
+                buffer.pop();
+            }
+            Key::Enter => {
+                let code = std::mem::take(buffer);
+                self.entry = None;
+                self.stage(&code, "the paste field");
+            }
+            Key::Escape => self.entry = None,
+            _ => {}
+        }
+    }
+}
+
+/// Text on the system clipboard. winit has no clipboard access, so this asks
+/// the platform's own tool; `None` when none is installed. The tool is a child
+/// process, so this blocks and runs on the IO task pool.
+fn read_clipboard() -> Option<String> {
+    let tools: &[(&str, &[&str])] = if cfg!(target_os = "windows") {
+        &[("powershell", &["-NoProfile", "-Command", "Get-Clipboard"])]
+    } else if cfg!(target_os = "macos") {
+        &[("pbpaste", &[])]
+    } else {
+        &[
+            ("wl-paste", &["--no-newline"]),
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ]
+    };
+    tools.iter().find_map(|(tool, args)| {
+        let output = Command::new(tool).args(*args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    })
+}
+
+#[derive(Component)]
+pub struct SaveTransferPanel;
+
+#[derive(Component)]
+pub struct SaveTransferBody;
+
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum TransferAction {
+    Export,
+    PasteCode,
+    ReadImportFile,
+    ConfirmImport,
+    CancelImport,
+    Close,
+}
+
+// ========== SYSTEMS ==========
+
+pub fn toggle_save_transfer(
+    keys: Res<ButtonInput<KeyCode>>,
+    current_state: Res<State<GameState>>,
+    transfer: Option<Res<SaveTransfer>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // [E] is just a letter while a code is being typed
+    if transfer.is_some_and(|t| t.is_typing()) {
+        return;
+    }
+    if keys.just_pressed(KeyCode::KeyE) {
+        match current_state.get() {
+            GameState::Playing => next_state.set(GameState::SaveTransfer),
+            GameState::SaveTransfer => next_state.set(GameState::Playing),
+            _ => {}
+        }
+    }
+}
+
+pub fn open_save_transfer(mut commands: Commands) {
+    commands.insert_resource(SaveTransfer::default());
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.05, 0.85)),
+            SaveTransferPanel,
+        ))
+        .with_children(|backdrop| {
+            backdrop.spawn((
+                Node {
+                    width: Val::Px(520.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(24.0)),
+                    row_gap: Val::Px(10.0),
+                    border_radius: BorderRadius::all(Val::Px(10.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.06, 0.04, 0.14, 0.95)),
+                SaveTransferBody,
+            ));
+        });
+}
+
+pub fn close_save_transfer(
+    mut commands: Commands,
+    panels: Query<Entity, With<SaveTransferPanel>>,
+) {
+    for entity in &panels {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<SaveTransfer>();
+}
+
+/// Redraws the screen when the status, error or pending import changes
+pub fn rebuild_save_transfer(
+    mut commands: Commands,
+    transfer: Res<SaveTransfer>,
+    active: Res<ActiveProfile>,
+    content: Res<GameContent>,
+    bodies: Query<Entity, With<SaveTransferBody>>,
+) {
+    if !transfer.is_changed() {
+        return;
+    }
+    let folder = active
+        .0
+        .as_ref()
+        .map(|p| p.dir.display().to_string())
+        .unwrap_or_default();
+
+    for body in &bodies {
+        commands.entity(body).despawn_related::<Children>();
+        commands.entity(body).with_children(|panel| {
+            panel.spawn((
+                Text::new("Transfer Save"),
+                TextFont { font_size: 26.0, ..default() },
+                TextColor(Color::srgb(0.8, 0.7, 1.0)),
+            ));
+
+            panel.spawn((
+                Text::new(format!(
+                    "Export writes a save code to {} in your profile folder. To import, paste a \
+                     code, drop a file containing one onto the window, or save it as {} there.",
+                    EXPORT_FILE, IMPORT_FILE
+                )),
+                TextFont { font_size: 13.0, ..default() },
+                TextColor(Color::srgba(0.7, 0.65, 0.8, 0.8)),
+            ));
+
+            panel.spawn((
+                Text::new(folder.clone()),
+                TextFont { font_size: 11.0, ..default() },
+                TextColor(Color::srgba(0.5, 0.5, 0.6, 0.7)),
+            ));
+
+            if let Some(ref status) = transfer.status {
+                panel.spawn((
+                    Text::new(status.clone()),
+                    TextFont { font_size: 13.0, ..default() },
+                    TextColor(Color::srgb(0.6, 0.9, 1.0)),
+                ));
+            }
+            if let Some(ref error) = transfer.error {
+                panel.spawn((
+                    Text::new(error.clone()),
+                    TextFont { font_size: 13.0, ..default() },
+                    TextColor(Color::srgb(1.0, 0.5, 0.45)),
+                ));
+            }
+
+            // Paste field; long codes show their start and length
+            if let Some(ref entry) = transfer.entry {
+                let shown: String = entry.chars().take(40).collect();
+                let label = if entry.chars().count() > 40 {
+                    format!("{}... ({} characters)_", shown, entry.chars().count())
+                } else {
+                    format!("{}_", shown)
+                };
+                panel
+                    .spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            padding: UiRect::axes(Val::Px(10.0), Val::Px(6.0)),
+                            border_radius: BorderRadius::all(Val::Px(4.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.15, 0.1, 0.25, 0.8)),
+                    ))
+                    .with_children(|field| {
+                        field.spawn((
+                            Text::new(label),
+                            TextFont { font_size: 13.0, ..default() },
+                            TextColor(Color::srgb(0.95, 0.9, 1.0)),
+                        ));
+                    });
+                panel.spawn((
+                    Text::new("Type or paste (Ctrl+V) a code, Enter to read it, Esc to cancel"),
+                    TextFont { font_size: 12.0, ..default() },
+                    TextColor(Color::srgba(0.5, 0.5, 0.6, 0.7)),
+                ));
+            }
+
+            // Preview of the staged import
+            if let Some(ref save) = transfer.pending {
+                panel.spawn((
+                    Node { width: Val::Percent(80.0), height: Val::Px(1.0), ..default() },
+                    BackgroundColor(Color::srgba(0.7, 0.5, 1.0, 0.3)),
+                ));
+                panel.spawn((
+                    Text::new("Imported Save"),
+                    TextFont { font_size: 18.0, ..default() },
+                    TextColor(Color::srgb(1.0, 0.85, 0.3)),
+                ));
+                for line in preview_lines(save, &content) {
+                    panel.spawn((
+                        Text::new(line),
+                        TextFont { font_size: 14.0, ..default() },
+                        TextColor(Color::srgb(0.9, 0.85, 1.0)),
+                    ));
+                }
+                panel.spawn((
+                    Text::new("Importing replaces this profile's current progress. A backup is kept."),
+                    TextFont { font_size: 12.0, ..default() },
+                    TextColor(Color::srgba(1.0, 0.6, 0.5, 0.8)),
+                ));
+            }
+
+            let actions: &[(TransferAction, &str)] = if transfer.pending.is_some() {
+                &[
+                    (TransferAction::ConfirmImport, "Overwrite Save"),
+                    (TransferAction::CancelImport, "Cancel"),
+                ]
+            } else {
+                &[
+                    (TransferAction::Export, "Export"),
+                    (TransferAction::PasteCode, "Paste Code"),
+                    (TransferAction::ReadImportFile, "Read import.txt"),
+                    (TransferAction::Close, "Close"),
+                ]
+            };
+            panel
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(8.0),
+                    margin: UiRect::top(Val::Px(6.0)),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for &(action, label) in actions {
+                        let color = match action {
+                            TransferAction::ConfirmImport => Color::srgba(0.8, 0.35, 0.35, 0.85),
+                            TransferAction::Export => Color::srgba(0.7, 0.5, 1.0, 0.85),
+                            _ => Color::srgba(0.25, 0.18, 0.4, 0.8),
+                        };
+                        row.spawn((
+                            Button,
+                            Node {
+                                padding: UiRect::axes(Val::Px(16.0), Val::Px(8.0)),
+                                border_radius: BorderRadius::all(Val::Px(4.0)),
+                                ..default()
+                            },
+                            BackgroundColor(color),
+                            action,
+                        ))
+                        .with_children(|btn| {
+                            btn.spawn((
+                                Text::new(label),
+                                TextFont { font_size: 15.0, ..default() },
+                                TextColor(Color::srgb(1.0, 1.0, 1.0)),
+                            ));
+                        });
+                    }
+                });
+        });
+    }
+}
+
+/// Export, pasting, reading the import file, cancelling and closing
+pub fn handle_transfer_buttons(
+    interactions: Query<(&Interaction, &TransferAction), Changed<Interaction>>,
+    mut transfer: ResMut<SaveTransfer>,
+    sources: SaveSources,
+    active: Res<ActiveProfile>,
+    storage: Res<SaveStore>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(ref profile) = active.0 else {
+        return;
+    };
+    for (interaction, action) in &interactions {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            TransferAction::Export => {
+                let code = save_code::encode(&sources.capture());
+                let path = profile.dir.join(EXPORT_FILE);
+                match storage.write(&path, code.as_bytes()) {
+                    Ok(()) => {
+                        transfer.error = None;
+                        transfer.status = Some(format!(
+                            "Exported {} characters to {}",
+                            code.len(),
+                            path.display()
+                        ));
+                    }
+                    Err(e) => transfer.error = Some(format!("Could not export: {}", e)),
+                }
+            }
+            TransferAction::PasteCode => {
+                // Start with whatever is on the clipboard; typing adds to it
+                transfer.entry = Some(String::new());
+                transfer.error = None;
+                transfer.request_clipboard();
+            }
+            TransferAction::ReadImportFile => {
+                let path = profile.dir.join(IMPORT_FILE);
+                let code = storage
+                    .read(&path)
+                    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+                match code {
+                    Ok(code) => transfer.stage(&code, &path.display().to_string()),
+                    Err(e) => {
+                        transfer.error = Some(format!("Could not read {}: {}", path.display(), e));
+                    }
+                }
+            }
+            TransferAction::CancelImport => {
+                transfer.pending = None;
+                transfer.status = None;
+            }
+            TransferAction::Close => next_state.set(GameState::Playing),
+            TransferAction::ConfirmImport => {}
+        }
+    }
+}
+
+/// Keys typed into the paste field; Ctrl+V (Cmd+V on macOS) pastes the clipboard
+pub fn handle_code_entry(
+    mut keyboard: MessageReader<KeyboardInput>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut transfer: ResMut<SaveTransfer>,
+) {
+    if !transfer.is_typing() {
+        keyboard.clear();
+        return;
+    }
+    let modifier = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]);
+    for input in keyboard.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+        if modifier {
+            if input.key_code == KeyCode::KeyV {
+                transfer.request_clipboard();
+            }
+            continue;
+        }
+        transfer.type_key(&input.logical_key);
+    }
+}
+
+/// Pastes the clipboard text once its read finishes
+pub fn receive_clipboard(mut transfer: ResMut<SaveTransfer>) {
+    // Polling shouldn't mark the screen for a redraw every frame
+    let Some(task) = transfer.bypass_change_detection().clipboard.as_mut() else {
+        return;
+    };
+    let Some(text) = check_ready(task) else {
+        return;
+    };
+    transfer.clipboard = None;
+    transfer.paste(&text.unwrap_or_default());
+}
+
+/// Stage a save code from a file dropped onto the window. The file lives
+/// wherever the player keeps it, outside the save storage, so it is read directly.
+pub fn handle_dropped_code(
+    mut drops: MessageReader<FileDragAndDrop>,
+    mut transfer: ResMut<SaveTransfer>,
+) {
+    for drop in drops.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = drop else {
+            continue;
+        };
+        match fs::read_to_string(path_buf) {
+            Ok(code) => transfer.stage(&code, &path_buf.display().to_string()),
+            Err(e) => {
+                transfer.error = Some(format!("Could not read {}: {}", path_buf.display(), e));
+            }
+        }
+    }
+}
+
+/// Replace the running game and the profile's save with the staged import
+pub fn apply_import(
+    interactions: Query<(&Interaction, &TransferAction), Changed<Interaction>>,
+    mut transfer: ResMut<SaveTransfer>,
+    mut targets: SaveTargets,
+    active: Res<ActiveProfile>,
+    save_lock: Res<SaveLock>,
+    backups: Res<BackupSettings>,
+    storage: Res<SaveStore>,
+) {
+    let confirmed = interactions
+        .iter()
+        .any(|(i, a)| *i == Interaction::Pressed && *a == TransferAction::ConfirmImport);
+    if !confirmed {
+        return;
+    }
+    let Some(ref profile) = active.0 else {
+        return;
+    };
+    if save_lock.0 {
+        transfer.error =
+            Some("Saving is disabled this session, so imports are too.".to_string());
+        return;
+    }
+    let Some(mut save) = transfer.pending.take() else {
+        return;
+    };
+
+    // Keep what the player had, then write the import straight away
+    save.timestamp = now_secs();
+    let result = save_files::force_backup(&**storage, &profile.dir, &backups, save.timestamp)
+        .and_then(|_| save_files::write_save(&**storage, &profile.dir, &save, &backups));
+    if let Err(e) = result {
+        transfer.error = Some(format!("Could not write imported save: {}", e));
+        return;
+    }
+    targets.restore(&save, 0.0);
+    transfer.error = None;
+    transfer.status = Some("Save imported. The previous save was backed up.".to_string());
+}
+
+fn preview_lines(save: &SaveData, content: &GameContent) -> Vec<String> {
+    let age_hours = now_secs().saturating_sub(save.timestamp) / 3600;
+    vec![
+        format!("Insight: {}", save.insight),
+        format!("Transcendences: {}", save.total_transcendences),
+        format!(
+            "Achievements: {} / {}",
+            save.unlocked_achievements.len(),
+            AchievementId::ALL.len()
+        ),
+        format!("Lifetime truths: {}", save.lifetime_truths),
+        format!("Arcane Focus: {}", save.focus_points.format_short()),
+        format!("School: {}", content.school(save.school).name),
+        format!("Saved {} hours ago", age_hours),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::testing::TestApp;
+
+    fn typing() -> SaveTransfer {
+        SaveTransfer {
+            entry: Some(String::new()),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn pasted_code_is_staged_on_enter() {
+        let code = save_code::encode(&SaveData::new_game());
+        let (head, tail) = code.split_at(code.len() / 2);
+        let mut transfer = typing();
+        transfer.paste(&format!(" {}\n", head));
+        transfer.type_key(&Key::Character(tail.into()));
+        transfer.type_key(&Key::Enter);
+
+        assert!(!transfer.is_typing());
+        assert!(transfer.pending.is_some(), "{:?}", transfer.error);
+    }
+
+    #[test]
+    fn bad_paste_reports_an_error_and_escape_cancels() {
+        let mut transfer = typing();
+        transfer.type_key(&Key::Character("not a code".into()));
+        transfer.type_key(&Key::Backspace);
+        transfer.type_key(&Key::Enter);
+        assert!(transfer.pending.is_none());
+        assert!(transfer.error.is_some());
+
+        let mut transfer = typing();
+        transfer.type_key(&Key::Escape);
+        assert!(!transfer.is_typing() && transfer.pending.is_none());
+    }
+
+    #[test]
+    fn clipboard_text_lands_in_the_entry_field_without_blocking() {
+        let mut game = TestApp::new();
+        game.set_state(GameState::SaveTransfer);
+        {
+            let mut transfer = game.resource_mut::<SaveTransfer>();
+            transfer.entry = Some("ab".to_string());
+            transfer.clipboard = Some(IoTaskPool::get().spawn(async { Some("c d\n".into()) }));
+        }
+        while game.resource::<SaveTransfer>().clipboard.is_some() {
+            game.update();
+        }
+        assert_eq!(
+            game.resource::<SaveTransfer>().entry.as_deref(),
+            Some("abcd")
+        );
+    }
+}
@@ -1,13 +1,16 @@
 use super::achievements::AchievementTracker;
 use super::acolytes::AcolyteState;
+use super::bignum::BigNum;
+use super::content::GameContent;
 use super::generators::GeneratorState;
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
 use super::moments::MomentState;
 use super::progression::ArcaneProgress;
 use super::shadow_thoughts::ShadowState;
 use super::shop::PurchaseTracker;
 use super::state::GameState;
 use super::transcendence::TranscendenceState;
-use super::wisdom::WisdomMeter;
+use super::wisdom::{TruthGenerated, WisdomMeter};
 use crate::orb::types::{EquippedOrb, OrbType};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -23,42 +26,20 @@ pub enum SchoolOfThought {
 }
 
 impl SchoolOfThought {
-    pub const CHOOSABLE: [SchoolOfThought; 4] = [
+    pub const ALL: [SchoolOfThought; 5] = [
+        Self::None,
         Self::Stoicism,
         Self::Mysticism,
         Self::Empiricism,
         Self::Nihilism,
     ];
 
-    pub fn name(&self) -> &'static str {
-        match self {
-            Self::None => "Unaligned",
-            Self::Stoicism => "Stoicism",
-            Self::Mysticism => "Mysticism",
-            Self::Empiricism => "Empiricism",
-            Self::Nihilism => "Nihilism",
-        }
-    }
-
-    pub fn subtitle(&self) -> &'static str {
-        match self {
-            Self::None => "",
-            Self::Stoicism => "The Way of Stillness",
-            Self::Mysticism => "The Way of Visions",
-            Self::Empiricism => "The Way of Method",
-            Self::Nihilism => "The Way of the Void",
-        }
-    }
-
-    pub fn description(&self) -> &'static str {
-        match self {
-            Self::None => "No school chosen.",
-            Self::Stoicism => "Passive generation +50%. Wisdom scaling reduced to 1.07x. The patient mind sees furthest.",
-            Self::Mysticism => "Moments of Clarity appear 2x as often. Buff durations +50%. Wisdom bursts doubled. Embrace the unknowable.",
-            Self::Empiricism => "Click wisdom +75%. AFP per truth +30%. Each observation builds the next. Knowledge compounds.",
-            Self::Nihilism => "All generation starts at 0.5x. Each truth generated adds +5% generation. From nothing, everything accelerates.",
-        }
-    }
+    pub const CHOOSABLE: [SchoolOfThought; 4] = [
+        Self::Stoicism,
+        Self::Mysticism,
+        Self::Empiricism,
+        Self::Nihilism,
+    ];
 
     pub fn color(&self) -> Color {
         match self {
@@ -71,8 +52,17 @@ impl SchoolOfThought {
     }
 }
 
+/// A school's name and pitch, from the content file
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchoolDef {
+    pub id: SchoolOfThought,
+    pub name: String,
+    pub subtitle: String,
+    pub description: String,
+}
+
 /// Tracks the active school and run-specific school state
-#[derive(Resource, Debug, Default)]
+#[derive(Resource, Debug, Default, Clone)]
 pub struct SchoolState {
     pub active: SchoolOfThought,
     /// Truths generated this run (for Nihilism scaling)
@@ -142,6 +132,14 @@ impl SchoolState {
     }
 }
 
+impl ModifierSource for SchoolState {
+    fn contribute(&self, _content: &GameContent, modifiers: &mut WisdomModifiers) {
+        modifiers.mul("school", ModifierChannel::Click, self.click_multiplier() as f64);
+        modifiers.mul("school", ModifierChannel::Passive, self.passive_multiplier() as f64);
+        modifiers.add("school", ModifierChannel::Afp, self.afp_bonus_per_truth() as f64);
+    }
+}
+
 // ========== SCHOOL SELECTION UI ==========
 
 #[derive(Component)]
@@ -150,7 +148,7 @@ pub struct SchoolSelectionPanel;
 #[derive(Component)]
 pub struct SchoolChoiceButton(pub SchoolOfThought);
 
-pub fn open_school_selection(mut commands: Commands) {
+pub fn open_school_selection(mut commands: Commands, content: Res<GameContent>) {
     commands
         .spawn((
             Node {
@@ -206,6 +204,7 @@ pub fn open_school_selection(mut commands: Commands) {
                         .with_children(|list| {
                             for school in SchoolOfThought::CHOOSABLE {
                                 let school_color = school.color();
+                                let def = content.school(school);
 
                                 list.spawn((
                                     Button,
@@ -229,12 +228,12 @@ pub fn open_school_selection(mut commands: Commands) {
                                     })
                                     .with_children(|header| {
                                         header.spawn((
-                                            Text::new(school.name()),
+                                            Text::new(def.name.clone()),
                                             TextFont { font_size: 20.0, ..default() },
                                             TextColor(school_color),
                                         ));
                                         header.spawn((
-                                            Text::new(school.subtitle()),
+                                            Text::new(def.subtitle.clone()),
                                             TextFont { font_size: 14.0, ..default() },
                                             TextColor(school_color.with_alpha(0.6)),
                                         ));
@@ -242,7 +241,7 @@ pub fn open_school_selection(mut commands: Commands) {
 
                                     // Description
                                     card.spawn((
-                                        Text::new(school.description()),
+                                        Text::new(def.description.clone()),
                                         TextFont { font_size: 13.0, ..default() },
                                         TextColor(Color::srgba(0.8, 0.75, 0.85, 0.8)),
                                     ));
@@ -278,6 +277,7 @@ pub fn handle_school_choice(
     mut challenges: ResMut<super::challenges::ChallengeState>,
     mut resources: ResMut<super::resources::SecondaryResources>,
     mut equipped: ResMut<EquippedOrb>,
+    mut rng: ResMut<super::rng::GameRng>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
     for (interaction, button) in &interactions {
@@ -292,20 +292,20 @@ pub fn handle_school_choice(
         // Reset run state
         let starting_afp = transcendence.starting_afp();
         *progress = ArcaneProgress {
-            focus_points: starting_afp,
+            focus_points: BigNum::from(starting_afp),
             ..Default::default()
         };
         *wisdom = WisdomMeter::default();
         *generators = GeneratorState::default();
         *acolytes = AcolyteState::default();
         *tracker = PurchaseTracker::default();
-        *moments = MomentState::default();
-        *shadows = ShadowState::default();
+        rng.next_run();
+        *moments = MomentState::new(&mut rng);
+        *shadows = ShadowState::new(&mut rng);
         *resources = super::resources::SecondaryResources::default();
         challenges.active = None;
         achievements.reset_run_stats();
         equipped.0 = OrbType::Crystal;
-        tracker.recalculate(OrbType::Crystal);
 
         // Start the new run
         next_state.set(GameState::Playing);
@@ -315,12 +315,7 @@ pub fn handle_school_choice(
 /// Tracks truths generated this run (for Nihilism scaling)
 pub fn track_run_truths(
     mut school: ResMut<SchoolState>,
-    wisdom: Res<WisdomMeter>,
-    mut last_truths: Local<u32>,
+    mut truth_messages: MessageReader<TruthGenerated>,
 ) {
-    if wisdom.truths_generated > *last_truths {
-        let new_truths = wisdom.truths_generated - *last_truths;
-        school.run_truths += new_truths;
-    }
-    *last_truths = wisdom.truths_generated;
+    school.run_truths += truth_messages.read().filter(|t| t.is_meter_truth()).count() as u32;
 }
@@ -1,3 +1,8 @@
+use super::bignum::BigNum;
+use super::input::PlayerInput;
+use super::ledger::{WisdomLedger, WisdomSource};
+use super::rng::{GameRng, RngStream};
+use super::shop::PurchaseTracker;
 use super::wisdom::WisdomMeter;
 use bevy::prelude::*;
 use rand::Rng;
@@ -13,31 +18,37 @@ pub struct ShadowState {
     /// Timer until next shadow spawns
     pub spawn_timer: Timer,
     /// Wisdom secretly stored by shadows (before multiplier)
-    pub stored_wisdom: f64,
+    pub stored_wisdom: BigNum,
     /// Drain rate per shadow (0.1 = 10% of generation)
     pub drain_per_shadow: f32,
     /// Multiplier per shadow when dispelled (1.1 = +10% per shadow)
     pub dispel_multiplier: f64,
 }
 
-impl Default for ShadowState {
-    fn default() -> Self {
-        let delay = rand::thread_rng().gen_range(120.0..300.0);
+impl FromWorld for ShadowState {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(&mut world.get_resource_or_init::<GameRng>())
+    }
+}
+
+impl ShadowState {
+    /// No shadows yet, with the first one a few minutes away
+    pub fn new(rng: &mut GameRng) -> Self {
+        let delay = rng.stream(RngStream::Shadows).gen_range(120.0..300.0);
         Self {
             count: 0,
             max_shadows: 5,
             spawn_timer: Timer::from_seconds(delay, TimerMode::Once),
-            stored_wisdom: 0.0,
+            stored_wisdom: BigNum::ZERO,
             drain_per_shadow: 0.10,
             dispel_multiplier: 1.1,
         }
     }
-}
 
-impl ShadowState {
-    /// Fraction of wisdom being drained (0.0 to max_shadows * drain_per_shadow)
-    pub fn drain_fraction(&self) -> f32 {
-        (self.count as f32 * self.drain_per_shadow).min(0.95)
+    /// Fraction of wisdom being drained (0.0 to max_shadows * drain_per_shadow),
+    /// less the share `ward` blocks
+    pub fn drain_fraction(&self, ward: f32) -> f32 {
+        (self.count as f32 * self.drain_per_shadow).min(0.95) * (1.0 - ward)
     }
 
     /// The multiplier applied to stored wisdom when all shadows are dispelled
@@ -45,16 +56,28 @@ impl ShadowState {
         self.dispel_multiplier.powi(self.count as i32)
     }
 
-    fn reset_spawn_timer(&mut self) {
-        let delay = rand::thread_rng().gen_range(90.0..240.0);
+    fn reset_spawn_timer(&mut self, rng: &mut GameRng) {
+        let delay = rng.stream(RngStream::Shadows).gen_range(90.0..240.0);
         self.spawn_timer = Timer::from_seconds(delay, TimerMode::Once);
     }
 }
 
+/// The player dispelled the gathered shadows
+#[derive(Message)]
+pub struct ShadowsDispelled {
+    pub count: u32,
+    /// Stored wisdom returned to the meter, including the dispel bonus
+    pub reclaimed: BigNum,
+}
+
 // ========== SYSTEMS ==========
 
 /// Spawns new shadow thoughts over time
-pub fn update_shadows(mut shadows: ResMut<ShadowState>, time: Res<Time>) {
+pub fn update_shadows(
+    mut shadows: ResMut<ShadowState>,
+    mut rng: ResMut<GameRng>,
+    time: Res<Time>,
+) {
     if shadows.count >= shadows.max_shadows {
         return;
     }
@@ -62,42 +85,38 @@ pub fn update_shadows(mut shadows: ResMut<ShadowState>, time: Res<Time>) {
     shadows.spawn_timer.tick(time.delta());
     if shadows.spawn_timer.just_finished() {
         shadows.count += 1;
-        shadows.reset_spawn_timer();
+        shadows.reset_spawn_timer(&mut rng);
     }
 }
 
 /// Siphons a portion of wisdom generation into shadow storage.
-/// Runs every frame: calculates what was added this frame and redirects a fraction.
+/// Runs every tick after production and redirects a fraction of what was produced.
 pub fn siphon_wisdom(
     mut shadows: ResMut<ShadowState>,
     mut wisdom: ResMut<WisdomMeter>,
-    mut last_wisdom: Local<f32>,
+    mut ledger: ResMut<WisdomLedger>,
+    tracker: Res<PurchaseTracker>,
 ) {
-    if shadows.count == 0 {
-        *last_wisdom = wisdom.current;
-        return;
-    }
-
-    // How much wisdom was added since last frame
-    let gained = wisdom.current - *last_wisdom;
-    if gained <= 0.0 {
-        *last_wisdom = wisdom.current;
+    let gained = ledger.take_tick();
+    if shadows.count == 0 || !gained.is_positive() {
         return;
     }
 
-    let drain = gained * shadows.drain_fraction();
+    let drain = gained * shadows.drain_fraction(tracker.shadow_ward) as f64;
     wisdom.current -= drain;
-    shadows.stored_wisdom += drain as f64;
-    *last_wisdom = wisdom.current;
+    shadows.stored_wisdom += drain;
 }
 
 /// Player presses [D] to dispel all shadows and reclaim stored wisdom with multiplier
 pub fn handle_dispel(
-    keys: Res<ButtonInput<KeyCode>>,
+    input: Res<PlayerInput>,
     mut shadows: ResMut<ShadowState>,
     mut wisdom: ResMut<WisdomMeter>,
+    mut ledger: ResMut<WisdomLedger>,
+    mut rng: ResMut<GameRng>,
+    mut dispelled: MessageWriter<ShadowsDispelled>,
 ) {
-    if !keys.just_pressed(KeyCode::KeyD) {
+    if !input.dispel {
         return;
     }
 
@@ -105,12 +124,18 @@ pub fn handle_dispel(
         return;
     }
 
+    // Stored wisdom was recorded when produced; only the bonus is new
     let multiplied = shadows.stored_wisdom * shadows.total_dispel_multiplier();
-    wisdom.current += multiplied as f32;
+    wisdom.current += shadows.stored_wisdom;
+    ledger.gain(&mut wisdom, WisdomSource::Dispel, multiplied - shadows.stored_wisdom);
+    dispelled.write(ShadowsDispelled {
+        count: shadows.count,
+        reclaimed: multiplied,
+    });
 
     shadows.count = 0;
-    shadows.stored_wisdom = 0.0;
-    shadows.reset_spawn_timer();
+    shadows.stored_wisdom = BigNum::ZERO;
+    shadows.reset_spawn_timer(&mut rng);
 }
 
 // ========== UI ==========
@@ -122,9 +147,10 @@ pub struct ShadowIndicator;
 pub fn render_shadow_ui(
     mut commands: Commands,
     shadows: Res<ShadowState>,
+    tracker: Res<PurchaseTracker>,
     existing: Query<Entity, With<ShadowIndicator>>,
 ) {
-    if !shadows.is_changed() {
+    if !shadows.is_changed() && !tracker.is_changed() {
         return;
     }
 
@@ -136,7 +162,7 @@ pub fn render_shadow_ui(
         return;
     }
 
-    let drain_pct = shadows.drain_fraction() * 100.0;
+    let drain_pct = shadows.drain_fraction(tracker.shadow_ward) * 100.0;
     let stored = shadows.stored_wisdom;
     let multiplier = shadows.total_dispel_multiplier();
     let payout = stored * multiplier;
@@ -173,7 +199,7 @@ pub fn render_shadow_ui(
             ));
 
             // Stored wisdom
-            if stored > 0.1 {
+            if stored > BigNum::from(0.1) {
                 panel.spawn((
                     Text::new(format!(
                         "Stored: {:.1} (x{:.2} = {:.1})",
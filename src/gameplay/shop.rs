@@ -1,4 +1,7 @@
-use super::generators::{GeneratorState, GeneratorType};
+use super::bignum::BigNum;
+use super::content::GameContent;
+use super::generators::{GeneratorPurchased, GeneratorState, GeneratorType};
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
 use super::progression::ArcaneProgress;
 use super::resources::SecondaryResources;
 use super::state::GameState;
@@ -11,23 +14,24 @@ use std::collections::HashSet;
 
 // ========== DATA TYPES ==========
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum ShopItemId {
-    ArcaneBiscuit,
-    VoidTea,
-    CosmicPretzel,
-    GlowingBerries,
-    FocusedMind,
-    DeepContemplation,
-    ArcaneAmplifier,
-    CrystalResonance,
-    GentleScaling,
-    ObsidianOrb,
-    MercuryOrb,
-    GalaxyOrb,
+/// Names a shop item in the content file and in saves
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ShopItemId(pub String);
+
+impl From<&str> for ShopItemId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl std::fmt::Display for ShopItemId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
 pub enum ShopCategory {
     Snacks,
     Upgrades,
@@ -53,123 +57,73 @@ impl ShopCategory {
     ];
 }
 
-#[derive(Debug, Clone)]
+/// What owning a shop item does
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum ShopEffect {
+    /// Added to the efficiency multiplier
+    Efficiency(f32),
+    /// Added to the wisdom speed multiplier
+    WisdomSpeed(f32),
+    AfpPerTruth(u32),
+    /// Replaces the truth scaling factor; the lowest owned wins
+    Scaling(f32),
+    /// Makes an orb equippable; its bonuses apply while equipped
+    UnlockOrb(OrbType),
+    /// Multiplies one generator tier's production
+    GeneratorMultiplier {
+        generator: GeneratorType,
+        factor: f64,
+    },
+    /// Adds click wisdom for every owned generator of a tier
+    ClickPerGenerator {
+        generator: GeneratorType,
+        amount: f64,
+    },
+    /// Shadows drain this fraction less; owned wards add up
+    ShadowWard(f32),
+}
+
+/// Owned-count threshold a generator upgrade unlocks at
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct GeneratorMilestone {
+    pub generator: GeneratorType,
+    pub owned: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct ShopItem {
     pub id: ShopItemId,
     pub category: ShopCategory,
-    pub name: &'static str,
-    pub description: &'static str,
+    pub name: String,
+    pub description: String,
     pub cost: u64,
+    pub effect: ShopEffect,
+    /// Generator upgrades list under this generator and unlock at its threshold
+    #[serde(default)]
+    pub requires: Option<GeneratorMilestone>,
 }
 
-#[derive(Resource)]
-pub struct ShopCatalog {
-    pub items: Vec<ShopItem>,
-}
-
-impl Default for ShopCatalog {
-    fn default() -> Self {
-        Self {
-            items: vec![
-                // Snacks
-                ShopItem {
-                    id: ShopItemId::ArcaneBiscuit,
-                    category: ShopCategory::Snacks,
-                    name: "Arcane Biscuit",
-                    description: "Tastes like contemplation and oats. (+0.1 efficiency)",
-                    cost: 20,
-                },
-                ShopItem {
-                    id: ShopItemId::VoidTea,
-                    category: ShopCategory::Snacks,
-                    name: "Void Tea",
-                    description: "Brewed from the absence of tea leaves. (+0.25 efficiency)",
-                    cost: 50,
-                },
-                ShopItem {
-                    id: ShopItemId::CosmicPretzel,
-                    category: ShopCategory::Snacks,
-                    name: "Cosmic Pretzel",
-                    description: "Twisted by gravitational forces of pure thought. (+0.5 efficiency)",
-                    cost: 100,
-                },
-                ShopItem {
-                    id: ShopItemId::GlowingBerries,
-                    category: ShopCategory::Snacks,
-                    name: "Glowing Berries",
-                    description: "Harvested from bushes that dream of being stars. (+1.0 efficiency)",
-                    cost: 200,
-                },
-                // Upgrades
-                ShopItem {
-                    id: ShopItemId::FocusedMind,
-                    category: ShopCategory::Upgrades,
-                    name: "Focused Mind",
-                    description: "Sharpen your mental lens. (+20% wisdom speed)",
-                    cost: 30,
-                },
-                ShopItem {
-                    id: ShopItemId::DeepContemplation,
-                    category: ShopCategory::Upgrades,
-                    name: "Deep Contemplation",
-                    description: "Think thoughts about thoughts. (+50% wisdom speed)",
-                    cost: 75,
-                },
-                ShopItem {
-                    id: ShopItemId::ArcaneAmplifier,
-                    category: ShopCategory::Upgrades,
-                    name: "Arcane Amplifier",
-                    description: "Focuses the arcane flow. (+5 AFP per truth)",
-                    cost: 40,
-                },
-                ShopItem {
-                    id: ShopItemId::CrystalResonance,
-                    category: ShopCategory::Upgrades,
-                    name: "Crystal Resonance",
-                    description: "The orb hums in harmony. (+10 AFP per truth)",
-                    cost: 80,
-                },
-                ShopItem {
-                    id: ShopItemId::GentleScaling,
-                    category: ShopCategory::Upgrades,
-                    name: "Gentle Scaling",
-                    description: "Softens the rising tide of wisdom. (Scaling 1.1x \u{2192} 1.07x)",
-                    cost: 60,
-                },
-                // Orb Collection
-                ShopItem {
-                    id: ShopItemId::ObsidianOrb,
-                    category: ShopCategory::OrbCollection,
-                    name: "Obsidian Orb",
-                    description: "Forged in forgotten volcanoes. (+0.3 efficiency, +5 AFP/truth)",
-                    cost: 150,
-                },
-                ShopItem {
-                    id: ShopItemId::MercuryOrb,
-                    category: ShopCategory::OrbCollection,
-                    name: "Mercury Orb",
-                    description: "Liquid metal in a sphere of pure intent. (+40% wisdom speed)",
-                    cost: 300,
-                },
-                ShopItem {
-                    id: ShopItemId::GalaxyOrb,
-                    category: ShopCategory::OrbCollection,
-                    name: "Galaxy Orb",
-                    description: "Contains an entire galaxy. (Scaling -0.03)",
-                    cost: 500,
-                },
-            ],
-        }
+impl ShopItem {
+    /// Whether the generator threshold, if any, is met
+    pub fn is_unlocked(&self, generators: &GeneratorState) -> bool {
+        self.requires
+            .is_none_or(|req| generators.count(req.generator) >= req.owned)
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Debug, Clone)]
 pub struct PurchaseTracker {
     pub purchased: HashSet<ShopItemId>,
     pub efficiency_bonus: f32,
     pub wisdom_speed_bonus: f32,
     pub afp_bonus: u32,
     pub scaling_factor: f32,
+    /// Production multiplier from upgrades for each generator tier
+    pub generator_mult: [f64; 8],
+    /// Click wisdom added per owned generator of each tier
+    pub click_per_generator: [f64; 8],
+    /// Fraction of shadow drain blocked (0.0 to 0.9)
+    pub shadow_ward: f32,
 }
 
 impl Default for PurchaseTracker {
@@ -180,29 +134,38 @@ impl Default for PurchaseTracker {
             wisdom_speed_bonus: 1.0,
             afp_bonus: 0,
             scaling_factor: 1.1,
+            generator_mult: [1.0; 8],
+            click_per_generator: [0.0; 8],
+            shadow_ward: 0.0,
         }
     }
 }
 
 impl PurchaseTracker {
-    pub fn recalculate(&mut self, equipped: OrbType) {
+    /// Items no longer in the content file are kept but give nothing
+    pub fn recalculate(&mut self, equipped: OrbType, content: &GameContent) {
         self.efficiency_bonus = 0.0;
         self.wisdom_speed_bonus = 1.0;
         self.afp_bonus = 0;
         self.scaling_factor = 1.1;
-
-        for item in &self.purchased {
-            match item {
-                ShopItemId::ArcaneBiscuit => self.efficiency_bonus += 0.1,
-                ShopItemId::VoidTea => self.efficiency_bonus += 0.25,
-                ShopItemId::CosmicPretzel => self.efficiency_bonus += 0.5,
-                ShopItemId::GlowingBerries => self.efficiency_bonus += 1.0,
-                ShopItemId::FocusedMind => self.wisdom_speed_bonus += 0.2,
-                ShopItemId::DeepContemplation => self.wisdom_speed_bonus += 0.5,
-                ShopItemId::ArcaneAmplifier => self.afp_bonus += 5,
-                ShopItemId::CrystalResonance => self.afp_bonus += 10,
-                ShopItemId::GentleScaling => self.scaling_factor = 1.07,
-                _ => {}
+        self.generator_mult = [1.0; 8];
+        self.click_per_generator = [0.0; 8];
+        self.shadow_ward = 0.0;
+
+        for item in self.purchased.iter().filter_map(|id| content.shop_item(id)) {
+            match item.effect {
+                ShopEffect::Efficiency(v) => self.efficiency_bonus += v,
+                ShopEffect::WisdomSpeed(v) => self.wisdom_speed_bonus += v,
+                ShopEffect::AfpPerTruth(v) => self.afp_bonus += v,
+                ShopEffect::Scaling(v) => self.scaling_factor = self.scaling_factor.min(v),
+                ShopEffect::UnlockOrb(_) => {}
+                ShopEffect::GeneratorMultiplier { generator, factor } => {
+                    self.generator_mult[generator as usize] *= factor;
+                }
+                ShopEffect::ClickPerGenerator { generator, amount } => {
+                    self.click_per_generator[generator as usize] += amount;
+                }
+                ShopEffect::ShadowWard(v) => self.shadow_ward = (self.shadow_ward + v).min(0.9),
             }
         }
 
@@ -223,6 +186,18 @@ impl PurchaseTracker {
     }
 }
 
+impl ModifierSource for PurchaseTracker {
+    fn contribute(&self, _content: &GameContent, modifiers: &mut WisdomModifiers) {
+        let efficiency = 1.0 + self.efficiency_bonus as f64;
+        let speed = self.wisdom_speed_bonus as f64;
+        for channel in [ModifierChannel::Click, ModifierChannel::Passive] {
+            modifiers.mul("shop_efficiency", channel, efficiency);
+            modifiers.mul("shop_speed", channel, speed);
+        }
+        modifiers.add("shop", ModifierChannel::Afp, self.afp_bonus as f64);
+    }
+}
+
 // ========== UI COMPONENTS ==========
 
 #[derive(Component)]
@@ -243,21 +218,102 @@ pub struct EquipButton(pub OrbType);
 #[derive(Component)]
 pub struct BuyGeneratorButton(pub GeneratorType);
 
+#[derive(Component)]
+pub struct BuyQuantityButton(pub BuyQuantity);
+
 #[derive(Component)]
 pub struct ShopAfpText;
 
 #[derive(Resource)]
 pub struct SelectedCategory(pub ShopCategory);
 
-fn shop_item_to_orb_type(id: ShopItemId) -> Option<OrbType> {
-    match id {
-        ShopItemId::ObsidianOrb => Some(OrbType::Obsidian),
-        ShopItemId::MercuryOrb => Some(OrbType::Mercury),
-        ShopItemId::GalaxyOrb => Some(OrbType::Galaxy),
-        _ => None,
+/// How many generators one click on a buy button purchases
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BuyQuantity {
+    #[default]
+    One,
+    Ten,
+    Hundred,
+    /// As many as AFP and serenity allow
+    Max,
+}
+
+impl BuyQuantity {
+    pub const ALL: [BuyQuantity; 4] = [
+        BuyQuantity::One,
+        BuyQuantity::Ten,
+        BuyQuantity::Hundred,
+        BuyQuantity::Max,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            BuyQuantity::One => "x1",
+            BuyQuantity::Ten => "x10",
+            BuyQuantity::Hundred => "x100",
+            BuyQuantity::Max => "Max",
+        }
+    }
+}
+
+/// What a generator buy button offers right now at the selected quantity
+pub struct GeneratorOffer {
+    pub count: u32,
+    pub cost: BigNum,
+    pub serenity: Option<f64>,
+    pub affordable: bool,
+}
+
+impl GeneratorOffer {
+    /// "Max" with nothing affordable still prices a single generator
+    pub fn new(
+        content: &GameContent,
+        gt: GeneratorType,
+        owned: u32,
+        discount: f64,
+        quantity: BuyQuantity,
+        afp: BigNum,
+        serenity: f64,
+    ) -> Self {
+        let count = match quantity {
+            BuyQuantity::One => 1,
+            BuyQuantity::Ten => 10,
+            BuyQuantity::Hundred => 100,
+            BuyQuantity::Max => content
+                .max_affordable_generators(gt, owned, discount, afp, serenity)
+                .max(1),
+        };
+        let cost = content.generator_bulk_cost(gt, owned, count, discount);
+        // Serenity is charged per generator, not discounted
+        let serenity_cost = content
+            .generator(gt)
+            .serenity_cost
+            .map(|per_unit| per_unit * count as f64);
+        let affordable = afp >= cost && serenity_cost.map_or(true, |s| serenity >= s);
+        Self {
+            count,
+            cost,
+            serenity: serenity_cost,
+            affordable,
+        }
+    }
+
+    /// Button text, with the batch size when buying more than one
+    pub fn label(&self) -> String {
+        if self.count == 1 {
+            format_afp(self.cost)
+        } else {
+            format!("x{} {}", self.count, format_afp(self.cost))
+        }
     }
 }
 
+/// A shop upgrade or orb was bought
+#[derive(Message)]
+pub struct ShopItemBought {
+    pub item: ShopItemId,
+}
+
 // ========== SYSTEMS ==========
 
 pub fn toggle_shop(
@@ -276,7 +332,7 @@ pub fn toggle_shop(
 
 pub fn open_shop(
     mut commands: Commands,
-    catalog: Res<ShopCatalog>,
+    content: Res<GameContent>,
     tracker: Res<PurchaseTracker>,
     progress: Res<ArcaneProgress>,
     equipped: Res<EquippedOrb>,
@@ -284,6 +340,7 @@ pub fn open_shop(
     synergies: Res<SynergyState>,
     transcendence: Res<TranscendenceState>,
     resources: Res<SecondaryResources>,
+    quantity: Res<BuyQuantity>,
 ) {
     commands.insert_resource(SelectedCategory(ShopCategory::Snacks));
 
@@ -334,7 +391,7 @@ pub fn open_shop(
                                 TextColor(Color::srgb(1.0, 0.85, 0.4)),
                             ));
                             header.spawn((
-                                Text::new(format!("AFP: {}", progress.focus_points)),
+                                Text::new(format!("AFP: {}", format_afp(progress.focus_points))),
                                 TextFont {
                                     font_size: 20.0,
                                     ..default()
@@ -422,7 +479,7 @@ pub fn open_shop(
                         .with_children(|list| {
                             spawn_items(
                                 list,
-                                &catalog,
+                                &content,
                                 &tracker,
                                 &progress,
                                 ShopCategory::Snacks,
@@ -431,6 +488,7 @@ pub fn open_shop(
                                 &synergies,
                                 &transcendence,
                                 &resources,
+                                *quantity,
                             );
                         });
 
@@ -477,7 +535,7 @@ pub fn handle_category_click(
 pub fn rebuild_item_list(
     mut commands: Commands,
     selected: Res<SelectedCategory>,
-    catalog: Res<ShopCatalog>,
+    content: Res<GameContent>,
     tracker: Res<PurchaseTracker>,
     progress: Res<ArcaneProgress>,
     equipped: Res<EquippedOrb>,
@@ -485,6 +543,7 @@ pub fn rebuild_item_list(
     synergies: Res<SynergyState>,
     transcendence: Res<TranscendenceState>,
     resources: Res<SecondaryResources>,
+    quantity: Res<BuyQuantity>,
     list_query: Query<Entity, With<ShopItemList>>,
     tab_query: Query<(&CategoryTab, &Children)>,
     mut text_query: Query<&mut TextColor>,
@@ -493,6 +552,7 @@ pub fn rebuild_item_list(
         && !equipped.is_changed()
         && !tracker.is_changed()
         && !generators.is_changed()
+        && !quantity.is_changed()
     {
         return;
     }
@@ -521,7 +581,7 @@ pub fn rebuild_item_list(
             .with_children(|list: &mut ChildSpawnerCommands| {
                 spawn_items(
                     list,
-                    &catalog,
+                    &content,
                     &tracker,
                     &progress,
                     selected.0,
@@ -530,6 +590,7 @@ pub fn rebuild_item_list(
                     &synergies,
                     &transcendence,
                     &resources,
+                    *quantity,
                 );
             });
     }
@@ -553,10 +614,12 @@ pub fn update_tab_backgrounds(
 
 pub fn handle_buy_click(
     interactions: Query<(&Interaction, &BuyButton), Changed<Interaction>>,
-    catalog: Res<ShopCatalog>,
+    content: Res<GameContent>,
     mut tracker: ResMut<PurchaseTracker>,
     mut progress: ResMut<ArcaneProgress>,
     equipped: Res<EquippedOrb>,
+    generators: Res<GeneratorState>,
+    mut bought: MessageWriter<ShopItemBought>,
 ) {
     for (interaction, button) in &interactions {
         if *interaction != Interaction::Pressed {
@@ -567,56 +630,43 @@ pub fn handle_buy_click(
             continue;
         }
 
-        let Some(item) = catalog.items.iter().find(|i| i.id == button.0) else {
+        let Some(item) = content.shop_item(&button.0) else {
             continue;
         };
 
-        if progress.focus_points < item.cost {
+        if !item.is_unlocked(&generators) || progress.focus_points < BigNum::from(item.cost) {
             continue;
         }
 
         // Purchase!
-        progress.focus_points -= item.cost;
-        tracker.purchased.insert(button.0);
+        progress.focus_points -= BigNum::from(item.cost);
+        tracker.purchased.insert(button.0.clone());
+        bought.write(ShopItemBought { item: button.0.clone() });
 
         // Apply orb unlocks directly
-        match button.0 {
-            ShopItemId::ObsidianOrb => {
-                if !progress.unlocked_orbs.contains(&OrbType::Obsidian) {
-                    progress.unlocked_orbs.push(OrbType::Obsidian);
-                }
+        if let ShopEffect::UnlockOrb(orb) = item.effect {
+            if !progress.unlocked_orbs.contains(&orb) {
+                progress.unlocked_orbs.push(orb);
             }
-            ShopItemId::MercuryOrb => {
-                if !progress.unlocked_orbs.contains(&OrbType::Mercury) {
-                    progress.unlocked_orbs.push(OrbType::Mercury);
-                }
-            }
-            ShopItemId::GalaxyOrb => {
-                if !progress.unlocked_orbs.contains(&OrbType::Galaxy) {
-                    progress.unlocked_orbs.push(OrbType::Galaxy);
-                }
-            }
-            _ => {}
         }
 
-        tracker.recalculate(equipped.0);
+        tracker.recalculate(equipped.0, &content);
     }
 }
 
 pub fn update_shop_buttons(
     tracker: Res<PurchaseTracker>,
     progress: Res<ArcaneProgress>,
-    catalog: Res<ShopCatalog>,
+    content: Res<GameContent>,
+    generators: Res<GeneratorState>,
     mut buttons: Query<(&BuyButton, &mut BackgroundColor, &Children)>,
     mut texts: Query<&mut Text>,
 ) {
     for (button, mut bg, children) in &mut buttons {
         let owned = tracker.purchased.contains(&button.0);
-        let affordable = catalog
-            .items
-            .iter()
-            .find(|i| i.id == button.0)
-            .map(|i| progress.focus_points >= i.cost)
+        let affordable = content
+            .shop_item(&button.0)
+            .map(|i| i.is_unlocked(&generators) && progress.focus_points >= BigNum::from(i.cost))
             .unwrap_or(false);
 
         let color = if owned {
@@ -633,8 +683,8 @@ pub fn update_shop_buttons(
             if let Ok(mut text) = texts.get_mut(child) {
                 if owned {
                     **text = "Owned".to_string();
-                } else if let Some(item) = catalog.items.iter().find(|i| i.id == button.0) {
-                    **text = format!("{} AFP", item.cost);
+                } else if let Some(item) = content.shop_item(&button.0) {
+                    **text = item_price_label(item, &generators, &content);
                 }
             }
         }
@@ -654,6 +704,7 @@ pub fn handle_equip_click(
     interactions: Query<(&Interaction, &EquipButton), Changed<Interaction>>,
     mut equipped: ResMut<EquippedOrb>,
     mut tracker: ResMut<PurchaseTracker>,
+    content: Res<GameContent>,
     mut orb_query: Query<&mut Orb>,
 ) {
     for (interaction, button) in &interactions {
@@ -670,7 +721,7 @@ pub fn handle_equip_click(
             orb.orb_type = button.0;
         }
 
-        tracker.recalculate(button.0);
+        tracker.recalculate(button.0, &content);
     }
 }
 
@@ -678,7 +729,7 @@ pub fn handle_equip_click(
 
 fn spawn_items(
     parent: &mut ChildSpawnerCommands,
-    catalog: &ShopCatalog,
+    content: &GameContent,
     tracker: &PurchaseTracker,
     progress: &ArcaneProgress,
     category: ShopCategory,
@@ -687,10 +738,21 @@ fn spawn_items(
     synergies: &SynergyState,
     transcendence: &TranscendenceState,
     resources: &SecondaryResources,
+    quantity: BuyQuantity,
 ) {
     // Generator tab has its own rendering
     if category == ShopCategory::Generators {
-        spawn_generator_items(parent, generators, synergies, progress, transcendence, resources);
+        spawn_generator_items(
+            parent,
+            content,
+            tracker,
+            generators,
+            synergies,
+            progress,
+            transcendence,
+            resources,
+            quantity,
+        );
         return;
     }
 
@@ -706,8 +768,8 @@ fn spawn_items(
         );
     }
 
-    let items: Vec<&ShopItem> = catalog
-        .items
+    let items: Vec<&ShopItem> = content
+        .shop_items
         .iter()
         .filter(|i| i.category == category)
         .collect();
@@ -726,8 +788,7 @@ fn spawn_items(
 
     for item in items {
         let owned = tracker.purchased.contains(&item.id);
-        let affordable = progress.focus_points >= item.cost;
-        let is_orb = item.category == ShopCategory::OrbCollection;
+        let affordable = progress.focus_points >= BigNum::from(item.cost);
 
         // Item row
         parent
@@ -755,7 +816,7 @@ fn spawn_items(
                         Color::srgb(0.9, 0.88, 0.8)
                     };
                     info.spawn((
-                        Text::new(item.name),
+                        Text::new(item.name.clone()),
                         TextFont {
                             font_size: 18.0,
                             ..default()
@@ -763,7 +824,7 @@ fn spawn_items(
                         TextColor(name_color),
                     ));
                     info.spawn((
-                        Text::new(item.description),
+                        Text::new(item.description.clone()),
                         TextFont {
                             font_size: 13.0,
                             ..default()
@@ -773,8 +834,7 @@ fn spawn_items(
                 });
 
                 // For owned orb items: show Equip/Equipped button
-                if is_orb && owned {
-                    let orb_type = shop_item_to_orb_type(item.id).unwrap();
+                if let (true, ShopEffect::UnlockOrb(orb_type)) = (owned, item.effect) {
                     let is_equipped = equipped.0 == orb_type;
                     let (btn_bg, btn_text_color, btn_label) = if is_equipped {
                         (
@@ -844,7 +904,7 @@ fn spawn_items(
                             ..default()
                         },
                         BackgroundColor(btn_bg),
-                        BuyButton(item.id),
+                        BuyButton(item.id.clone()),
                     ))
                     .with_children(|btn| {
                         btn.spawn((
@@ -861,6 +921,103 @@ fn spawn_items(
     }
 }
 
+/// An upgrade listed under its generator, indented to show which one it belongs to
+fn spawn_generator_upgrade_row(
+    parent: &mut ChildSpawnerCommands,
+    item: &ShopItem,
+    content: &GameContent,
+    tracker: &PurchaseTracker,
+    generators: &GeneratorState,
+    progress: &ArcaneProgress,
+) {
+    let owned = tracker.purchased.contains(&item.id);
+    let unlocked = item.is_unlocked(generators);
+    let affordable = unlocked && progress.focus_points >= BigNum::from(item.cost);
+
+    parent
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            padding: UiRect::new(Val::Px(32.0), Val::Px(8.0), Val::Px(2.0), Val::Px(2.0)),
+            column_gap: Val::Px(12.0),
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn(Node {
+                flex_direction: FlexDirection::Column,
+                flex_grow: 1.0,
+                ..default()
+            })
+            .with_children(|info| {
+                let name_color = if owned || !unlocked {
+                    Color::srgba(0.6, 0.55, 0.7, 0.5)
+                } else {
+                    Color::srgb(0.9, 0.88, 0.8)
+                };
+                info.spawn((
+                    Text::new(item.name.clone()),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(name_color),
+                ));
+                info.spawn((
+                    Text::new(item.description.clone()),
+                    TextFont {
+                        font_size: 11.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgba(0.6, 0.55, 0.7, 0.7)),
+                ));
+            });
+
+            let (btn_bg, btn_text_color, btn_label) = if owned {
+                (
+                    Color::srgba(0.2, 0.5, 0.25, 0.6),
+                    Color::srgb(0.4, 0.9, 0.5),
+                    "Owned".to_string(),
+                )
+            } else if affordable {
+                (
+                    Color::srgba(1.0, 0.85, 0.4, 0.9),
+                    Color::srgb(0.08, 0.06, 0.14),
+                    item_price_label(item, generators, content),
+                )
+            } else {
+                (
+                    Color::srgba(0.3, 0.25, 0.4, 0.5),
+                    Color::srgba(0.5, 0.45, 0.6, 0.5),
+                    item_price_label(item, generators, content),
+                )
+            };
+
+            row.spawn((
+                Button,
+                Node {
+                    padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                    border_radius: BorderRadius::all(Val::Px(4.0)),
+                    justify_content: JustifyContent::Center,
+                    min_width: Val::Px(80.0),
+                    ..default()
+                },
+                BackgroundColor(btn_bg),
+                BuyButton(item.id.clone()),
+            ))
+            .with_children(|btn| {
+                btn.spawn((
+                    Text::new(btn_label),
+                    TextFont {
+                        font_size: 12.0,
+                        ..default()
+                    },
+                    TextColor(btn_text_color),
+                ));
+            });
+        });
+}
+
 fn spawn_orb_row(
     parent: &mut ChildSpawnerCommands,
     name: &str,
@@ -943,29 +1100,89 @@ fn spawn_orb_row(
         });
 }
 
+/// Row of x1 / x10 / x100 / Max toggles above the generator list
+fn spawn_quantity_selector(parent: &mut ChildSpawnerCommands, selected: BuyQuantity) {
+    parent
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::FlexEnd,
+            column_gap: Val::Px(6.0),
+            ..default()
+        })
+        .with_children(|row| {
+            for quantity in BuyQuantity::ALL {
+                let is_active = quantity == selected;
+                let (bg, text_color) = if is_active {
+                    (
+                        Color::srgba(1.0, 0.85, 0.4, 0.15),
+                        Color::srgb(1.0, 0.85, 0.4),
+                    )
+                } else {
+                    (
+                        Color::srgba(0.3, 0.25, 0.4, 0.3),
+                        Color::srgba(0.6, 0.55, 0.7, 0.6),
+                    )
+                };
+
+                row.spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                        border_radius: BorderRadius::all(Val::Px(4.0)),
+                        ..default()
+                    },
+                    BackgroundColor(bg),
+                    BuyQuantityButton(quantity),
+                ))
+                .with_children(|btn| {
+                    btn.spawn((
+                        Text::new(quantity.label()),
+                        TextFont {
+                            font_size: 13.0,
+                            ..default()
+                        },
+                        TextColor(text_color),
+                    ));
+                });
+            }
+        });
+}
+
 fn spawn_generator_items(
     parent: &mut ChildSpawnerCommands,
+    content: &GameContent,
+    tracker: &PurchaseTracker,
     generators: &GeneratorState,
     synergies: &SynergyState,
     progress: &ArcaneProgress,
     transcendence: &TranscendenceState,
     resources: &SecondaryResources,
+    quantity: BuyQuantity,
 ) {
     let mut any_visible = false;
     let discount = transcendence.generator_cost_discount();
 
+    spawn_quantity_selector(parent, quantity);
+
     for gt in GeneratorType::ALL {
-        if progress.total_truths < gt.unlock_threshold() {
+        let def = content.generator(gt);
+        if progress.total_truths < def.unlock_truths {
             continue;
         }
         any_visible = true;
 
         let owned = generators.count(gt);
-        let cost = gt.next_cost_discounted(owned, discount);
-        let serenity_cost = gt.serenity_cost();
-        let has_serenity = serenity_cost.map_or(true, |s| resources.serenity >= s);
-        let affordable = progress.focus_points >= cost && has_serenity;
-        let production = gt.base_production();
+        let offer = GeneratorOffer::new(
+            content,
+            gt,
+            owned,
+            discount,
+            quantity,
+            progress.focus_points,
+            resources.serenity,
+        );
+        let has_serenity = offer.serenity.map_or(true, |s| resources.serenity >= s);
+        let production = def.base_production;
         let syn_mult = synergies.total_mult(gt);
 
         parent
@@ -989,9 +1206,9 @@ fn spawn_generator_items(
                 .with_children(|info| {
                     // Name + owned count
                     let name_label = if owned > 0 {
-                        format!("{} ({})", gt.name(), owned)
+                        format!("{} ({})", def.name, owned)
                     } else {
-                        gt.name().to_string()
+                        def.name.clone()
                     };
                     info.spawn((
                         Text::new(name_label),
@@ -1008,7 +1225,7 @@ fn spawn_generator_items(
                         if syn_mult > 1.001 {
                             format!(
                                 "{} (+{:.1}/s each x{:.2}, {:.1}/s total)",
-                                gt.description(),
+                                def.description,
                                 production,
                                 syn_mult,
                                 effective_per_unit * owned as f64,
@@ -1016,20 +1233,20 @@ fn spawn_generator_items(
                         } else {
                             format!(
                                 "{} (+{:.1}/s each, {:.1}/s total)",
-                                gt.description(),
+                                def.description,
                                 production,
-                                gt.production(owned),
+                                def.production(owned),
                             )
                         }
                     } else if syn_mult > 1.001 {
                         format!(
                             "{} (+{:.1} wisdom/s x{:.2})",
-                            gt.description(),
+                            def.description,
                             production,
                             syn_mult
                         )
                     } else {
-                        format!("{} (+{:.1} wisdom/s)", gt.description(), production)
+                        format!("{} (+{:.1} wisdom/s)", def.description, production)
                     };
                     info.spawn((
                         Text::new(desc),
@@ -1041,7 +1258,7 @@ fn spawn_generator_items(
                     ));
 
                     // Serenity cost line
-                    if let Some(s_cost) = serenity_cost {
+                    if let Some(s_cost) = offer.serenity {
                         let color = if has_serenity {
                             Color::srgba(0.4, 0.7, 0.9, 0.8)
                         } else {
@@ -1058,7 +1275,7 @@ fn spawn_generator_items(
                     }
 
                     // Synergy details line
-                    if let Some(syn_desc) = synergies.synergy_description(gt, generators) {
+                    if let Some(syn_desc) = synergies.synergy_description(gt, generators, content) {
                         info.spawn((
                             Text::new(syn_desc),
                             TextFont {
@@ -1071,7 +1288,7 @@ fn spawn_generator_items(
                 });
 
                 // Buy button
-                let (btn_bg, btn_text_color) = if affordable {
+                let (btn_bg, btn_text_color) = if offer.affordable {
                     (
                         Color::srgba(1.0, 0.85, 0.4, 0.9),
                         Color::srgb(0.08, 0.06, 0.14),
@@ -1097,7 +1314,7 @@ fn spawn_generator_items(
                 ))
                 .with_children(|btn| {
                     btn.spawn((
-                        Text::new(format_afp(cost)),
+                        Text::new(offer.label()),
                         TextFont {
                             font_size: 14.0,
                             ..default()
@@ -1106,6 +1323,16 @@ fn spawn_generator_items(
                     ));
                 });
             });
+
+        let mut upgrades: Vec<&ShopItem> = content
+            .shop_items
+            .iter()
+            .filter(|item| item.requires.is_some_and(|req| req.generator == gt))
+            .collect();
+        upgrades.sort_by_key(|item| item.requires.map_or(0, |req| req.owned));
+        for item in upgrades {
+            spawn_generator_upgrade_row(parent, item, content, tracker, generators, progress);
+        }
     }
 
     if !any_visible {
@@ -1126,6 +1353,9 @@ pub fn handle_buy_generator(
     mut progress: ResMut<ArcaneProgress>,
     mut resources: ResMut<SecondaryResources>,
     transcendence: Res<TranscendenceState>,
+    content: Res<GameContent>,
+    quantity: Res<BuyQuantity>,
+    mut purchased: MessageWriter<GeneratorPurchased>,
 ) {
     let discount = transcendence.generator_cost_discount();
     for (interaction, button) in &interactions {
@@ -1134,34 +1364,102 @@ pub fn handle_buy_generator(
         }
 
         let owned = generators.count(button.0);
-        let cost = button.0.next_cost_discounted(owned, discount);
-
-        if progress.focus_points < cost {
+        let offer = GeneratorOffer::new(
+            &content,
+            button.0,
+            owned,
+            discount,
+            *quantity,
+            progress.focus_points,
+            resources.serenity,
+        );
+        if !offer.affordable {
             continue;
         }
 
-        // Check serenity requirement for high-tier generators
-        if let Some(serenity_cost) = button.0.serenity_cost() {
-            if resources.serenity < serenity_cost {
-                continue;
-            }
+        // High-tier generators also cost serenity, per unit
+        if let Some(serenity_cost) = offer.serenity {
             resources.serenity -= serenity_cost;
         }
+        progress.focus_points -= offer.cost;
+        generators.add_many(button.0, offer.count);
+        purchased.write(GeneratorPurchased {
+            generator: button.0,
+            owned: generators.count(button.0),
+        });
+    }
+}
+
+pub fn handle_quantity_click(
+    interactions: Query<(&Interaction, &BuyQuantityButton), Changed<Interaction>>,
+    mut quantity: ResMut<BuyQuantity>,
+) {
+    for (interaction, button) in &interactions {
+        if *interaction == Interaction::Pressed && *quantity != button.0 {
+            *quantity = button.0;
+        }
+    }
+}
+
+/// Keep generator prices current as AFP and serenity change; "Max" moves with them.
+/// Only values that differ are written, so untouched buttons aren't re-laid out.
+pub fn update_generator_buttons(
+    content: Res<GameContent>,
+    generators: Res<GeneratorState>,
+    progress: Res<ArcaneProgress>,
+    resources: Res<SecondaryResources>,
+    transcendence: Res<TranscendenceState>,
+    quantity: Res<BuyQuantity>,
+    mut buttons: Query<(&BuyGeneratorButton, &mut BackgroundColor, &Children)>,
+    mut texts: Query<(&mut Text, &mut TextColor)>,
+) {
+    let discount = transcendence.generator_cost_discount();
+    for (button, mut bg, children) in &mut buttons {
+        let offer = GeneratorOffer::new(
+            &content,
+            button.0,
+            generators.count(button.0),
+            discount,
+            *quantity,
+            progress.focus_points,
+            resources.serenity,
+        );
+        let (btn_bg, btn_text_color) = if offer.affordable {
+            (
+                Color::srgba(1.0, 0.85, 0.4, 0.9),
+                Color::srgb(0.08, 0.06, 0.14),
+            )
+        } else {
+            (
+                Color::srgba(0.3, 0.25, 0.4, 0.5),
+                Color::srgba(0.5, 0.45, 0.6, 0.5),
+            )
+        };
+        bg.set_if_neq(BackgroundColor(btn_bg));
+
+        let label = offer.label();
+        for child in children.iter() {
+            if let Ok((mut text, mut color)) = texts.get_mut(child) {
+                if text.0 != label {
+                    text.0.clone_from(&label);
+                }
+                color.set_if_neq(TextColor(btn_text_color));
+            }
+        }
+    }
+}
 
-        progress.focus_points -= cost;
-        generators.add(button.0);
+/// Price on a shop item's buy button, or the generator count it still needs
+fn item_price_label(item: &ShopItem, generators: &GeneratorState, content: &GameContent) -> String {
+    match item.requires {
+        Some(req) if !item.is_unlocked(generators) => {
+            format!("Own {} {}", req.owned, content.generator(req.generator).name)
+        }
+        _ => format!("{} AFP", item.cost),
     }
 }
 
 /// Format large AFP values with K/M/B suffixes for readability
-fn format_afp(value: u64) -> String {
-    if value >= 1_000_000_000 {
-        format!("{:.1}B AFP", value as f64 / 1_000_000_000.0)
-    } else if value >= 1_000_000 {
-        format!("{:.1}M AFP", value as f64 / 1_000_000.0)
-    } else if value >= 10_000 {
-        format!("{:.1}K AFP", value as f64 / 1_000.0)
-    } else {
-        format!("{} AFP", value)
-    }
+fn format_afp(value: BigNum) -> String {
+    format!("{} AFP", value.format_short())
 }
@@ -0,0 +1,357 @@
+use super::bignum::BigNum;
+use super::content::GameContent;
+use super::economy::EconomyModel;
+use super::generators::GeneratorType;
+use super::ledger::WisdomSource;
+use super::persistence::SaveData;
+use super::transcendence::pending_insight;
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+// ========== STRATEGY ==========
+
+/// What the simulated player spends AFP on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Strategy {
+    /// Buy nothing
+    Idle,
+    /// Only summon acolytes
+    Acolytes,
+    /// Only buy generators, cheapest first
+    Generators,
+    /// Whatever is cheapest, acolytes included
+    Cheapest,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [Self::Idle, Self::Acolytes, Self::Generators, Self::Cheapest];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Acolytes => "acolytes",
+            Self::Generators => "generators",
+            Self::Cheapest => "cheapest",
+        }
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.name() == s)
+            .ok_or_else(|| format!("unknown strategy \"{}\"", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Purchase {
+    Acolyte,
+    Generator(GeneratorType),
+}
+
+/// The cheapest purchase `strategy` allows that can be made right now
+fn next_purchase(model: &EconomyModel, strategy: Strategy) -> Option<(Purchase, BigNum)> {
+    let mut options = Vec::new();
+    if matches!(strategy, Strategy::Acolytes | Strategy::Cheapest) {
+        options.push((Purchase::Acolyte, model.acolytes.next_cost(&model.content.balance)));
+    }
+    if matches!(strategy, Strategy::Generators | Strategy::Cheapest) {
+        let discount = model.transcendence.generator_cost_discount();
+        for gt in GeneratorType::ALL {
+            let def = model.content.generator(gt);
+            let unlocked = model.progress.total_truths >= def.unlock_truths;
+            let calm = def.serenity_cost.is_none_or(|s| model.resources.serenity >= s);
+            if unlocked && calm {
+                let owned = model.generators.count(gt);
+                let cost = model.content.generator_cost(gt, owned, discount);
+                options.push((Purchase::Generator(gt), cost));
+            }
+        }
+    }
+    options
+        .into_iter()
+        .filter(|(_, cost)| model.progress.focus_points >= *cost)
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+/// Spend AFP until nothing else is affordable; returns the AFP spent
+fn spend(model: &mut EconomyModel, strategy: Strategy) -> BigNum {
+    let mut spent = BigNum::ZERO;
+    while let Some((purchase, cost)) = next_purchase(model, strategy) {
+        let bought = match purchase {
+            Purchase::Acolyte => model.summon_acolyte(),
+            Purchase::Generator(gt) => model.buy_generator(gt),
+        };
+        if !bought {
+            break;
+        }
+        spent += cost;
+    }
+    spent
+}
+
+// ========== SIMULATION ==========
+
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    pub strategy: Strategy,
+    pub duration_secs: f64,
+    /// Purchases are made at the start of each step
+    pub step_secs: f64,
+    /// Orb clicks per second; fractions carry over between steps
+    pub clicks_per_sec: f64,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            strategy: Strategy::Cheapest,
+            duration_secs: 4.0 * 60.0 * 60.0,
+            step_secs: 1.0,
+            clicks_per_sec: 0.0,
+        }
+    }
+}
+
+/// When a generator tier first showed up in the shop
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneratorUnlock {
+    pub generator: GeneratorType,
+    pub name: String,
+    pub owned: u32,
+    /// Seconds into the simulation; `None` if never reached
+    pub unlocked_at: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimReport {
+    pub strategy: Strategy,
+    pub duration_secs: f64,
+    /// Truths earned during the simulation
+    pub truths: u32,
+    pub total_truths: u32,
+    pub afp: BigNum,
+    pub afp_earned: BigNum,
+    pub afp_spent: BigNum,
+    pub wisdom_per_sec: BigNum,
+    pub acolytes: u32,
+    pub generators: Vec<GeneratorUnlock>,
+    /// Insight a transcendence would grant at the end
+    pub pending_insight: u32,
+    /// Insight already banked from earlier transcendences
+    pub insight: u32,
+    /// First point at which transcending would grant insight
+    pub first_transcendence_at: Option<f64>,
+}
+
+/// Run the economy forward from `save` under `config`, without an `App`
+pub fn simulate(save: &SaveData, content: &GameContent, config: &SimConfig) -> SimReport {
+    let mut model = EconomyModel::from_save(save, content);
+    let mut ledger = save.wisdom_ledger.clone();
+    let start_truths = model.progress.total_truths;
+    let mut unlocked_at: [Option<f64>; 8] = [None; 8];
+    let mut first_transcendence_at = None;
+    let mut afp_earned = BigNum::ZERO;
+    let mut afp_spent = BigNum::ZERO;
+    let mut clicks_owed = 0.0;
+    let mut elapsed = 0.0;
+
+    loop {
+        for gt in GeneratorType::ALL {
+            let seen = model.progress.total_truths >= content.generator(gt).unlock_truths;
+            if seen && unlocked_at[gt as usize].is_none() {
+                unlocked_at[gt as usize] = Some(elapsed);
+            }
+        }
+        if first_transcendence_at.is_none() && pending_insight(&ledger, &model.ascension) > 0 {
+            first_transcendence_at = Some(elapsed);
+        }
+        if elapsed >= config.duration_secs {
+            break;
+        }
+
+        let dt = config.step_secs.min(config.duration_secs - elapsed);
+        afp_spent += spend(&mut model, config.strategy);
+
+        clicks_owed += config.clicks_per_sec * dt;
+        let clicks = clicks_owed.floor();
+        clicks_owed -= clicks;
+        ledger.record(WisdomSource::Click, model.ponder(clicks as u32));
+
+        let shares = production_shares(&model);
+        let report = model.advance(dt);
+        afp_earned += report.afp_earned;
+        for (source, share) in shares {
+            ledger.record(source, report.wisdom_produced * share);
+        }
+        elapsed += dt;
+    }
+
+    SimReport {
+        strategy: config.strategy,
+        duration_secs: elapsed,
+        truths: model.progress.total_truths - start_truths,
+        total_truths: model.progress.total_truths,
+        afp: model.progress.focus_points,
+        afp_earned,
+        afp_spent,
+        wisdom_per_sec: model.passive_rate(),
+        acolytes: model.acolytes.count,
+        generators: GeneratorType::ALL
+            .iter()
+            .map(|&gt| GeneratorUnlock {
+                generator: gt,
+                name: content.generator(gt).name.clone(),
+                owned: model.generators.count(gt),
+                unlocked_at: unlocked_at[gt as usize],
+            })
+            .collect(),
+        pending_insight: pending_insight(&ledger, &model.ascension),
+        insight: model.transcendence.insight,
+        first_transcendence_at,
+    }
+}
+
+/// How passive wisdom splits between acolytes and each generator. Modifiers scale
+/// every passive source alike, so base production gives the split.
+fn production_shares(model: &EconomyModel) -> Vec<(WisdomSource, f64)> {
+    let mut parts = vec![(
+        WisdomSource::Acolyte,
+        model.acolytes.passive_rate(&model.content.balance) as f64,
+    )];
+    for gt in GeneratorType::ALL {
+        let production = model
+            .synergies
+            .synergized_production(&model.generators, gt, &model.content);
+        parts.push((WisdomSource::Generator(gt), production));
+    }
+    let total: f64 = parts.iter().map(|&(_, base)| base).sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+    parts
+        .into_iter()
+        .filter(|&(_, base)| base > 0.0)
+        .map(|(source, base)| (source, base / total))
+        .collect()
+}
+
+// ========== REPORT ==========
+
+/// "1h 02m 03s", dropping leading zero units
+pub fn format_duration(secs: f64) -> String {
+    let secs = secs.max(0.0).round() as u64;
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+fn format_time(at: Option<f64>) -> String {
+    at.map_or_else(|| "not reached".to_string(), format_duration)
+}
+
+impl fmt::Display for SimReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Strategy: {}, {} simulated",
+            self.strategy.name(),
+            format_duration(self.duration_secs)
+        )?;
+        writeln!(f, "Truths: {} ({} total)", self.truths, self.total_truths)?;
+        writeln!(
+            f,
+            "AFP: {} now, {} earned, {} spent",
+            self.afp.format_short(),
+            self.afp_earned.format_short(),
+            self.afp_spent.format_short()
+        )?;
+        writeln!(f, "Passive wisdom: {}/s", self.wisdom_per_sec.format_short())?;
+        writeln!(f, "Acolytes: {}", self.acolytes)?;
+        writeln!(f, "Generators:")?;
+        for unlock in &self.generators {
+            writeln!(
+                f,
+                "  {:<18} {:>5} owned, unlocked: {}",
+                unlock.name,
+                unlock.owned,
+                format_time(unlock.unlocked_at)
+            )?;
+        }
+        writeln!(
+            f,
+            "Insight: {} pending, {} banked",
+            self.pending_insight, self.insight
+        )?;
+        write!(
+            f,
+            "First transcendence: {}",
+            format_time(self.first_transcendence_at)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(strategy: Strategy, clicks_per_sec: f64, hours: f64) -> SimReport {
+        let config = SimConfig {
+            strategy,
+            duration_secs: hours * 3600.0,
+            step_secs: 1.0,
+            clicks_per_sec,
+        };
+        simulate(&SaveData::new_game(), &GameContent::default(), &config)
+    }
+
+    #[test]
+    fn idle_fresh_game_makes_no_progress() {
+        let report = run(Strategy::Idle, 0.0, 1.0);
+        assert_eq!(report.truths, 0);
+        assert_eq!(report.generators[0].unlocked_at, Some(0.0));
+        assert_eq!(report.generators[1].unlocked_at, None);
+        assert_eq!(report.first_transcendence_at, None);
+    }
+
+    #[test]
+    fn spending_beats_hoarding() {
+        let idle = run(Strategy::Idle, 2.0, 2.0);
+        let cheapest = run(Strategy::Cheapest, 2.0, 2.0);
+        assert!(idle.truths > 0);
+        assert!(cheapest.truths > idle.truths);
+        assert!(cheapest.afp_spent.is_positive());
+        assert!(cheapest.generators[0].owned > 0 || cheapest.acolytes > 0);
+    }
+
+    #[test]
+    fn unlocks_arrive_in_tier_order() {
+        let report = run(Strategy::Cheapest, 2.0, 4.0);
+        let times: Vec<f64> = report.generators.iter().filter_map(|g| g.unlocked_at).collect();
+        assert!(times.len() > 1);
+        assert!(times.windows(2).all(|w| w[0] <= w[1]));
+        if let Some(at) = report.first_transcendence_at {
+            assert!(at <= report.duration_secs);
+            assert!(report.pending_insight > 0);
+        }
+    }
+
+    #[test]
+    fn strategies_parse_by_name() {
+        for strategy in Strategy::ALL {
+            assert_eq!(strategy.name().parse::<Strategy>(), Ok(strategy));
+        }
+        assert!("hoard".parse::<Strategy>().is_err());
+        assert_eq!(format_duration(3723.0), "1h 02m 03s");
+    }
+}
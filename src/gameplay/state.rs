@@ -2,16 +2,20 @@ use bevy::prelude::*;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 pub enum GameState {
+    /// Choosing a player profile before the save is loaded
     #[default]
+    ProfileSelect,
     Playing,
     Paused,
     LogbookOpen,
     ShopOpen,
     TranscendenceOpen,
+    AscensionOpen,
     SchoolSelection,
     AchievementsOpen,
     ChallengesOpen,
     CodexOpen,
+    SaveTransfer,
 }
 
 #[derive(Component)]
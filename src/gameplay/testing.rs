@@ -0,0 +1,457 @@
+//! Drives the whole `GameplayPlugin` headlessly for scenario tests
+
+use super::GameplayPlugin;
+use super::economy::TICKS_PER_SECOND;
+use super::persistence::{SaveData, SaveSources};
+use super::rng::GameRng;
+use super::save_storage::SaveStore;
+use super::state::GameState;
+use crate::environment::daynight::DayNightCycle;
+use crate::orb::types::{EquippedOrb, OrbType};
+use bevy::ecs::system::RunSystemOnce;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::FileDragAndDrop;
+use std::time::Duration;
+
+/// Seed for every test game, so scenarios draw the same randomness each run
+pub const SEED: u64 = 11;
+
+/// The game on `MinimalPlugins` with scripted input and a manual clock.
+/// Each `update` is one frame of `1 / fps` seconds; the economy ticks at its
+/// usual fixed rate underneath.
+pub struct TestApp {
+    pub app: App,
+    frame: Duration,
+}
+
+impl Default for TestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestApp {
+    /// A seeded game in `Playing`, rendering at 60 fps. It has run its first
+    /// economy tick, so modifiers are collected before any input arrives.
+    pub fn new() -> Self {
+        let mut app = App::new();
+        // Input arrives through `tap` rather than `InputPlugin`, which would clear it
+        app.insert_resource(GameRng::from_seed(SEED))
+            .insert_resource(SaveStore::memory())
+            .add_plugins((MinimalPlugins, StatesPlugin, GameplayPlugin))
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ButtonInput<MouseButton>>()
+            .add_message::<KeyboardInput>()
+            .add_message::<FileDragAndDrop>()
+            .init_resource::<DayNightCycle>()
+            .insert_resource(EquippedOrb(OrbType::Crystal))
+            .insert_state(GameState::Playing);
+        let mut game = Self {
+            app,
+            frame: Duration::ZERO,
+        }
+        .at_fps(60);
+        while game.resource::<Time<Fixed>>().elapsed().is_zero() {
+            game.update();
+        }
+        game
+    }
+
+    /// Render at a different frame rate
+    pub fn at_fps(mut self, fps: u32) -> Self {
+        self.frame = Duration::from_secs(1) / fps;
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(self.frame));
+        self
+    }
+
+    // ========== INPUT ==========
+
+    /// Press and release `key`, leaving two frames for the press to land
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.tap::<KeyCode>(key);
+    }
+
+    /// Left click somewhere that isn't a UI button
+    pub fn click_orb(&mut self) {
+        self.tap::<MouseButton>(MouseButton::Left);
+    }
+
+    /// Press the first `B` button that `matches`, as a player clicking it would
+    pub fn click_button<B: Component>(&mut self, matches: impl Fn(&B) -> bool) {
+        let world = self.app.world_mut();
+        let entity = world
+            .query::<(Entity, &B, &Interaction)>()
+            .iter(world)
+            .find(|(_, button, _)| matches(button))
+            .map(|(entity, _, _)| entity)
+            .unwrap_or_else(|| panic!("no {} on screen", std::any::type_name::<B>()));
+
+        world.entity_mut(entity).insert(Interaction::Pressed);
+        self.update();
+        if let Ok(mut released) = self.app.world_mut().get_entity_mut(entity) {
+            released.insert(Interaction::None);
+        }
+        self.update();
+    }
+
+    /// One frame with `input` held, then one with it released. Consumers run in
+    /// `Update` or the next economy tick, and state changes land a frame later.
+    fn tap<T: Copy + Eq + std::hash::Hash + Send + Sync + 'static>(&mut self, input: T) {
+        self.resource_mut::<ButtonInput<T>>().press(input);
+        self.update();
+        let mut buttons = self.resource_mut::<ButtonInput<T>>();
+        buttons.release(input);
+        buttons.clear();
+        self.update();
+    }
+
+    // ========== TIME ==========
+
+    pub fn update(&mut self) {
+        self.app.update();
+    }
+
+    /// Step frames until exactly `secs` more seconds of economy ticks have run
+    pub fn advance_secs(&mut self, secs: u32) {
+        let timestep = self.resource::<Time<Fixed>>().timestep();
+        let target =
+            self.resource::<Time<Fixed>>().elapsed() + timestep * secs * TICKS_PER_SECOND as u32;
+        loop {
+            let fixed = self.resource::<Time<Fixed>>();
+            let remaining = target - fixed.elapsed() - fixed.overstep();
+            if remaining <= self.frame {
+                // One short last frame lands on the target tick
+                self.app
+                    .insert_resource(TimeUpdateStrategy::ManualDuration(remaining));
+                self.update();
+                self.app
+                    .insert_resource(TimeUpdateStrategy::ManualDuration(self.frame));
+                break;
+            }
+            self.update();
+        }
+        assert_eq!(self.resource::<Time<Fixed>>().elapsed(), target);
+    }
+
+    // ========== STATE ==========
+
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world().resource::<R>()
+    }
+
+    pub fn resource_mut<R: Resource>(&mut self) -> Mut<'_, R> {
+        self.app.world_mut().resource_mut::<R>()
+    }
+
+    pub fn state(&self) -> GameState {
+        *self.resource::<State<GameState>>().get()
+    }
+
+    /// Switch screens directly, running the exit and enter systems
+    pub fn set_state(&mut self, state: GameState) {
+        self.resource_mut::<NextState<GameState>>().set(state);
+        self.update();
+    }
+
+    /// What an auto-save would write right now
+    pub fn capture_save(&mut self) -> SaveData {
+        self.app
+            .world_mut()
+            .run_system_once(|sources: SaveSources| sources.capture())
+            .expect("save sources are all present")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::achievements::{AchievementId, AchievementTracker};
+    use crate::gameplay::bignum::BigNum;
+    use crate::gameplay::challenges::{ChallengeButton, ChallengeId, ChallengeState};
+    use crate::gameplay::codex::TruthCodex;
+    use crate::gameplay::content::GameContent;
+    use crate::gameplay::generators::{GeneratorState, GeneratorType};
+    use crate::gameplay::ledger::{WisdomLedger, WisdomSource};
+    use crate::gameplay::modifiers::{WisdomModifiers, CLICK_BASE};
+    use crate::gameplay::persistence::load_game;
+    use crate::gameplay::profiles::{ActiveProfile, Profile};
+    use crate::gameplay::progression::ArcaneProgress;
+    use crate::gameplay::save_files;
+    use crate::gameplay::schools::{SchoolChoiceButton, SchoolOfThought, SchoolState};
+    use crate::gameplay::synergies::SynergyState;
+    use crate::gameplay::shop::{
+        BuyButton, BuyGeneratorButton, BuyQuantity, BuyQuantityButton, CategoryTab,
+        PurchaseTracker, ShopCategory, ShopItemId,
+    };
+    use crate::gameplay::transcendence::{TranscendButton, TranscendenceState};
+    use crate::gameplay::wisdom::WisdomMeter;
+    use bevy::app::AppExit;
+    use std::path::PathBuf;
+
+    fn afp(game: &TestApp) -> BigNum {
+        game.resource::<ArcaneProgress>().focus_points
+    }
+
+    #[test]
+    fn clicking_the_orb_fills_the_meter_into_a_truth() {
+        let mut game = TestApp::new();
+        game.click_orb();
+        assert!(game.resource::<WisdomMeter>().current.is_positive());
+
+        while game.resource::<WisdomMeter>().truths_generated == 0 {
+            game.click_orb();
+        }
+        game.update();
+
+        assert!(afp(&game).is_positive());
+        assert_eq!(game.resource::<TruthCodex>().discovered.len(), 1);
+        assert!(
+            game.resource::<WisdomLedger>()
+                .run
+                .get(WisdomSource::Click)
+                .is_positive()
+        );
+    }
+
+    #[test]
+    fn shop_purchases_spend_afp() {
+        let mut game = TestApp::new();
+        game.resource_mut::<ArcaneProgress>().focus_points = BigNum::from(100.0);
+        game.press_key(KeyCode::KeyB);
+        assert_eq!(game.state(), GameState::ShopOpen);
+
+        let biscuit = ShopItemId::from("ArcaneBiscuit");
+        game.click_button::<BuyButton>(|button| button.0 == biscuit);
+        let tracker = game.resource::<PurchaseTracker>();
+        assert!(tracker.purchased.contains(&biscuit));
+        assert!(tracker.efficiency_bonus > 0.0);
+        assert_eq!(afp(&game), BigNum::from(80.0));
+
+        game.click_button::<CategoryTab>(|tab| tab.0 == ShopCategory::Generators);
+        game.click_button::<BuyGeneratorButton>(|button| button.0 == GeneratorType::Candle);
+        assert_eq!(
+            game.resource::<GeneratorState>()
+                .count(GeneratorType::Candle),
+            1
+        );
+        assert!(afp(&game) < BigNum::from(80.0));
+
+        game.press_key(KeyCode::KeyB);
+        assert_eq!(game.state(), GameState::Playing);
+        game.advance_secs(5);
+        assert!(game.resource::<WisdomLedger>().run.total().is_positive());
+    }
+
+    #[test]
+    fn bulk_buying_generators_charges_the_batch_price() {
+        let mut game = TestApp::new();
+        game.resource_mut::<ArcaneProgress>().focus_points = BigNum::from(1.0e6);
+        game.press_key(KeyCode::KeyB);
+        game.click_button::<CategoryTab>(|tab| tab.0 == ShopCategory::Generators);
+
+        let content = game.resource::<GameContent>().clone();
+        let candle = GeneratorType::Candle;
+        game.click_button::<BuyQuantityButton>(|button| button.0 == BuyQuantity::Ten);
+        game.click_button::<BuyGeneratorButton>(|button| button.0 == candle);
+        assert_eq!(game.resource::<GeneratorState>().count(candle), 10);
+        let batch = content.generator_bulk_cost(candle, 0, 10, 0.0);
+        assert_eq!(afp(&game), BigNum::from(1.0e6) - batch);
+
+        // Max leaves too little for one more
+        game.click_button::<BuyQuantityButton>(|button| button.0 == BuyQuantity::Max);
+        game.click_button::<BuyGeneratorButton>(|button| button.0 == candle);
+        let owned = game.resource::<GeneratorState>().count(candle);
+        assert!(owned > 10);
+        assert!(afp(&game) < content.generator_cost(candle, owned, 0.0));
+    }
+
+    #[test]
+    fn generator_upgrades_unlock_at_owned_thresholds() {
+        let mut game = TestApp::new();
+        game.resource_mut::<ArcaneProgress>().focus_points = BigNum::from(1.0e6);
+        game.resource_mut::<GeneratorState>().owned[GeneratorType::Candle as usize] = 9;
+        game.press_key(KeyCode::KeyB);
+        game.click_button::<CategoryTab>(|tab| tab.0 == ShopCategory::Generators);
+
+        let wicks = ShopItemId::from("BraidedWicks");
+        let focus = ShopItemId::from("WaxenFocus");
+        game.click_button::<BuyButton>(|button| button.0 == wicks);
+        assert!(!game.resource::<PurchaseTracker>().purchased.contains(&wicks));
+
+        // The tenth candle unlocks the first production tier
+        game.click_button::<BuyGeneratorButton>(|button| button.0 == GeneratorType::Candle);
+        game.click_button::<BuyButton>(|button| button.0 == wicks);
+        game.click_button::<BuyButton>(|button| button.0 == focus);
+        let tracker = game.resource::<PurchaseTracker>();
+        assert!(tracker.purchased.contains(&wicks) && tracker.purchased.contains(&focus));
+
+        game.press_key(KeyCode::KeyB);
+        game.advance_secs(1);
+        let synergies = game.resource::<SynergyState>();
+        assert_eq!(synergies.upgrade_mult[GeneratorType::Candle as usize], 2.0);
+        assert!((synergies.click_bonus - 1.0).abs() < 1e-9);
+        assert!(game.resource::<WisdomModifiers>().click_wisdom() > BigNum::from(CLICK_BASE));
+
+        let save = game.capture_save();
+        assert!(save.purchased_items.contains(&wicks));
+        assert!(save.purchased_items.contains(&focus));
+    }
+
+    #[test]
+    fn challenges_fail_on_a_broken_rule_and_complete_on_time() {
+        let mut game = TestApp::new();
+        let begin_blindfold = |game: &mut TestApp| {
+            game.press_key(KeyCode::KeyC);
+            assert_eq!(game.state(), GameState::ChallengesOpen);
+            game.click_button::<ChallengeButton>(|button| button.0 == ChallengeId::Blindfold);
+            assert_eq!(game.state(), GameState::Playing);
+        };
+
+        begin_blindfold(&mut game);
+        game.click_orb();
+        let active = game.resource::<ChallengeState>().active.clone();
+        assert!(active.is_some_and(|a| a.failed));
+
+        // [C] abandons the failed attempt
+        game.press_key(KeyCode::KeyC);
+        assert!(!game.resource::<ChallengeState>().is_active());
+
+        begin_blindfold(&mut game);
+        // The challenge clock sums f32 deltas and lands just short of 300 at five minutes
+        game.advance_secs(301);
+        let challenges = game.resource::<ChallengeState>();
+        assert!(challenges.has_completed(ChallengeId::Blindfold));
+        assert!(!challenges.is_active());
+        assert!(challenges.passive_multiplier() > 1.0);
+    }
+
+    #[test]
+    fn buying_a_generator_in_the_shop_fails_silence_and_counts_for_achievements() {
+        let mut game = TestApp::new();
+        game.resource_mut::<ArcaneProgress>().focus_points = BigNum::from(100.0);
+        game.press_key(KeyCode::KeyC);
+        game.click_button::<ChallengeButton>(|button| button.0 == ChallengeId::Silence);
+        game.advance_secs(1);
+        let active = game.resource::<ChallengeState>().active.clone();
+        assert!(active.is_some_and(|a| !a.failed));
+
+        game.press_key(KeyCode::KeyB);
+        game.click_button::<CategoryTab>(|tab| tab.0 == ShopCategory::Generators);
+        game.click_button::<BuyGeneratorButton>(|button| button.0 == GeneratorType::Candle);
+        let active = game.resource::<ChallengeState>().active.clone();
+        assert!(active.is_some_and(|a| a.failed));
+
+        // Stay in the shop long enough for the purchase messages to expire
+        game.advance_secs(1);
+        assert_eq!(game.state(), GameState::ShopOpen);
+        game.press_key(KeyCode::KeyB);
+        game.advance_secs(1);
+        assert!(
+            game.resource::<AchievementTracker>()
+                .has(AchievementId::FirstGenerator)
+        );
+    }
+
+    #[test]
+    fn transcending_resets_the_run_but_keeps_insight() {
+        let mut game = TestApp::new();
+        game.resource_mut::<WisdomLedger>()
+            .record(WisdomSource::Acolyte, BigNum::from(4000.0));
+        game.resource_mut::<GeneratorState>().owned[0] = 10;
+        game.resource_mut::<ArcaneProgress>().focus_points = BigNum::from(500.0);
+
+        game.press_key(KeyCode::KeyT);
+        assert_eq!(game.state(), GameState::TranscendenceOpen);
+        game.click_button::<TranscendButton>(|_| true);
+        assert_eq!(game.state(), GameState::SchoolSelection);
+        assert_eq!(game.resource::<TranscendenceState>().insight, 2);
+
+        game.click_button::<SchoolChoiceButton>(|button| button.0 == SchoolOfThought::Stoicism);
+        assert_eq!(game.state(), GameState::Playing);
+        assert_eq!(
+            game.resource::<SchoolState>().active,
+            SchoolOfThought::Stoicism
+        );
+        assert_eq!(game.resource::<GeneratorState>().owned[0], 0);
+        assert_eq!(afp(&game), BigNum::ZERO);
+        assert!(game.resource::<WisdomLedger>().run.total().is_zero());
+
+        let transcendence = game.resource::<TranscendenceState>();
+        assert_eq!(transcendence.insight, 2);
+        assert_eq!(transcendence.total_transcendences, 1);
+    }
+
+    #[test]
+    fn saves_round_trip_through_a_profile() {
+        let profile = Profile {
+            name: "Tester".to_string(),
+            dir: PathBuf::from("profiles/tester"),
+            config_dir: PathBuf::from("profiles/tester"),
+        };
+        let storage = SaveStore::memory();
+
+        let mut game = TestApp::new();
+        game.app
+            .insert_resource(storage.clone())
+            .insert_resource(ActiveProfile(Some(profile.clone())));
+        game.resource_mut::<ArcaneProgress>().focus_points = BigNum::from(100.0);
+        game.press_key(KeyCode::KeyA);
+        for _ in 0..25 {
+            game.click_orb();
+        }
+        game.advance_secs(10);
+        game.app.world_mut().write_message(AppExit::Success);
+        game.update();
+        let saved = game.capture_save();
+        assert!(saved.truths_generated > 0, "scenario should produce truths");
+
+        let mut loaded = TestApp::new();
+        loaded
+            .app
+            .insert_resource(storage)
+            .insert_resource(ActiveProfile(Some(profile)));
+        // What leaving the profile picker runs, without the picker's own disk access
+        loaded.app.world_mut().run_system_once(load_game).unwrap();
+        let restored = loaded.capture_save();
+
+        assert_eq!(restored.truths_generated, saved.truths_generated);
+        assert_eq!(restored.acolyte_count, 1);
+        assert_eq!(restored.focus_points, saved.focus_points);
+        assert_eq!(restored.wisdom_current, saved.wisdom_current);
+        let sorted = |mut truths: Vec<usize>| {
+            truths.sort_unstable();
+            truths
+        };
+        assert_eq!(
+            sorted(restored.discovered_truths),
+            sorted(saved.discovered_truths)
+        );
+        assert_eq!(restored.wisdom_ledger.run, saved.wisdom_ledger.run);
+    }
+
+    #[test]
+    fn nothing_is_saved_while_picking_a_profile() {
+        let profile = Profile {
+            name: "Tester".to_string(),
+            dir: PathBuf::from("profiles/tester"),
+            config_dir: PathBuf::from("profiles/tester"),
+        };
+        let storage = SaveStore::memory();
+
+        let mut game = TestApp::new();
+        game.app
+            .insert_resource(storage.clone())
+            .insert_resource(ActiveProfile(Some(profile.clone())));
+        game.set_state(GameState::ProfileSelect);
+        // Past the auto-save interval, then quit from the picker
+        game.advance_secs(31);
+        game.app.world_mut().write_message(AppExit::Success);
+        game.update();
+
+        assert!(!storage.exists(&save_files::save_file_path(&profile.dir)));
+    }
+}
@@ -1,15 +1,25 @@
+use super::ascension::AscensionState;
+use super::content::GameContent;
+use super::ledger::WisdomLedger;
+use super::modifiers::{ModifierChannel, ModifierSource, WisdomModifiers};
 use super::state::GameState;
-use super::wisdom::WisdomMeter;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // ========== DATA ==========
 
+/// Generator cost discount per Efficient Design rank
+pub const DISCOUNT_PER_RANK: f64 = 0.1;
+
+/// Largest generator cost discount, so generators never become free
+pub const MAX_GENERATOR_DISCOUNT: f64 = 0.9;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EnlightenmentId {
-    /// +10% base wisdom per click permanently
+    /// +10% base wisdom per click per rank
     DeepRoots,
-    /// +25% passive generation permanently
+    /// +25% passive generation per rank
     EternalFlow,
     /// Start each run with 50 AFP
     HeadStart,
@@ -21,7 +31,7 @@ pub enum EnlightenmentId {
     ClarityAffinity,
     /// +100% all wisdom generation permanently
     Transcendent,
-    /// Generators cost 10% less
+    /// Generators cost 10% less per rank
     EfficientDesign,
 }
 
@@ -36,87 +46,173 @@ impl EnlightenmentId {
         Self::Transcendent,
         Self::EfficientDesign,
     ];
+}
 
-    pub fn name(&self) -> &'static str {
-        match self {
-            Self::DeepRoots => "Deep Roots",
-            Self::EternalFlow => "Eternal Flow",
-            Self::HeadStart => "Head Start",
-            Self::CosmicResonance => "Cosmic Resonance",
-            Self::ArcaneInheritance => "Arcane Inheritance",
-            Self::ClarityAffinity => "Clarity Affinity",
-            Self::Transcendent => "Transcendent Mind",
-            Self::EfficientDesign => "Efficient Design",
-        }
-    }
+/// An enlightenment's text, Insight cost and place in the tree, from the content file
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnlightenmentDef {
+    pub id: EnlightenmentId,
+    pub name: String,
+    pub description: String,
+    /// Insight for the first rank
+    pub cost: u32,
+    /// Ranks that can be bought; 1 for a one-time enlightenment
+    pub max_rank: u32,
+    /// Each rank costs this many times the one before it
+    pub cost_growth: f64,
+    /// Every one of these must be learned first
+    #[serde(default)]
+    pub requires: Vec<EnlightenmentId>,
+    /// Only one enlightenment in an exclusive group can be learned at a time
+    #[serde(default)]
+    pub exclusive: Option<String>,
+    /// Longest chain of prerequisites below this one; filled in when content is validated
+    #[serde(skip)]
+    pub tier: u32,
+}
 
-    pub fn description(&self) -> &'static str {
-        match self {
-            Self::DeepRoots => "Your pondering echoes across lifetimes. (+10% click wisdom)",
-            Self::EternalFlow => "Passive wisdom flows more freely. (+25% passive generation)",
-            Self::HeadStart => "Begin each journey with arcane reserves. (Start with 50 AFP)",
-            Self::CosmicResonance => {
-                "The cosmos amplifies your meditation. (+50% passive generation)"
-            }
-            Self::ArcaneInheritance => "Greater reserves carry over. (Start with 200 AFP)",
-            Self::ClarityAffinity => "Moments of Clarity find you more easily. (2x frequency)",
-            Self::Transcendent => "Your mind operates on a higher plane. (+100% all wisdom)",
-            Self::EfficientDesign => "Generators cost less to construct. (-10% generator costs)",
-        }
+impl EnlightenmentDef {
+    /// Base Insight for rank `rank + 1`, given `rank` already owned
+    pub fn rank_cost(&self, rank: u32) -> u32 {
+        (self.cost as f64 * self.cost_growth.powi(rank as i32)).ceil() as u32
     }
+}
 
-    pub fn cost(&self) -> u32 {
-        match self {
-            Self::DeepRoots => 1,
-            Self::EternalFlow => 2,
-            Self::HeadStart => 3,
-            Self::CosmicResonance => 5,
-            Self::ArcaneInheritance => 5,
-            Self::ClarityAffinity => 8,
-            Self::Transcendent => 15,
-            Self::EfficientDesign => 4,
-        }
-    }
+/// Where an enlightenment stands in the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeStatus {
+    /// Every rank is learned
+    Maxed,
+    /// A prerequisite is still missing
+    Locked,
+    /// Another enlightenment in its exclusive group is learned
+    Excluded,
+    /// The next rank can be learned for this much Insight
+    Available(u32),
 }
 
 // ========== RESOURCES ==========
 
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Clone, Default)]
 pub struct TranscendenceState {
     pub insight: u32,
     pub total_transcendences: u32,
-    pub purchased_enlightenments: Vec<EnlightenmentId>,
-    /// Total wisdom accumulated in this run (for insight calculation)
-    pub run_wisdom_accumulated: f64,
+    /// Ranks learned in each enlightenment; missing means none
+    pub ranks: HashMap<EnlightenmentId, u32>,
+    /// Insight paid for the learned ranks, which a respec gives back
+    pub spent: u32,
 }
 
-impl Default for TranscendenceState {
-    fn default() -> Self {
-        Self {
-            insight: 0,
-            total_transcendences: 0,
-            purchased_enlightenments: Vec::new(),
-            run_wisdom_accumulated: 0.0,
-        }
-    }
+/// How much insight would be earned if transcending now, from all wisdom gained this run
+pub fn pending_insight(ledger: &WisdomLedger, ascension: &AscensionState) -> u32 {
+    ascension.insight_gain((ledger.run_total() / 1000.0).sqrt().floor().to_u32())
 }
 
 impl TranscendenceState {
-    /// How much insight would be earned if transcending now
-    pub fn pending_insight(&self) -> u32 {
-        (self.run_wisdom_accumulated / 1000.0).sqrt().floor() as u32
+    pub fn rank(&self, id: EnlightenmentId) -> u32 {
+        self.ranks.get(&id).copied().unwrap_or(0)
     }
 
     pub fn has(&self, id: EnlightenmentId) -> bool {
-        self.purchased_enlightenments.contains(&id)
+        self.rank(id) > 0
+    }
+
+    /// Insight the next rank of `id` costs, after ascension discounts
+    pub fn next_rank_cost(
+        &self,
+        id: EnlightenmentId,
+        content: &GameContent,
+        ascension: &AscensionState,
+    ) -> u32 {
+        ascension.enlightenment_cost(content.enlightenment(id).rank_cost(self.rank(id)))
+    }
+
+    pub fn status(
+        &self,
+        id: EnlightenmentId,
+        content: &GameContent,
+        ascension: &AscensionState,
+    ) -> NodeStatus {
+        let def = content.enlightenment(id);
+        if self.rank(id) >= def.max_rank {
+            NodeStatus::Maxed
+        } else if !def.requires.iter().all(|&req| self.has(req)) {
+            NodeStatus::Locked
+        } else if self.excluded(id, content) {
+            NodeStatus::Excluded
+        } else {
+            NodeStatus::Available(self.next_rank_cost(id, content, ascension))
+        }
+    }
+
+    /// True if another enlightenment in `id`'s exclusive group is learned
+    fn excluded(&self, id: EnlightenmentId, content: &GameContent) -> bool {
+        let Some(group) = &content.enlightenment(id).exclusive else {
+            return false;
+        };
+        EnlightenmentId::ALL.into_iter().any(|other| {
+            other != id
+                && self.has(other)
+                && content.enlightenment(other).exclusive.as_ref() == Some(group)
+        })
+    }
+
+    /// Learn the next rank of `id` if the tree allows it and it is affordable
+    pub fn buy(
+        &mut self,
+        id: EnlightenmentId,
+        content: &GameContent,
+        ascension: &AscensionState,
+    ) -> bool {
+        let NodeStatus::Available(cost) = self.status(id, content, ascension) else {
+            return false;
+        };
+        if self.insight < cost {
+            return false;
+        }
+        self.insight -= cost;
+        self.spent += cost;
+        *self.ranks.entry(id).or_insert(0) += 1;
+        true
+    }
+
+    /// Every learned rank at today's price, for saves that didn't record `spent`
+    pub fn ranks_at_current_prices(
+        &self,
+        content: &GameContent,
+        ascension: &AscensionState,
+    ) -> u32 {
+        self.ranks
+            .iter()
+            .flat_map(|(&id, &rank)| {
+                let def = content.enlightenment(id);
+                (0..rank).map(move |r| ascension.enlightenment_cost(def.rank_cost(r)))
+            })
+            .sum()
+    }
+
+    /// Forget every enlightenment and refund the Insight paid. Returns the refund.
+    pub fn respec(&mut self) -> u32 {
+        let refund = self.spent;
+        self.insight += refund;
+        self.spent = 0;
+        self.ranks.clear();
+        refund
+    }
+
+    /// Enlightenments learned or shut off by an exclusive choice, out of all of them
+    pub fn tree_progress(&self, content: &GameContent) -> (usize, usize) {
+        let settled = EnlightenmentId::ALL
+            .into_iter()
+            .filter(|&id| self.has(id) || self.excluded(id, content))
+            .count();
+        (settled, EnlightenmentId::ALL.len())
     }
 
     /// Permanent click wisdom multiplier from enlightenments
     pub fn click_multiplier(&self) -> f32 {
         let mut mult = 1.0;
-        if self.has(EnlightenmentId::DeepRoots) {
-            mult += 0.1;
-        }
+        mult += 0.1 * self.rank(EnlightenmentId::DeepRoots) as f32;
         if self.has(EnlightenmentId::Transcendent) {
             mult += 1.0;
         }
@@ -126,9 +222,7 @@ impl TranscendenceState {
     /// Permanent passive wisdom multiplier from enlightenments
     pub fn passive_multiplier(&self) -> f32 {
         let mut mult = 1.0;
-        if self.has(EnlightenmentId::EternalFlow) {
-            mult += 0.25;
-        }
+        mult += 0.25 * self.rank(EnlightenmentId::EternalFlow) as f32;
         if self.has(EnlightenmentId::CosmicResonance) {
             mult += 0.5;
         }
@@ -152,11 +246,8 @@ impl TranscendenceState {
 
     /// Generator cost discount (0.0 = no discount, 0.1 = 10% off)
     pub fn generator_cost_discount(&self) -> f64 {
-        if self.has(EnlightenmentId::EfficientDesign) {
-            0.1
-        } else {
-            0.0
-        }
+        let rank = self.rank(EnlightenmentId::EfficientDesign) as f64;
+        (DISCOUNT_PER_RANK * rank).min(MAX_GENERATOR_DISCOUNT)
     }
 
     /// Moment spawn speed multiplier (higher = more frequent)
@@ -169,18 +260,17 @@ impl TranscendenceState {
     }
 }
 
-/// System to accumulate run wisdom from all sources
-pub fn accumulate_run_wisdom(
-    wisdom: Res<WisdomMeter>,
-    mut transcendence: ResMut<TranscendenceState>,
-    mut last_wisdom: Local<f32>,
-) {
-    let current = wisdom.current;
-    if current < *last_wisdom {
-        // Wisdom was reset (truth generated) - the amount that was consumed is the max wisdom
-        transcendence.run_wisdom_accumulated += wisdom.max_wisdom as f64;
+impl ModifierSource for TranscendenceState {
+    fn contribute(&self, _content: &GameContent, modifiers: &mut WisdomModifiers) {
+        modifiers.mul("enlightenment", ModifierChannel::Click, self.click_multiplier() as f64);
+        modifiers.mul("enlightenment", ModifierChannel::Passive, self.passive_multiplier() as f64);
     }
-    *last_wisdom = current;
+}
+
+/// The player transcended; the new run starts once a school is chosen
+#[derive(Message)]
+pub struct Transcended {
+    pub insight_gained: u32,
 }
 
 // ========== TRANSCENDENCE UI ==========
@@ -194,6 +284,9 @@ pub struct TranscendButton;
 #[derive(Component)]
 pub struct EnlightenmentBuyButton(pub EnlightenmentId);
 
+#[derive(Component)]
+pub struct RespecButton;
+
 #[derive(Component)]
 pub struct EnlightenmentPanel;
 
@@ -214,8 +307,14 @@ pub fn toggle_transcendence(
     }
 }
 
-pub fn open_transcendence_ui(mut commands: Commands, transcendence: Res<TranscendenceState>) {
-    let pending = transcendence.pending_insight();
+pub fn open_transcendence_ui(
+    mut commands: Commands,
+    transcendence: Res<TranscendenceState>,
+    ascension: Res<AscensionState>,
+    ledger: Res<WisdomLedger>,
+    content: Res<GameContent>,
+) {
+    let pending = pending_insight(&ledger, &ascension);
 
     commands
         .spawn((
@@ -234,7 +333,7 @@ pub fn open_transcendence_ui(mut commands: Commands, transcendence: Res<Transcen
             backdrop
                 .spawn((
                     Node {
-                        width: Val::Px(550.0),
+                        width: Val::Px(720.0),
                         max_height: Val::Percent(85.0),
                         flex_direction: FlexDirection::Column,
                         padding: UiRect::all(Val::Px(24.0)),
@@ -286,6 +385,20 @@ pub fn open_transcendence_ui(mut commands: Commands, transcendence: Res<Transcen
                             TextColor(Color::srgba(0.7, 0.6, 0.9, 0.8)),
                         ));
 
+                        // Where this run's wisdom came from
+                        section.spawn((
+                            Text::new(format!("Wisdom this run: {}", ledger.run_total().format_short())),
+                            TextFont { font_size: 13.0, ..default() },
+                            TextColor(Color::srgba(0.7, 0.6, 0.9, 0.7)),
+                        ));
+                        for (source, amount) in ledger.run.ranked().iter().take(5) {
+                            section.spawn((
+                                Text::new(format!("  {}: {}", source.name(&content), amount.format_short())),
+                                TextFont { font_size: 12.0, ..default() },
+                                TextColor(Color::srgba(0.6, 0.55, 0.8, 0.6)),
+                            ));
+                        }
+
                         section.spawn((
                             Text::new("Transcendence resets AFP, generators, shop upgrades, and acolytes.\nInsight and enlightenments are permanent."),
                             TextFont { font_size: 13.0, ..default() },
@@ -320,99 +433,17 @@ pub fn open_transcendence_ui(mut commands: Commands, transcendence: Res<Transcen
                         BackgroundColor(Color::srgba(0.7, 0.5, 1.0, 0.15)),
                     ));
 
-                    // Enlightenment upgrades header
-                    panel.spawn((
-                        Text::new("Enlightenment Upgrades"),
-                        TextFont { font_size: 20.0, ..default() },
-                        TextColor(Color::srgb(0.9, 0.8, 1.0)),
-                    ));
-
-                    // Enlightenment items
+                    // Enlightenment tree, redrawn by `rebuild_enlightenment_tree`
                     panel.spawn((
                         Node {
                             width: Val::Percent(100.0),
                             flex_direction: FlexDirection::Column,
-                            row_gap: Val::Px(8.0),
+                            row_gap: Val::Px(10.0),
                             ..default()
                         },
                         EnlightenmentPanel,
-                    )).with_children(|list| {
-                        for eid in EnlightenmentId::ALL {
-                            let owned = transcendence.has(eid);
-                            let affordable = transcendence.insight >= eid.cost();
-
-                            list.spawn(Node {
-                                width: Val::Percent(100.0),
-                                justify_content: JustifyContent::SpaceBetween,
-                                align_items: AlignItems::Center,
-                                padding: UiRect::all(Val::Px(8.0)),
-                                column_gap: Val::Px(12.0),
-                                border_radius: BorderRadius::all(Val::Px(4.0)),
-                                ..default()
-                            }).with_children(|row| {
-                                row.spawn(Node {
-                                    flex_direction: FlexDirection::Column,
-                                    row_gap: Val::Px(2.0),
-                                    flex_grow: 1.0,
-                                    ..default()
-                                }).with_children(|info| {
-                                    let name_color = if owned {
-                                        Color::srgba(0.6, 0.55, 0.7, 0.5)
-                                    } else {
-                                        Color::srgb(0.9, 0.88, 0.8)
-                                    };
-                                    info.spawn((
-                                        Text::new(eid.name()),
-                                        TextFont { font_size: 18.0, ..default() },
-                                        TextColor(name_color),
-                                    ));
-                                    info.spawn((
-                                        Text::new(eid.description()),
-                                        TextFont { font_size: 13.0, ..default() },
-                                        TextColor(Color::srgba(0.6, 0.55, 0.7, 0.7)),
-                                    ));
-                                });
-
-                                let (btn_bg, btn_text_color, label) = if owned {
-                                    (
-                                        Color::srgba(0.2, 0.5, 0.25, 0.6),
-                                        Color::srgb(0.4, 0.9, 0.5),
-                                        "Owned".to_string(),
-                                    )
-                                } else if affordable {
-                                    (
-                                        Color::srgba(0.7, 0.5, 1.0, 0.9),
-                                        Color::srgb(1.0, 1.0, 1.0),
-                                        format!("{} Insight", eid.cost()),
-                                    )
-                                } else {
-                                    (
-                                        Color::srgba(0.3, 0.25, 0.4, 0.5),
-                                        Color::srgba(0.5, 0.45, 0.6, 0.5),
-                                        format!("{} Insight", eid.cost()),
-                                    )
-                                };
-
-                                row.spawn((
-                                    Button,
-                                    Node {
-                                        padding: UiRect::axes(Val::Px(14.0), Val::Px(6.0)),
-                                        border_radius: BorderRadius::all(Val::Px(4.0)),
-                                        justify_content: JustifyContent::Center,
-                                        min_width: Val::Px(90.0),
-                                        ..default()
-                                    },
-                                    BackgroundColor(btn_bg),
-                                    EnlightenmentBuyButton(eid),
-                                )).with_children(|btn| {
-                                    btn.spawn((
-                                        Text::new(label),
-                                        TextFont { font_size: 14.0, ..default() },
-                                        TextColor(btn_text_color),
-                                    ));
-                                });
-                            });
-                        }
+                    )).with_children(|tree| {
+                        spawn_enlightenment_tree(tree, &transcendence, &ascension, &content);
                     });
 
                     // Footer
@@ -441,14 +472,17 @@ pub fn close_transcendence_ui(
 pub fn handle_transcend_click(
     interactions: Query<&Interaction, (Changed<Interaction>, With<TranscendButton>)>,
     mut transcendence: ResMut<TranscendenceState>,
+    ascension: Res<AscensionState>,
+    mut ledger: ResMut<WisdomLedger>,
     mut next_state: ResMut<NextState<GameState>>,
+    mut transcended: MessageWriter<Transcended>,
 ) {
     for interaction in &interactions {
         if *interaction != Interaction::Pressed {
             continue;
         }
 
-        let gained = transcendence.pending_insight();
+        let gained = pending_insight(&ledger, &ascension);
         if gained == 0 {
             continue;
         }
@@ -456,7 +490,10 @@ pub fn handle_transcend_click(
         // Grant insight (permanent)
         transcendence.insight += gained;
         transcendence.total_transcendences += 1;
-        transcendence.run_wisdom_accumulated = 0.0;
+        ledger.start_run();
+        transcended.write(Transcended {
+            insight_gained: gained,
+        });
 
         // Go to school selection — the actual reset happens when a school is chosen
         next_state.set(GameState::SchoolSelection);
@@ -466,22 +503,331 @@ pub fn handle_transcend_click(
 pub fn handle_enlightenment_buy(
     interactions: Query<(&Interaction, &EnlightenmentBuyButton), Changed<Interaction>>,
     mut transcendence: ResMut<TranscendenceState>,
+    ascension: Res<AscensionState>,
+    content: Res<GameContent>,
 ) {
     for (interaction, button) in &interactions {
         if *interaction != Interaction::Pressed {
             continue;
         }
+        transcendence.buy(button.0, &content, &ascension);
+    }
+}
 
-        if transcendence.has(button.0) {
-            continue;
+pub fn handle_respec_click(
+    interactions: Query<&Interaction, (Changed<Interaction>, With<RespecButton>)>,
+    mut transcendence: ResMut<TranscendenceState>,
+) {
+    for interaction in &interactions {
+        if *interaction == Interaction::Pressed {
+            transcendence.respec();
         }
+    }
+}
 
-        let cost = button.0.cost();
-        if transcendence.insight < cost {
-            continue;
+/// Redraw the tree and the Insight line after a purchase or respec
+pub fn rebuild_enlightenment_tree(
+    mut commands: Commands,
+    transcendence: Res<TranscendenceState>,
+    ascension: Res<AscensionState>,
+    content: Res<GameContent>,
+    tree_query: Query<Entity, With<EnlightenmentPanel>>,
+    mut insight_text: Query<&mut Text, With<InsightText>>,
+) {
+    if !transcendence.is_changed() {
+        return;
+    }
+
+    for mut text in &mut insight_text {
+        **text = format!(
+            "Current Insight: {}  |  Transcendences: {}",
+            transcendence.insight, transcendence.total_transcendences
+        );
+    }
+
+    for tree in &tree_query {
+        commands.entity(tree).despawn_related::<Children>();
+        commands
+            .entity(tree)
+            .with_children(|tree: &mut ChildSpawnerCommands| {
+                spawn_enlightenment_tree(tree, &transcendence, &ascension, &content);
+            });
+    }
+}
+
+// ========== UI HELPERS ==========
+
+/// The respec button and one row per tier, so every enlightenment sits below
+/// the ones it requires
+fn spawn_enlightenment_tree(
+    parent: &mut ChildSpawnerCommands,
+    transcendence: &TranscendenceState,
+    ascension: &AscensionState,
+    content: &GameContent,
+) {
+    let refund = transcendence.spent;
+    parent.spawn(Node {
+        width: Val::Percent(100.0),
+        justify_content: JustifyContent::SpaceBetween,
+        align_items: AlignItems::Center,
+        ..default()
+    }).with_children(|header| {
+        header.spawn((
+            Text::new("Enlightenment Tree"),
+            TextFont { font_size: 20.0, ..default() },
+            TextColor(Color::srgb(0.9, 0.8, 1.0)),
+        ));
+        if refund > 0 {
+            header.spawn((
+                Button,
+                Node {
+                    padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+                    border_radius: BorderRadius::all(Val::Px(4.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.4, 0.3, 0.55, 0.8)),
+                RespecButton,
+            )).with_children(|btn| {
+                btn.spawn((
+                    Text::new(format!("Respec (+{} Insight)", refund)),
+                    TextFont { font_size: 13.0, ..default() },
+                    TextColor(Color::srgb(1.0, 1.0, 1.0)),
+                ));
+            });
         }
+    });
+
+    let top_tier = EnlightenmentId::ALL
+        .iter()
+        .map(|&id| content.enlightenment(id).tier)
+        .max()
+        .unwrap_or(0);
+    for tier in 0..=top_tier {
+        parent.spawn(Node {
+            width: Val::Percent(100.0),
+            flex_wrap: FlexWrap::Wrap,
+            justify_content: JustifyContent::Center,
+            column_gap: Val::Px(8.0),
+            row_gap: Val::Px(8.0),
+            ..default()
+        }).with_children(|row| {
+            for eid in EnlightenmentId::ALL {
+                if content.enlightenment(eid).tier == tier {
+                    spawn_enlightenment_node(row, eid, transcendence, ascension, content);
+                }
+            }
+        });
+    }
+}
+
+fn spawn_enlightenment_node(
+    parent: &mut ChildSpawnerCommands,
+    id: EnlightenmentId,
+    transcendence: &TranscendenceState,
+    ascension: &AscensionState,
+    content: &GameContent,
+) {
+    let def = content.enlightenment(id);
+    let rank = transcendence.rank(id);
+    let (outline, btn_bg, btn_text_color, label) = match transcendence.status(id, content, ascension) {
+        NodeStatus::Maxed => (
+            Color::srgba(0.4, 0.9, 0.5, 0.6),
+            Color::srgba(0.2, 0.5, 0.25, 0.6),
+            Color::srgb(0.4, 0.9, 0.5),
+            "Learned".to_string(),
+        ),
+        NodeStatus::Locked => (
+            Color::srgba(0.4, 0.35, 0.5, 0.4),
+            Color::srgba(0.2, 0.18, 0.25, 0.5),
+            Color::srgba(0.5, 0.45, 0.6, 0.5),
+            "Locked".to_string(),
+        ),
+        NodeStatus::Excluded => (
+            Color::srgba(0.6, 0.25, 0.3, 0.5),
+            Color::srgba(0.3, 0.15, 0.18, 0.5),
+            Color::srgba(0.7, 0.45, 0.5, 0.6),
+            "Path closed".to_string(),
+        ),
+        NodeStatus::Available(cost) if transcendence.insight >= cost => (
+            Color::srgba(0.7, 0.5, 1.0, 0.9),
+            Color::srgba(0.7, 0.5, 1.0, 0.9),
+            Color::srgb(1.0, 1.0, 1.0),
+            format!("{} Insight", cost),
+        ),
+        NodeStatus::Available(cost) => (
+            Color::srgba(0.5, 0.4, 0.7, 0.5),
+            Color::srgba(0.3, 0.25, 0.4, 0.5),
+            Color::srgba(0.5, 0.45, 0.6, 0.5),
+            format!("{} Insight", cost),
+        ),
+    };
+
+    parent.spawn((
+        Node {
+            width: Val::Px(200.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(3.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            border: UiRect::all(Val::Px(1.0)),
+            border_radius: BorderRadius::all(Val::Px(4.0)),
+            ..default()
+        },
+        BorderColor::all(outline),
+        BackgroundColor(Color::srgba(0.1, 0.07, 0.18, 0.9)),
+    )).with_children(|card| {
+        card.spawn((
+            Text::new(def.name.clone()),
+            TextFont { font_size: 16.0, ..default() },
+            TextColor(Color::srgb(0.9, 0.88, 0.8)),
+        ));
+        if def.max_rank > 1 {
+            card.spawn((
+                Text::new(format!("Rank {}/{}", rank, def.max_rank)),
+                TextFont { font_size: 12.0, ..default() },
+                TextColor(Color::srgb(0.8, 0.7, 1.0)),
+            ));
+        }
+        card.spawn((
+            Text::new(def.description.clone()),
+            TextFont { font_size: 12.0, ..default() },
+            TextColor(Color::srgba(0.6, 0.55, 0.7, 0.7)),
+        ));
+        if !def.requires.is_empty() {
+            let names: Vec<&str> = def
+                .requires
+                .iter()
+                .map(|&req| content.enlightenment(req).name.as_str())
+                .collect();
+            card.spawn((
+                Text::new(format!("Needs {}", names.join(", "))),
+                TextFont { font_size: 11.0, ..default() },
+                TextColor(Color::srgba(0.6, 0.55, 0.7, 0.6)),
+            ));
+        }
+        if let Some(group) = &def.exclusive {
+            card.spawn((
+                Text::new(format!("Choose one: {}", group)),
+                TextFont { font_size: 11.0, ..default() },
+                TextColor(Color::srgba(0.8, 0.5, 0.55, 0.7)),
+            ));
+        }
+        card.spawn((
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                border_radius: BorderRadius::all(Val::Px(4.0)),
+                justify_content: JustifyContent::Center,
+                margin: UiRect::top(Val::Px(4.0)),
+                ..default()
+            },
+            BackgroundColor(btn_bg),
+            EnlightenmentBuyButton(id),
+        )).with_children(|btn| {
+            btn.spawn((
+                Text::new(label),
+                TextFont { font_size: 13.0, ..default() },
+                TextColor(btn_text_color),
+            ));
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::ascension::AscensionUpgradeId;
+    use crate::gameplay::testing::TestApp;
+
+    fn with_insight(insight: u32) -> TranscendenceState {
+        TranscendenceState {
+            insight,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn the_tree_gates_ranks_and_exclusive_choices() {
+        let content = GameContent::default();
+        let ascension = AscensionState::default();
+        let mut state = with_insight(100);
+        let status = |state: &TranscendenceState, id| state.status(id, &content, &ascension);
+
+        assert_eq!(status(&state, EnlightenmentId::HeadStart), NodeStatus::Locked);
+        assert!(!state.buy(EnlightenmentId::HeadStart, &content, &ascension));
+        assert!(state.buy(EnlightenmentId::DeepRoots, &content, &ascension));
+        assert_eq!(status(&state, EnlightenmentId::DeepRoots), NodeStatus::Available(2));
+        assert_eq!(status(&state, EnlightenmentId::HeadStart), NodeStatus::Available(3));
+
+        for _ in 1..5 {
+            assert!(state.buy(EnlightenmentId::DeepRoots, &content, &ascension));
+        }
+        assert_eq!(state.rank(EnlightenmentId::DeepRoots), 5);
+        assert_eq!(status(&state, EnlightenmentId::DeepRoots), NodeStatus::Maxed);
+        assert!((state.click_multiplier() - 1.5).abs() < 1e-6);
+
+        assert!(state.buy(EnlightenmentId::EternalFlow, &content, &ascension));
+        assert!(state.buy(EnlightenmentId::CosmicResonance, &content, &ascension));
+        assert_eq!(status(&state, EnlightenmentId::ClarityAffinity), NodeStatus::Excluded);
+        assert!(!state.buy(EnlightenmentId::ClarityAffinity, &content, &ascension));
+    }
+
+    #[test]
+    fn respec_refunds_every_rank() {
+        let content = GameContent::default();
+        let ascension = AscensionState::default();
+        let mut state = with_insight(40);
+        for id in [
+            EnlightenmentId::EternalFlow,
+            EnlightenmentId::EternalFlow,
+            EnlightenmentId::ClarityAffinity,
+            EnlightenmentId::EfficientDesign,
+        ] {
+            assert!(state.buy(id, &content, &ascension));
+        }
+        let spent = 40 - state.insight;
+        assert_eq!(spent, 2 + 4 + 8 + 4);
+        assert_eq!(state.spent, spent);
+
+        // A discount gained after learning doesn't shrink the refund
+        let mut frugal = AscensionState::default();
+        frugal
+            .purchased_upgrades
+            .push(AscensionUpgradeId::FrugalEnlightenment);
+        assert!(state.ranks_at_current_prices(&content, &frugal) < spent);
+
+        assert_eq!(state.respec(), spent);
+        assert_eq!(state.insight, 40);
+        assert_eq!(state.spent, 0);
+        assert!(state.ranks.is_empty());
+        assert_eq!(
+            state.status(EnlightenmentId::CosmicResonance, &content, &ascension),
+            NodeStatus::Locked
+        );
+    }
 
-        transcendence.insight -= cost;
-        transcendence.purchased_enlightenments.push(button.0);
+    #[test]
+    fn the_screen_redraws_after_learning_and_respec() {
+        let mut game = TestApp::new();
+        *game.resource_mut::<TranscendenceState>() = with_insight(10);
+        game.set_state(GameState::TranscendenceOpen);
+
+        game.click_button::<EnlightenmentBuyButton>(|b| b.0 == EnlightenmentId::DeepRoots);
+        assert_eq!(game.resource::<TranscendenceState>().rank(EnlightenmentId::DeepRoots), 1);
+        game.click_button::<EnlightenmentBuyButton>(|b| b.0 == EnlightenmentId::HeadStart);
+        assert_eq!(game.resource::<TranscendenceState>().insight, 6);
+
+        let world = game.app.world_mut();
+        let labels: Vec<String> = world
+            .query::<&Text>()
+            .iter(world)
+            .map(|text| text.0.clone())
+            .collect();
+        assert!(labels.iter().any(|label| label == "Rank 1/5"));
+        assert!(labels.iter().any(|label| label == "Respec (+4 Insight)"));
+
+        game.click_button::<RespecButton>(|_| true);
+        let state = game.resource::<TranscendenceState>();
+        assert_eq!(state.insight, 10);
+        assert!(!state.has(EnlightenmentId::HeadStart));
     }
 }
@@ -1,20 +1,22 @@
+use super::bignum::BigNum;
 use super::challenges::ChallengeState;
+use super::economy;
 use super::schools::SchoolState;
 use super::shop::PurchaseTracker;
 use bevy::prelude::*;
 
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Clone)]
 pub struct WisdomMeter {
-    pub current: f32,
-    pub max_wisdom: f32,
+    pub current: BigNum,
+    pub max_wisdom: BigNum,
     pub truths_generated: u32,
 }
 
 impl Default for WisdomMeter {
     fn default() -> Self {
         Self {
-            current: 0.0,
-            max_wisdom: 10.0,
+            current: BigNum::ZERO,
+            max_wisdom: BigNum::from(10.0),
             truths_generated: 0,
         }
     }
@@ -26,6 +28,13 @@ pub struct TruthGenerated {
     pub truth_index: usize,
 }
 
+impl TruthGenerated {
+    /// True for truths that emptied the meter; dream truths arrive on their own
+    pub fn is_meter_truth(&self) -> bool {
+        self.truth_index < DEEP_TRUTHS.len()
+    }
+}
+
 pub const DEEP_TRUTHS: &[&str] = &[
     // Original truths
     "The orb knows what the clock forgets.",
@@ -346,19 +355,11 @@ pub fn check_truth_generation(
     challenges: Res<ChallengeState>,
 ) {
     if wisdom.current >= wisdom.max_wisdom {
-        wisdom.current = 0.0;
-        wisdom.truths_generated += 1;
-
-        let index = (wisdom.truths_generated as usize - 1) % DEEP_TRUTHS.len();
+        let scaling = economy::truth_scaling(&tracker, &school, &challenges);
+        let index = economy::complete_truth(&mut wisdom, scaling);
         truth_messages.write(TruthGenerated {
             text: DEEP_TRUTHS[index].to_string(),
             truth_index: index,
         });
-
-        let scaling = challenges
-            .active_scaling_override()
-            .or(school.scaling_override())
-            .unwrap_or(tracker.scaling_factor);
-        wisdom.max_wisdom *= scaling;
     }
 }
@@ -0,0 +1,6 @@
+pub mod audio;
+pub mod environment;
+pub mod familiars;
+pub mod gameplay;
+pub mod orb;
+pub mod ui;
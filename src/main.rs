@@ -1,11 +1,5 @@
 use bevy::prelude::*;
-
-mod audio;
-mod environment;
-mod familiars;
-mod gameplay;
-mod orb;
-mod ui;
+use orb_pondering_simulator::{audio, environment, familiars, gameplay, orb, ui};
 
 fn main() {
     App::new()
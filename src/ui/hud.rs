@@ -1,10 +1,13 @@
 use crate::environment::daynight::DayNightCycle;
 use crate::gameplay::{
-    acolytes::AcolyteState, generators::GeneratorState, layers::LayerState,
-    pondering::PonderState, progression::ArcaneProgress, resources::SecondaryResources,
-    shop::PurchaseTracker, synergies::SynergyState, wisdom::WisdomMeter,
+    acolytes::AcolyteState, advisor, bignum::BigNum, content::GameContent, economy,
+    generators::GeneratorState, layers::LayerState, modifiers::WisdomModifiers,
+    pondering::PonderState,
+    progression::ArcaneProgress, resources::SecondaryResources, simulation::format_duration,
+    synergies::SynergyState, wisdom::WisdomMeter,
 };
 use bevy::prelude::*;
+use bevy::tasks::{futures::check_ready, AsyncComputeTaskPool, Task};
 
 #[derive(Component)]
 pub struct WisdomText;
@@ -42,6 +45,9 @@ pub struct FocusText;
 #[derive(Component)]
 pub struct LayerIndicatorText;
 
+#[derive(Component)]
+pub struct AdvisorText;
+
 pub fn setup_hud(mut commands: Commands) {
     // Root
     commands.spawn(Node {
@@ -162,6 +168,13 @@ pub fn setup_hud(mut commands: Commands) {
                     TextColor(Color::srgb(0.4, 0.6, 1.0)),
                     LayerIndicatorText,
                 ));
+
+                right.spawn((
+                    Text::new(""),
+                    TextFont { font_size: 13.0, ..default() },
+                    TextColor(Color::srgb(0.8, 0.8, 0.6)),
+                    AdvisorText,
+                ));
             });
         });
 
@@ -173,7 +186,7 @@ pub fn setup_hud(mut commands: Commands) {
             ..default()
         }).with_children(|bottom| {
             bottom.spawn((
-                Text::new("[Click] Ponder | [SPACE] Deep Focus | [G] Focus | [A] Summon | [D] Dispel | [F] Pet | [B] Shop | [L] Logbook | [T] Transcend | [V] Achievements | [C] Challenges | [X] Codex"),
+                Text::new("[Click] Ponder | [SPACE] Deep Focus | [G] Focus | [A] Summon | [D] Dispel | [F] Pet | [B] Shop | [L] Logbook | [T] Transcend | [N] Ascend | [V] Achievements | [C] Challenges | [X] Codex"),
                 TextFont { font_size: 14.0, ..default() },
                 TextColor(Color::srgba(0.6, 0.6, 0.7, 0.6)),
                 PonderHint,
@@ -187,7 +200,7 @@ pub fn update_wisdom_display(
     mut text_query: Query<&mut Text, With<WisdomText>>,
     mut bar_query: Query<&mut Node, With<WisdomBar>>,
 ) {
-    let pct = (wisdom.current / wisdom.max_wisdom * 100.0).min(100.0);
+    let pct = ((wisdom.current / wisdom.max_wisdom).to_f64() * 100.0).min(100.0) as f32;
     for mut text in &mut text_query {
         **text = format!("Wisdom: {:.0}%", pct);
     }
@@ -208,44 +221,36 @@ pub fn update_afp_display(
 pub fn update_generator_display(
     generators: Res<GeneratorState>,
     synergies: Res<SynergyState>,
-    tracker: Res<PurchaseTracker>,
+    modifiers: Res<WisdomModifiers>,
+    content: Res<GameContent>,
     mut text_query: Query<&mut Text, With<GeneratorText>>,
 ) {
-    let base = synergies.total_synergized_production(&generators);
-    if base <= 0.0 {
+    let rate = economy::generator_rate(&generators, &synergies, &modifiers, &content);
+    if !rate.is_positive() {
         for mut text in &mut text_query {
             **text = String::new();
         }
         return;
     }
-    let rate = base * (1.0 + tracker.efficiency_bonus as f64) * tracker.wisdom_speed_bonus as f64;
     for mut text in &mut text_query {
         **text = format!("Generators: +{:.1}/s", rate);
     }
 }
 
-fn format_afp(value: u64) -> String {
-    if value >= 1_000_000_000 {
-        format!("{:.1}B", value as f64 / 1_000_000_000.0)
-    } else if value >= 1_000_000 {
-        format!("{:.1}M", value as f64 / 1_000_000.0)
-    } else if value >= 10_000 {
-        format!("{:.1}K", value as f64 / 1_000.0)
-    } else {
-        format!("{}", value)
-    }
+fn format_afp(value: BigNum) -> String {
+    value.format_short()
 }
 
 pub fn update_acolyte_display(
     acolytes: Res<AcolyteState>,
-    tracker: Res<PurchaseTracker>,
+    modifiers: Res<WisdomModifiers>,
     mut acolyte_text: Query<&mut Text, With<AcolyteText>>,
     mut cost_text: Query<&mut Text, (With<SummonCostText>, Without<AcolyteText>)>,
     mut cost_color: Query<&mut TextColor, With<SummonCostText>>,
     progress: Res<ArcaneProgress>,
+    content: Res<GameContent>,
 ) {
-    let rate =
-        acolytes.passive_rate() * (1.0 + tracker.efficiency_bonus) * tracker.wisdom_speed_bonus;
+    let rate = economy::acolyte_rate(&acolytes, &modifiers, &content.balance);
 
     for mut text in &mut acolyte_text {
         if acolytes.count > 0 {
@@ -255,9 +260,9 @@ pub fn update_acolyte_display(
         }
     }
 
-    let cost = acolytes.next_cost();
+    let cost = acolytes.next_cost(&content.balance);
     for mut text in &mut cost_text {
-        **text = format!("[A] Summon ({} AFP)", cost);
+        **text = format!("[A] Summon ({} AFP)", format_afp(cost));
     }
 
     let can_afford = progress.focus_points >= cost;
@@ -326,18 +331,34 @@ pub fn update_deep_focus_display(
 pub fn update_layer_display(
     layers: Res<LayerState>,
     cycle: Res<DayNightCycle>,
+    content: Res<GameContent>,
     mut text_query: Query<&mut Text, With<LayerIndicatorText>>,
     mut color_query: Query<&mut TextColor, With<LayerIndicatorText>>,
 ) {
     let highest = layers.highest_unlocked();
+    let name = &content.layer(highest).name;
     let dream_mult = layers.dream_multiplier(&cycle);
     let has_dream = layers.has(crate::gameplay::layers::ContentLayer::Dream);
+    let has_void = layers.has(crate::gameplay::layers::ContentLayer::Void);
+
+    let mut details = Vec::new();
+    if has_dream && dream_mult > 1.01 {
+        details.push(format!("+{:.0}% dream", (dream_mult - 1.0) * 100.0));
+    }
+    if has_void {
+        details.push(format!(
+            "{} essence, +{:.0}% passive, void in {}",
+            layers.void_essence,
+            (layers.void_multiplier() - 1.0) * 100.0,
+            layers.truths_until_void()
+        ));
+    }
 
     for mut text in &mut text_query {
-        if has_dream && dream_mult > 1.01 {
-            **text = format!("{} (+{:.0}% dream)", highest.name(), (dream_mult - 1.0) * 100.0);
+        if details.is_empty() {
+            **text = name.clone();
         } else {
-            **text = highest.name().to_string();
+            **text = format!("{} ({})", name, details.join(", "));
         }
     }
 
@@ -345,3 +366,51 @@ pub fn update_layer_display(
         color.0 = highest.color();
     }
 }
+
+/// Seconds between advisor refreshes; each one plays every option forward
+const ADVISOR_REFRESH_SECS: f32 = 2.0;
+
+/// Names the purchase that pays for itself soonest. The appraisal runs on the
+/// async compute pool from a snapshot of the economy, and the label keeps its
+/// last result until the next one lands.
+pub fn update_advisor_display(
+    live: economy::EconomyResources,
+    time: Res<Time>,
+    mut until_refresh: Local<f32>,
+    mut pending: Local<Option<Task<String>>>,
+    mut text_query: Query<&mut Text, With<AdvisorText>>,
+) {
+    if let Some(task) = pending.as_mut() {
+        if let Some(label) = check_ready(task) {
+            *pending = None;
+            for mut text in &mut text_query {
+                if **text != label {
+                    **text = label.clone();
+                }
+            }
+        }
+    }
+
+    *until_refresh -= time.delta_secs();
+    if *until_refresh > 0.0 || pending.is_some() {
+        return;
+    }
+    *until_refresh = ADVISOR_REFRESH_SECS;
+
+    let model = economy::EconomyModel::capture(&live);
+    *pending = Some(AsyncComputeTaskPool::get().spawn(async move { advisor_label(&model) }));
+}
+
+fn advisor_label(model: &economy::EconomyModel) -> String {
+    let appraisals = advisor::appraise(model, &advisor::AdvisorConfig::default());
+    let best = appraisals.iter().find_map(|a| a.payback_secs.map(|secs| (a, secs)));
+    match best {
+        Some((appraisal, secs)) => format!(
+            "{}: {} (pays back in {})",
+            if appraisal.affordable { "Best buy" } else { "Save for" },
+            appraisal.action.name(&model.content),
+            format_duration(secs)
+        ),
+        None => String::new(),
+    }
+}
@@ -1,33 +1,9 @@
-use crate::gameplay::{state::GameState, wisdom::TruthGenerated};
+use crate::gameplay::{logbook::Logbook, state::GameState};
 use bevy::prelude::*;
 
-#[derive(Debug, Clone)]
-pub struct LogbookEntry {
-    pub text: String,
-    pub truth_number: u32,
-}
-
-#[derive(Resource, Default)]
-pub struct Logbook {
-    pub entries: Vec<LogbookEntry>,
-}
-
 #[derive(Component)]
 pub struct LogbookPanel;
 
-pub fn record_truths(
-    mut logbook: ResMut<Logbook>,
-    mut truth_events: MessageReader<TruthGenerated>,
-) {
-    for event in truth_events.read() {
-        let num = logbook.entries.len() as u32 + 1;
-        logbook.entries.push(LogbookEntry {
-            text: event.text.clone(),
-            truth_number: num,
-        });
-    }
-}
-
 pub fn toggle_logbook(
     keys: Res<ButtonInput<KeyCode>>,
     current_state: Res<State<GameState>>,
@@ -9,8 +9,7 @@ pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<logbook::Logbook>()
-            .add_systems(Startup, hud::setup_hud)
+        app.add_systems(Startup, hud::setup_hud)
             .add_systems(
                 Update,
                 (
@@ -21,9 +20,9 @@ impl Plugin for UiPlugin {
                     hud::update_deep_focus_display,
                     hud::update_secondary_display,
                     hud::update_layer_display,
+                    hud::update_advisor_display,
                     truth_display::show_truth_popup,
                     truth_display::animate_truth_popup,
-                    logbook::record_truths,
                 ),
             )
             .add_systems(Update, logbook::toggle_logbook)